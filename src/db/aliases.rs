@@ -0,0 +1,241 @@
+//! Show alias database operations
+//!
+//! Release groups title the same show differently, so besides `shows.alternate`
+//! each show can have any number of aliases. Aliases are seeded from AniList
+//! (romaji, english and synonyms) and from confirmed matches, and can be edited in
+//! the configure modal. The tracker searches and matches releases against all of them.
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use super::models::Show;
+
+/// Where an alias came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AliasOrigin {
+    /// Romaji, english or synonym title from AniList
+    Anilist,
+    /// Release title picked in the match selection (or auto-accepted)
+    Match,
+    /// Added by hand in the configure modal
+    Manual,
+    /// Fallback search title that found releases passing the show's filters
+    Learned,
+}
+
+impl AliasOrigin {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AliasOrigin::Anilist => "anilist",
+            AliasOrigin::Match => "match",
+            AliasOrigin::Manual => "manual",
+            AliasOrigin::Learned => "learned",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "anilist" => Some(AliasOrigin::Anilist),
+            "match" => Some(AliasOrigin::Match),
+            "manual" => Some(AliasOrigin::Manual),
+            "learned" => Some(AliasOrigin::Learned),
+            _ => None,
+        }
+    }
+}
+
+/// An alternate title of a show
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShowAlias {
+    pub id: u32,
+    pub show_id: u32,
+    pub alias: String,
+    pub origin: AliasOrigin,
+}
+
+/// Get all aliases of a show, in the order they were added
+pub fn get_show_aliases(conn: &Connection, show_id: u32) -> Result<Vec<ShowAlias>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, show_id, alias, origin
+             FROM show_aliases
+             WHERE show_id = ?1
+             ORDER BY id",
+        )
+        .context("Failed to prepare get_show_aliases query")?;
+
+    let aliases = stmt
+        .query_map([show_id], |row| {
+            Ok(ShowAlias {
+                id: row.get(0)?,
+                show_id: row.get(1)?,
+                alias: row.get(2)?,
+                origin: AliasOrigin::parse(&row.get::<_, String>(3)?).unwrap_or(AliasOrigin::Manual),
+            })
+        })
+        .context("Failed to execute get_show_aliases query")?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .context("Failed to collect show aliases")?;
+
+    Ok(aliases)
+}
+
+/// Get a single alias by its ID
+pub fn get_show_alias(conn: &Connection, id: u32) -> Result<Option<ShowAlias>> {
+    conn.query_row(
+        "SELECT id, show_id, alias, origin FROM show_aliases WHERE id = ?1",
+        [id],
+        |row| {
+            Ok(ShowAlias {
+                id: row.get(0)?,
+                show_id: row.get(1)?,
+                alias: row.get(2)?,
+                origin: AliasOrigin::parse(&row.get::<_, String>(3)?).unwrap_or(AliasOrigin::Manual),
+            })
+        },
+    )
+    .optional()
+    .context("Failed to get show alias")
+}
+
+/// Add an alias to a show
+///
+/// Aliases are unique per show, ignoring case. Returns the new alias ID, or None
+/// if the alias is blank or the show already has it.
+pub fn add_show_alias(
+    conn: &Connection,
+    show_id: u32,
+    alias: &str,
+    origin: AliasOrigin,
+) -> Result<Option<u32>> {
+    let alias = alias.trim();
+    if alias.is_empty() {
+        return Ok(None);
+    }
+
+    let inserted = conn
+        .execute(
+            "INSERT OR IGNORE INTO show_aliases (show_id, alias, origin) VALUES (?1, ?2, ?3)",
+            params![show_id, alias, origin.as_str()],
+        )
+        .context("Failed to insert show alias")?;
+
+    Ok((inserted > 0).then(|| conn.last_insert_rowid() as u32))
+}
+
+/// Delete an alias
+pub fn delete_show_alias(conn: &Connection, show_id: u32, id: u32) -> Result<bool> {
+    let rows_affected = conn
+        .execute(
+            "DELETE FROM show_aliases WHERE id = ?1 AND show_id = ?2",
+            [id, show_id],
+        )
+        .context("Failed to delete show alias")?;
+
+    Ok(rows_affected > 0)
+}
+
+/// Every title to search for a show: its alternate (or title) first, then its aliases
+///
+/// Duplicates are dropped, ignoring case.
+pub fn get_search_titles(conn: &Connection, show: &Show) -> Result<Vec<String>> {
+    let primary = if show.alternate.is_empty() {
+        &show.title
+    } else {
+        &show.alternate
+    };
+
+    let mut titles = vec![primary.clone()];
+    for alias in get_show_aliases(conn, show.id)? {
+        if !titles.iter().any(|t| t.eq_ignore_ascii_case(&alias.alias)) {
+            titles.push(alias.alias);
+        }
+    }
+
+    Ok(titles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::schema::init_database;
+    use crate::db::shows::{delete_show, insert_show};
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+        init_database(&conn).unwrap();
+        insert_show(
+            &conn,
+            &Show {
+                id: 1,
+                title: "Sousou no Frieren".to_string(),
+                alternate: "Frieren".to_string(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_add_and_list_aliases() {
+        let conn = setup_test_db();
+
+        let id = add_show_alias(&conn, 1, " Frieren: Beyond Journey's End ", AliasOrigin::Anilist)
+            .unwrap()
+            .unwrap();
+        add_show_alias(&conn, 1, "Sousou no Frieren", AliasOrigin::Match).unwrap();
+
+        let aliases = get_show_aliases(&conn, 1).unwrap();
+        assert_eq!(aliases.len(), 2);
+        assert_eq!(aliases[0].alias, "Frieren: Beyond Journey's End");
+        assert_eq!(aliases[0].origin, AliasOrigin::Anilist);
+        assert_eq!(aliases[1].origin, AliasOrigin::Match);
+
+        assert_eq!(get_show_alias(&conn, id).unwrap().unwrap().show_id, 1);
+    }
+
+    #[test]
+    fn test_duplicate_and_blank_aliases_ignored() {
+        let conn = setup_test_db();
+
+        assert!(add_show_alias(&conn, 1, "Frieren", AliasOrigin::Manual).unwrap().is_some());
+        assert!(add_show_alias(&conn, 1, "FRIEREN", AliasOrigin::Anilist).unwrap().is_none());
+        assert!(add_show_alias(&conn, 1, "   ", AliasOrigin::Manual).unwrap().is_none());
+        assert_eq!(get_show_aliases(&conn, 1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_search_titles_put_alternate_first() {
+        let conn = setup_test_db();
+
+        add_show_alias(&conn, 1, "frieren", AliasOrigin::Anilist).unwrap();
+        add_show_alias(&conn, 1, "Sousou no Frieren", AliasOrigin::Anilist).unwrap();
+
+        let show = crate::db::get_show(&conn, 1).unwrap().unwrap();
+        assert_eq!(
+            get_search_titles(&conn, &show).unwrap(),
+            vec!["Frieren", "Sousou no Frieren"]
+        );
+    }
+
+    #[test]
+    fn test_aliases_deleted_with_show() {
+        let conn = setup_test_db();
+
+        let id = add_show_alias(&conn, 1, "Frieren", AliasOrigin::Manual)
+            .unwrap()
+            .unwrap();
+        // Another show's ID doesn't reach this show's alias
+        assert!(!delete_show_alias(&conn, 2, id).unwrap());
+        assert!(delete_show_alias(&conn, 1, id).unwrap());
+        assert!(!delete_show_alias(&conn, 1, id).unwrap());
+
+        add_show_alias(&conn, 1, "Frieren", AliasOrigin::Manual).unwrap();
+        delete_show(&conn, 1).unwrap();
+        assert!(get_show_aliases(&conn, 1).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,100 @@
+//! Cached AniList query results
+//!
+//! Query results are stored as the raw JSON AniList returned, keyed by query (e.g.
+//! `seasonal:FALL:2023`). An entry past its expiry is still kept, so it can be served
+//! when AniList is unreachable.
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// A stored AniList query result
+#[derive(Debug, Clone)]
+pub struct CachedQuery {
+    /// Raw JSON of the result
+    pub body: String,
+    /// When the result was fetched (UTC, `YYYY-MM-DD HH:MM:SS`)
+    pub fetched_at: String,
+    /// Whether the entry is still within its TTL
+    pub fresh: bool,
+}
+
+/// Get the cached result of a query, fresh or not
+pub fn get_cached_query(conn: &Connection, key: &str) -> Result<Option<CachedQuery>> {
+    conn.query_row(
+        "SELECT body, fetched_at, expires_at IS NULL OR expires_at > datetime('now')
+         FROM anilist_cache
+         WHERE key = ?1",
+        [key],
+        |row| {
+            Ok(CachedQuery {
+                body: row.get(0)?,
+                fetched_at: row.get(1)?,
+                fresh: row.get::<_, i32>(2)? != 0,
+            })
+        },
+    )
+    .optional()
+    .context("Failed to get cached AniList query")
+}
+
+/// Store the result of a query, replacing any earlier one
+///
+/// `ttl_secs` of None keeps the entry fresh forever.
+pub fn store_cached_query(
+    conn: &Connection,
+    key: &str,
+    body: &str,
+    ttl_secs: Option<i64>,
+) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO anilist_cache (key, body, fetched_at, expires_at)
+         VALUES (?1, ?2, datetime('now'),
+                 CASE WHEN ?3 IS NULL THEN NULL ELSE datetime('now', ?3 || ' seconds') END)",
+        params![key, body, ttl_secs],
+    )
+    .context("Failed to store cached AniList query")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::schema::init_database;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_store_and_get() {
+        let conn = setup_test_db();
+
+        assert!(get_cached_query(&conn, "seasonal:FALL:2023").unwrap().is_none());
+
+        store_cached_query(&conn, "seasonal:FALL:2023", "[]", Some(3600)).unwrap();
+        let cached = get_cached_query(&conn, "seasonal:FALL:2023").unwrap().unwrap();
+        assert_eq!(cached.body, "[]");
+        assert!(cached.fresh);
+
+        // Storing again replaces the entry
+        store_cached_query(&conn, "seasonal:FALL:2023", "[{}]", Some(3600)).unwrap();
+        let cached = get_cached_query(&conn, "seasonal:FALL:2023").unwrap().unwrap();
+        assert_eq!(cached.body, "[{}]");
+    }
+
+    #[test]
+    fn test_expiry() {
+        let conn = setup_test_db();
+
+        store_cached_query(&conn, "airing", "[]", Some(-60)).unwrap();
+        let cached = get_cached_query(&conn, "airing").unwrap().unwrap();
+        assert!(!cached.fresh);
+
+        // No TTL never expires
+        store_cached_query(&conn, "seasonal:FALL:2000", "[]", None).unwrap();
+        assert!(get_cached_query(&conn, "seasonal:FALL:2000").unwrap().unwrap().fresh);
+    }
+}
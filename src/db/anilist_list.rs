@@ -0,0 +1,82 @@
+//! Shows tracked because of the AniList watchlist
+//!
+//! Only shows recorded here are untracked when they leave the watchlist, so shows
+//! tracked by hand are never touched by a list sync. Shows that left the list keep
+//! their record as [`DROPPED`], so they're tracked again if they come back.
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+
+/// Status of a show the list sync untracked because it left the watchlist
+pub const DROPPED: &str = "DROPPED";
+
+/// Get the IDs and list statuses of all shows imported from the watchlist
+pub fn get_list_shows(conn: &Connection) -> Result<Vec<(u32, String)>> {
+    let mut stmt = conn
+        .prepare("SELECT show_id, list_status FROM anilist_list_shows ORDER BY show_id")
+        .context("Failed to prepare get_list_shows query")?;
+
+    let shows = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .context("Failed to execute get_list_shows query")?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .context("Failed to collect list shows")?;
+
+    Ok(shows)
+}
+
+/// Record that a show is on the watchlist with the given status (CURRENT, PLANNING)
+pub fn mark_list_show(conn: &Connection, show_id: u32, status: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO anilist_list_shows (show_id, list_status) VALUES (?1, ?2)
+         ON CONFLICT(show_id) DO UPDATE SET list_status = ?2, synced_at = datetime('now')",
+        params![show_id, status],
+    )
+    .context("Failed to mark list show")?;
+
+    Ok(())
+}
+
+/// Record that a show left the watchlist
+pub fn drop_list_show(conn: &Connection, show_id: u32) -> Result<()> {
+    mark_list_show(conn, show_id, DROPPED)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{insert_show, models::Show, schema::init_database};
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        for id in [1, 2] {
+            let show = Show {
+                id,
+                title: format!("Show {}", id),
+                ..Default::default()
+            };
+            insert_show(&conn, &show).unwrap();
+        }
+        conn
+    }
+
+    #[test]
+    fn test_mark_and_drop_list_shows() {
+        let conn = setup_test_db();
+
+        mark_list_show(&conn, 1, "CURRENT").unwrap();
+        mark_list_show(&conn, 2, "PLANNING").unwrap();
+        mark_list_show(&conn, 2, "CURRENT").unwrap();
+        assert_eq!(
+            get_list_shows(&conn).unwrap(),
+            vec![(1, "CURRENT".to_string()), (2, "CURRENT".to_string())]
+        );
+
+        drop_list_show(&conn, 1).unwrap();
+        assert_eq!(
+            get_list_shows(&conn).unwrap(),
+            vec![(1, DROPPED.to_string()), (2, "CURRENT".to_string())]
+        );
+    }
+}
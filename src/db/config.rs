@@ -1,13 +1,17 @@
 use anyhow::{Context, Result};
 use rusqlite::{params, Connection};
 
-use super::models::RssConfig;
+use super::{models::RssConfig, MetadataSource};
 
 /// Get the RSS configuration (there's only one row with id=1)
 pub fn get_rss_config(conn: &Connection) -> Result<RssConfig> {
     let config = conn
         .query_row(
-            "SELECT id, poll_times_per_day, last_poll_time, enabled
+            "SELECT id, poll_times_per_day, last_poll_time, enabled, match_confidence_threshold,
+                    default_nyaa_category, default_language, anilist_username, anilist_list_sync,
+                    anilist_client_id, anilist_token, anilist_push_progress, auto_track_sequels,
+                    metadata_provider, airing_aware_polling, airing_poll_minutes,
+                    max_shows_per_poll, quiet_hours_start, quiet_hours_end
              FROM rss_config
              WHERE id = 1",
             [],
@@ -17,6 +21,22 @@ pub fn get_rss_config(conn: &Connection) -> Result<RssConfig> {
                     poll_times_per_day: row.get::<_, i32>(1)? as u8,
                     last_poll_time: row.get(2)?,
                     enabled: row.get::<_, i32>(3)? != 0,
+                    match_confidence_threshold: row.get(4)?,
+                    default_nyaa_category: row.get(5)?,
+                    default_language: row.get(6)?,
+                    anilist_username: row.get(7)?,
+                    anilist_list_sync: row.get::<_, i32>(8)? != 0,
+                    anilist_client_id: row.get(9)?,
+                    anilist_token: row.get(10)?,
+                    anilist_push_progress: row.get::<_, i32>(11)? != 0,
+                    auto_track_sequels: row.get::<_, i32>(12)? != 0,
+                    metadata_provider: MetadataSource::parse(&row.get::<_, String>(13)?)
+                        .unwrap_or(MetadataSource::AniList),
+                    airing_aware_polling: row.get::<_, i32>(14)? != 0,
+                    airing_poll_minutes: row.get(15)?,
+                    max_shows_per_poll: row.get(16)?,
+                    quiet_hours_start: row.get(17)?,
+                    quiet_hours_end: row.get(18)?,
                 })
             },
         )
@@ -36,6 +56,136 @@ pub fn update_poll_interval(conn: &Connection, times_per_day: u8) -> Result<()>
     Ok(())
 }
 
+/// Update the minimum similarity for auto-accepting a title match
+///
+/// The value is clamped to 0.0-1.0.
+pub fn update_match_threshold(conn: &Connection, threshold: f64) -> Result<()> {
+    conn.execute(
+        "UPDATE rss_config SET match_confidence_threshold = ?1 WHERE id = 1",
+        params![threshold.clamp(0.0, 1.0)],
+    )
+    .context("Failed to update match confidence threshold")?;
+
+    Ok(())
+}
+
+/// Update the Nyaa category and subtitle language used for shows without their own
+///
+/// A blank language clears the preference.
+pub fn update_nyaa_defaults(conn: &Connection, category: &str, language: Option<&str>) -> Result<()> {
+    let language = language.map(str::trim).filter(|l| !l.is_empty());
+    conn.execute(
+        "UPDATE rss_config SET default_nyaa_category = ?1, default_language = ?2 WHERE id = 1",
+        params![category.trim(), language],
+    )
+    .context("Failed to update Nyaa defaults")?;
+
+    Ok(())
+}
+
+/// Update the AniList account whose watchlist is imported
+///
+/// A blank username clears it.
+pub fn update_anilist_list(conn: &Connection, username: Option<&str>, sync: bool) -> Result<()> {
+    let username = username.map(str::trim).filter(|u| !u.is_empty());
+    conn.execute(
+        "UPDATE rss_config SET anilist_username = ?1, anilist_list_sync = ?2 WHERE id = 1",
+        params![username, sync as i32],
+    )
+    .context("Failed to update AniList list settings")?;
+
+    Ok(())
+}
+
+/// Update the AniList OAuth client, access token and progress push setting
+///
+/// A blank client ID clears it; a blank token keeps the stored one.
+pub fn update_anilist_account(
+    conn: &Connection,
+    client_id: Option<&str>,
+    token: Option<&str>,
+    push_progress: bool,
+) -> Result<()> {
+    let client_id = client_id.map(str::trim).filter(|c| !c.is_empty());
+    let token = token.map(str::trim).filter(|t| !t.is_empty());
+    conn.execute(
+        "UPDATE rss_config SET
+            anilist_client_id = ?1,
+            anilist_token = COALESCE(?2, anilist_token),
+            anilist_push_progress = ?3
+         WHERE id = 1",
+        params![client_id, token, push_progress as i32],
+    )
+    .context("Failed to update AniList account")?;
+
+    Ok(())
+}
+
+/// Enable or disable tracking sequels as soon as they're found
+pub fn set_auto_track_sequels(conn: &Connection, enabled: bool) -> Result<()> {
+    conn.execute(
+        "UPDATE rss_config SET auto_track_sequels = ?1 WHERE id = 1",
+        params![enabled as i32],
+    )
+    .context("Failed to set sequel auto-tracking")?;
+
+    Ok(())
+}
+
+/// Choose the provider for the seasonal and airing grid
+pub fn set_metadata_provider(conn: &Connection, source: MetadataSource) -> Result<()> {
+    conn.execute(
+        "UPDATE rss_config SET metadata_provider = ?1 WHERE id = 1",
+        params![source.as_str()],
+    )
+    .context("Failed to set metadata provider")?;
+
+    Ok(())
+}
+
+/// Update airing-aware polling: whether it's on, how often shows are searched
+/// right after an episode airs, and how many shows one poll searches at most
+///
+/// The poll interval is at least 5 minutes and at least one show is searched.
+pub fn update_airing_polling(
+    conn: &Connection,
+    enabled: bool,
+    poll_minutes: u16,
+    max_shows: u16,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE rss_config SET
+            airing_aware_polling = ?1,
+            airing_poll_minutes = ?2,
+            max_shows_per_poll = ?3
+         WHERE id = 1",
+        params![enabled as i32, poll_minutes.max(5), max_shows.max(1)],
+    )
+    .context("Failed to update airing-aware polling")?;
+
+    Ok(())
+}
+
+/// Update the daily quiet hours, as "HH:MM" local times
+///
+/// Quiet hours are only kept when both ends are given.
+pub fn update_quiet_hours(conn: &Connection, start: Option<&str>, end: Option<&str>) -> Result<()> {
+    let (start, end) = match (
+        start.map(str::trim).filter(|s| !s.is_empty()),
+        end.map(str::trim).filter(|e| !e.is_empty()),
+    ) {
+        (Some(start), Some(end)) => (Some(start), Some(end)),
+        _ => (None, None),
+    };
+    conn.execute(
+        "UPDATE rss_config SET quiet_hours_start = ?1, quiet_hours_end = ?2 WHERE id = 1",
+        params![start, end],
+    )
+    .context("Failed to update quiet hours")?;
+
+    Ok(())
+}
+
 /// Update the last poll time to the current time
 pub fn update_last_poll_time(conn: &Connection) -> Result<()> {
     conn.execute(
@@ -78,6 +228,120 @@ mod tests {
         assert_eq!(config.poll_times_per_day, 4);
         assert!(config.enabled);
         assert!(config.last_poll_time.is_none());
+        assert_eq!(config.match_confidence_threshold, 0.9);
+        assert_eq!(config.default_nyaa_category, "1_2");
+        assert!(config.default_language.is_none());
+        assert!(config.anilist_username.is_none());
+        assert!(!config.anilist_list_sync);
+        assert!(!config.auto_track_sequels);
+        assert_eq!(config.metadata_provider, MetadataSource::AniList);
+        assert!(config.airing_aware_polling);
+        assert_eq!(config.airing_poll_minutes, 15);
+        assert_eq!(config.max_shows_per_poll, 10);
+        assert!(config.quiet_hours_start.is_none());
+        assert!(config.quiet_hours_end.is_none());
+    }
+
+    #[test]
+    fn test_update_quiet_hours() {
+        let conn = setup_test_db();
+
+        update_quiet_hours(&conn, Some("23:00"), Some(" 07:30 ")).unwrap();
+        let config = get_rss_config(&conn).unwrap();
+        assert_eq!(config.quiet_hours_start.as_deref(), Some("23:00"));
+        assert_eq!(config.quiet_hours_end.as_deref(), Some("07:30"));
+
+        // Half a window clears them
+        update_quiet_hours(&conn, Some("23:00"), Some("")).unwrap();
+        let config = get_rss_config(&conn).unwrap();
+        assert!(config.quiet_hours_start.is_none());
+        assert!(config.quiet_hours_end.is_none());
+    }
+
+    #[test]
+    fn test_update_airing_polling() {
+        let conn = setup_test_db();
+
+        update_airing_polling(&conn, false, 30, 5).unwrap();
+        let config = get_rss_config(&conn).unwrap();
+        assert!(!config.airing_aware_polling);
+        assert_eq!(config.airing_poll_minutes, 30);
+        assert_eq!(config.max_shows_per_poll, 5);
+
+        // Too-frequent polling and empty polls are clamped
+        update_airing_polling(&conn, true, 1, 0).unwrap();
+        let config = get_rss_config(&conn).unwrap();
+        assert_eq!(config.airing_poll_minutes, 5);
+        assert_eq!(config.max_shows_per_poll, 1);
+    }
+
+    #[test]
+    fn test_set_metadata_provider() {
+        let conn = setup_test_db();
+
+        set_metadata_provider(&conn, MetadataSource::Kitsu).unwrap();
+        let config = get_rss_config(&conn).unwrap();
+        assert_eq!(config.metadata_provider, MetadataSource::Kitsu);
+    }
+
+    #[test]
+    fn test_update_anilist_list() {
+        let conn = setup_test_db();
+
+        update_anilist_list(&conn, Some(" someone "), true).unwrap();
+        let config = get_rss_config(&conn).unwrap();
+        assert_eq!(config.anilist_username.as_deref(), Some("someone"));
+        assert!(config.anilist_list_sync);
+
+        update_anilist_list(&conn, Some(""), false).unwrap();
+        let config = get_rss_config(&conn).unwrap();
+        assert!(config.anilist_username.is_none());
+        assert!(!config.anilist_list_sync);
+    }
+
+    #[test]
+    fn test_update_anilist_account() {
+        let conn = setup_test_db();
+
+        update_anilist_account(&conn, Some("1234"), Some("token"), true).unwrap();
+        let config = get_rss_config(&conn).unwrap();
+        assert_eq!(config.anilist_client_id.as_deref(), Some("1234"));
+        assert_eq!(config.anilist_token.as_deref(), Some("token"));
+        assert!(config.anilist_push_progress);
+
+        // A blank token keeps the stored one
+        update_anilist_account(&conn, Some("1234"), Some(" "), false).unwrap();
+        let config = get_rss_config(&conn).unwrap();
+        assert_eq!(config.anilist_token.as_deref(), Some("token"));
+        assert!(!config.anilist_push_progress);
+    }
+
+    #[test]
+    fn test_update_nyaa_defaults() {
+        let conn = setup_test_db();
+
+        update_nyaa_defaults(&conn, "1_3", Some("es")).unwrap();
+        let config = get_rss_config(&conn).unwrap();
+        assert_eq!(config.default_nyaa_category, "1_3");
+        assert_eq!(config.default_language.as_deref(), Some("es"));
+
+        update_nyaa_defaults(&conn, "1_2", Some(" ")).unwrap();
+        let config = get_rss_config(&conn).unwrap();
+        assert!(config.default_language.is_none());
+    }
+
+    #[test]
+    fn test_update_match_threshold() {
+        let conn = setup_test_db();
+
+        update_match_threshold(&conn, 0.75).unwrap();
+        let config = get_rss_config(&conn).unwrap();
+        assert_eq!(config.match_confidence_threshold, 0.75);
+
+        // Out-of-range values are clamped
+        update_match_threshold(&conn, 1.5).unwrap();
+        let config = get_rss_config(&conn).unwrap();
+        assert_eq!(config.match_confidence_threshold, 1.0);
     }
 
     #[test]
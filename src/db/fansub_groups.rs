@@ -0,0 +1,252 @@
+//! Fansub group registry database operations
+//!
+//! Each group has a canonical name plus aliases seen in release titles and show
+//! configuration (e.g. `[SP]` or the legacy lowercase `subsplease`), so every
+//! place that detects or compares groups agrees on who released what.
+
+use anyhow::{Context, Result};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+/// Built-in groups seeded on first run: (name, aliases, default feed, trust level)
+pub const DEFAULT_GROUPS: &[(&str, &str, Option<&str>, i32)] = &[
+    ("SubsPlease", "SP,subsplease_direct", Some("subsplease"), 2),
+    ("Erai-raws", "Erai", Some("Erai-raws"), 2),
+    ("HorribleSubs", "HS", None, 1),
+    ("Judas", "", None, 1),
+    ("Yameii", "", None, 1),
+    ("Ember", "", None, 1),
+    ("ASM", "", None, 1),
+];
+
+/// A fansub group in the database
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FansubGroup {
+    pub id: u32,
+    /// Canonical name, as the group writes it in its release tags
+    pub name: String,
+    /// Other spellings that refer to the same group
+    pub aliases: Vec<String>,
+    /// Nyaa uploader account to search when fetching this group's releases
+    pub default_feed: Option<String>,
+    /// 0 = unknown, 1 = normal, 2 = trusted
+    pub trust_level: i32,
+}
+
+impl FansubGroup {
+    /// Whether `name` is this group's canonical name or one of its aliases
+    pub fn matches(&self, name: &str) -> bool {
+        let name = name.trim();
+        self.name.eq_ignore_ascii_case(name)
+            || self.aliases.iter().any(|alias| alias.eq_ignore_ascii_case(name))
+    }
+}
+
+/// Input for creating or replacing a fansub group
+#[derive(Debug, Clone, Deserialize)]
+pub struct SaveFansubGroup {
+    pub name: String,
+    /// Comma-separated aliases
+    #[serde(default)]
+    pub aliases: String,
+    pub default_feed: Option<String>,
+    #[serde(default = "default_trust_level")]
+    pub trust_level: i32,
+}
+
+fn default_trust_level() -> i32 {
+    1
+}
+
+/// Splits a comma-separated alias list, dropping blanks
+fn parse_aliases(aliases: &str) -> Vec<String> {
+    aliases
+        .split(',')
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Build the in-memory list from the built-in defaults, without touching the database
+pub fn builtin_groups() -> Vec<FansubGroup> {
+    DEFAULT_GROUPS
+        .iter()
+        .enumerate()
+        .map(|(i, (name, aliases, feed, trust))| FansubGroup {
+            id: i as u32 + 1,
+            name: name.to_string(),
+            aliases: parse_aliases(aliases),
+            default_feed: feed.map(str::to_string),
+            trust_level: *trust,
+        })
+        .collect()
+}
+
+/// Get all fansub groups, ordered by name
+pub fn get_all_fansub_groups(conn: &Connection) -> Result<Vec<FansubGroup>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, aliases, default_feed, trust_level
+             FROM fansub_groups
+             ORDER BY name COLLATE NOCASE",
+        )
+        .context("Failed to prepare get_all_fansub_groups statement")?;
+
+    let groups = stmt
+        .query_map([], |row| {
+            Ok(FansubGroup {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                aliases: parse_aliases(&row.get::<_, String>(2)?),
+                default_feed: row.get(3)?,
+                trust_level: row.get(4)?,
+            })
+        })
+        .context("Failed to query fansub groups")?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .context("Failed to read fansub group rows")?;
+
+    Ok(groups)
+}
+
+/// Normalize form input: trim everything and treat an empty feed as none
+fn clean_input(group: &SaveFansubGroup) -> (String, String, Option<String>) {
+    let aliases = parse_aliases(&group.aliases).join(",");
+    let feed = group
+        .default_feed
+        .as_deref()
+        .map(str::trim)
+        .filter(|f| !f.is_empty())
+        .map(str::to_string);
+    (group.name.trim().to_string(), aliases, feed)
+}
+
+/// Create a fansub group, returning its ID
+pub fn create_fansub_group(conn: &Connection, group: &SaveFansubGroup) -> Result<u32> {
+    let (name, aliases, feed) = clean_input(group);
+    conn.execute(
+        "INSERT INTO fansub_groups (name, aliases, default_feed, trust_level)
+         VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![name, aliases, feed, group.trust_level],
+    )
+    .with_context(|| format!("Failed to insert fansub group {}", name))?;
+
+    Ok(conn.last_insert_rowid() as u32)
+}
+
+/// Replace a fansub group's fields
+pub fn update_fansub_group(conn: &Connection, id: u32, group: &SaveFansubGroup) -> Result<bool> {
+    let (name, aliases, feed) = clean_input(group);
+    let rows_affected = conn
+        .execute(
+            "UPDATE fansub_groups
+             SET name = ?1, aliases = ?2, default_feed = ?3, trust_level = ?4
+             WHERE id = ?5",
+            rusqlite::params![name, aliases, feed, group.trust_level, id],
+        )
+        .context("Failed to update fansub group")?;
+
+    Ok(rows_affected > 0)
+}
+
+/// Delete a fansub group
+pub fn delete_fansub_group(conn: &Connection, id: u32) -> Result<bool> {
+    let rows_affected = conn
+        .execute("DELETE FROM fansub_groups WHERE id = ?1", [id])
+        .context("Failed to delete fansub group")?;
+
+    Ok(rows_affected > 0)
+}
+
+/// Seed the built-in groups if the table is empty
+pub(crate) fn seed_default_groups(conn: &Connection) -> Result<()> {
+    let count: i32 = conn
+        .query_row("SELECT COUNT(*) FROM fansub_groups", [], |row| row.get(0))
+        .unwrap_or(0);
+
+    if count > 0 {
+        return Ok(());
+    }
+
+    for (name, aliases, feed, trust) in DEFAULT_GROUPS {
+        conn.execute(
+            "INSERT INTO fansub_groups (name, aliases, default_feed, trust_level)
+             VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![name, aliases, feed, trust],
+        )
+        .with_context(|| format!("Failed to insert default fansub group: {}", name))?;
+    }
+
+    tracing::info!("Seeded {} default fansub groups", DEFAULT_GROUPS.len());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::schema::init_database;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_defaults_seeded() {
+        let conn = setup_test_db();
+
+        let groups = get_all_fansub_groups(&conn).unwrap();
+        assert_eq!(groups.len(), DEFAULT_GROUPS.len());
+
+        let subsplease = groups.iter().find(|g| g.name == "SubsPlease").unwrap();
+        assert_eq!(subsplease.aliases, vec!["SP", "subsplease_direct"]);
+        assert_eq!(subsplease.default_feed.as_deref(), Some("subsplease"));
+        assert!(subsplease.matches("sp"));
+        assert!(subsplease.matches("subsplease"));
+        assert!(!subsplease.matches("Erai-raws"));
+    }
+
+    #[test]
+    fn test_create_update_delete() {
+        let conn = setup_test_db();
+
+        let id = create_fansub_group(
+            &conn,
+            &SaveFansubGroup {
+                name: " ToonsHub ".to_string(),
+                aliases: "Toons, ,TH".to_string(),
+                default_feed: Some("".to_string()),
+                trust_level: 0,
+            },
+        )
+        .unwrap();
+
+        let group = get_all_fansub_groups(&conn)
+            .unwrap()
+            .into_iter()
+            .find(|g| g.id == id)
+            .unwrap();
+        assert_eq!(group.name, "ToonsHub");
+        assert_eq!(group.aliases, vec!["Toons", "TH"]);
+        assert_eq!(group.default_feed, None);
+
+        let updated = update_fansub_group(
+            &conn,
+            id,
+            &SaveFansubGroup {
+                name: "ToonsHub".to_string(),
+                aliases: "TH".to_string(),
+                default_feed: Some("ToonsHub".to_string()),
+                trust_level: 2,
+            },
+        )
+        .unwrap();
+        assert!(updated);
+
+        assert!(delete_fansub_group(&conn, id).unwrap());
+        assert!(!delete_fansub_group(&conn, id).unwrap());
+    }
+}
@@ -14,6 +14,14 @@ pub enum FilterType {
     Group,
     TitleExclude,
     TitleInclude,
+    /// Matches if any file in the torrent has one of the comma-separated extensions
+    FileExtension,
+    /// Matches if the torrent contains more files than the pattern number
+    FileCount,
+    /// Matches if the title is tagged with one of the comma-separated language codes
+    Language,
+    /// Matches if the item is in the Nyaa category (e.g. "1_3"); "1_0" matches all anime
+    Category,
 }
 
 impl FilterType {
@@ -23,6 +31,10 @@ impl FilterType {
             FilterType::Group => "group",
             FilterType::TitleExclude => "title_exclude",
             FilterType::TitleInclude => "title_include",
+            FilterType::FileExtension => "file_extension",
+            FilterType::FileCount => "file_count",
+            FilterType::Language => "language",
+            FilterType::Category => "category",
         }
     }
 
@@ -32,9 +44,18 @@ impl FilterType {
             "group" => Some(FilterType::Group),
             "title_exclude" => Some(FilterType::TitleExclude),
             "title_include" => Some(FilterType::TitleInclude),
+            "file_extension" => Some(FilterType::FileExtension),
+            "file_count" => Some(FilterType::FileCount),
+            "language" => Some(FilterType::Language),
+            "category" => Some(FilterType::Category),
             _ => None,
         }
     }
+
+    /// Content rules need the torrent's file list rather than just its title
+    pub fn is_content_rule(&self) -> bool {
+        matches!(self, FilterType::FileExtension | FilterType::FileCount)
+    }
 }
 
 /// Filter action enumeration
@@ -464,6 +485,13 @@ mod tests {
         assert!(get_filter(&conn, id).unwrap().is_none());
     }
 
+    #[test]
+    fn test_content_filter_types_roundtrip() {
+        for filter_type in [FilterType::FileExtension, FilterType::FileCount] {
+            assert_eq!(FilterType::from_str(filter_type.as_str()), Some(filter_type));
+        }
+    }
+
     #[test]
     fn test_toggle_filter() {
         let conn = setup_test_db();
@@ -18,17 +18,20 @@ pub fn is_already_downloaded(conn: &Connection, info_hash: &str) -> Result<bool>
 }
 
 /// Record a new download in the history
+///
+/// `torrent_file` is the path of the archived .torrent, if one was saved.
 pub fn record_download(
     conn: &Connection,
     show_id: u32,
     episode: u16,
     hash: &str,
     url: &str,
+    torrent_file: Option<&str>,
 ) -> Result<()> {
     conn.execute(
-        "INSERT INTO download_history (show_id, episode, info_hash, torrent_url)
-         VALUES (?1, ?2, ?3, ?4)",
-        params![show_id, episode as i32, hash, url],
+        "INSERT INTO download_history (show_id, episode, info_hash, torrent_url, torrent_file)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![show_id, episode as i32, hash, url, torrent_file],
     )
     .context("Failed to record download")?;
 
@@ -39,7 +42,7 @@ pub fn record_download(
 pub fn get_show_history(conn: &Connection, show_id: u32) -> Result<Vec<DownloadRecord>> {
     let mut stmt = conn
         .prepare(
-            "SELECT id, show_id, episode, info_hash, torrent_url, downloaded_at
+            "SELECT id, show_id, episode, info_hash, torrent_url, downloaded_at, torrent_file
              FROM download_history
              WHERE show_id = ?1
              ORDER BY downloaded_at DESC",
@@ -55,6 +58,7 @@ pub fn get_show_history(conn: &Connection, show_id: u32) -> Result<Vec<DownloadR
                 info_hash: row.get(3)?,
                 torrent_url: row.get(4)?,
                 downloaded_at: row.get(5)?,
+                torrent_file: row.get(6)?,
             })
         })
         .context("Failed to execute get_show_history query")?
@@ -64,11 +68,34 @@ pub fn get_show_history(conn: &Connection, show_id: u32) -> Result<Vec<DownloadR
     Ok(records)
 }
 
+/// Get a single download record by its ID
+pub fn get_download(conn: &Connection, id: u32) -> Result<Option<DownloadRecord>> {
+    conn.query_row(
+        "SELECT id, show_id, episode, info_hash, torrent_url, downloaded_at, torrent_file
+         FROM download_history
+         WHERE id = ?1",
+        [id],
+        |row| {
+            Ok(DownloadRecord {
+                id: row.get(0)?,
+                show_id: row.get(1)?,
+                episode: row.get::<_, i32>(2)? as u16,
+                info_hash: row.get(3)?,
+                torrent_url: row.get(4)?,
+                downloaded_at: row.get(5)?,
+                torrent_file: row.get(6)?,
+            })
+        },
+    )
+    .optional()
+    .context("Failed to get download record")
+}
+
 /// Get all download history (useful for debugging/admin)
 pub fn get_all_history(conn: &Connection) -> Result<Vec<DownloadRecord>> {
     let mut stmt = conn
         .prepare(
-            "SELECT id, show_id, episode, info_hash, torrent_url, downloaded_at
+            "SELECT id, show_id, episode, info_hash, torrent_url, downloaded_at, torrent_file
              FROM download_history
              ORDER BY downloaded_at DESC",
         )
@@ -83,6 +110,7 @@ pub fn get_all_history(conn: &Connection) -> Result<Vec<DownloadRecord>> {
                 info_hash: row.get(3)?,
                 torrent_url: row.get(4)?,
                 downloaded_at: row.get(5)?,
+                torrent_file: row.get(6)?,
             })
         })
         .context("Failed to execute get_all_history query")?
@@ -126,7 +154,7 @@ mod tests {
     fn test_record_and_check_download() {
         let conn = setup_test_db();
 
-        record_download(&conn, 1, 5, "test_hash_123", "http://example.com/torrent").unwrap();
+        record_download(&conn, 1, 5, "test_hash_123", "http://example.com/torrent", None).unwrap();
 
         let result = is_already_downloaded(&conn, "test_hash_123").unwrap();
         assert!(result);
@@ -136,22 +164,44 @@ mod tests {
     fn test_get_show_history() {
         let conn = setup_test_db();
 
-        record_download(&conn, 1, 1, "hash1", "http://example.com/1").unwrap();
-        record_download(&conn, 1, 2, "hash2", "http://example.com/2").unwrap();
-        record_download(&conn, 1, 3, "hash3", "http://example.com/3").unwrap();
+        record_download(&conn, 1, 1, "hash1", "http://example.com/1", None).unwrap();
+        record_download(&conn, 1, 2, "hash2", "http://example.com/2", None).unwrap();
+        record_download(&conn, 1, 3, "hash3", "http://example.com/3", None).unwrap();
 
         let history = get_show_history(&conn, 1).unwrap();
         assert_eq!(history.len(), 3);
     }
 
+    #[test]
+    fn test_get_download_with_torrent_file() {
+        let conn = setup_test_db();
+
+        record_download(
+            &conn,
+            1,
+            4,
+            "archived_hash",
+            "https://nyaa.si/download/1.torrent",
+            Some("torrents/archived_hash.torrent"),
+        )
+        .unwrap();
+
+        let id = get_show_history(&conn, 1).unwrap()[0].id;
+        let record = get_download(&conn, id).unwrap().unwrap();
+        assert_eq!(record.episode, 4);
+        assert_eq!(record.torrent_file.as_deref(), Some("torrents/archived_hash.torrent"));
+
+        assert!(get_download(&conn, id + 1).unwrap().is_none());
+    }
+
     #[test]
     fn test_unique_hash_constraint() {
         let conn = setup_test_db();
 
-        record_download(&conn, 1, 1, "unique_hash", "http://example.com/1").unwrap();
+        record_download(&conn, 1, 1, "unique_hash", "http://example.com/1", None).unwrap();
 
         // Attempting to insert the same hash should fail
-        let result = record_download(&conn, 1, 2, "unique_hash", "http://example.com/2");
+        let result = record_download(&conn, 1, 2, "unique_hash", "http://example.com/2", None);
         assert!(result.is_err());
     }
 
@@ -159,7 +209,7 @@ mod tests {
     fn test_cascade_delete() {
         let conn = setup_test_db();
 
-        record_download(&conn, 1, 1, "cascade_test_hash", "http://example.com/1").unwrap();
+        record_download(&conn, 1, 1, "cascade_test_hash", "http://example.com/1", None).unwrap();
 
         // Delete the show
         conn.execute("DELETE FROM shows WHERE id = 1", []).unwrap();
@@ -1,8 +1,18 @@
+pub mod aliases;
+pub mod anilist_cache;
+pub mod anilist_list;
 pub mod config;
+pub mod fansub_groups;
 pub mod filters;
 pub mod history;
+pub mod poll_schedule;
+pub mod provider_ids;
+pub mod release_delays;
 pub mod schema;
+pub mod sequels;
 pub mod shows;
+pub mod sync_schedules;
+pub mod trackers;
 
 use std::sync::OnceLock;
 
@@ -32,6 +42,18 @@ pub fn init_connection() -> Result<()> {
     Ok(())
 }
 
+/// Initialize the global database connection with an in-memory database
+///
+/// Tests share it, so calling this more than once is fine.
+#[cfg(test)]
+pub fn init_test_connection() {
+    DB.get_or_init(|| {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        Mutex::new(conn)
+    });
+}
+
 /// Get a reference to the global database connection mutex
 pub fn get_connection() -> Result<&'static Mutex<Connection>> {
     DB.get()
@@ -66,17 +88,43 @@ where
 }
 
 // Re-export commonly used types and functions
-pub use config::{get_rss_config, set_rss_enabled, update_last_poll_time, update_poll_interval};
+pub use aliases::{
+    add_show_alias, delete_show_alias, get_search_titles, get_show_alias, get_show_aliases,
+    AliasOrigin, ShowAlias,
+};
+pub use anilist_cache::{get_cached_query, store_cached_query};
+pub use anilist_list::{drop_list_show, get_list_shows, mark_list_show};
+pub use config::{
+    get_rss_config, set_auto_track_sequels, set_metadata_provider, set_rss_enabled, update_anilist_account,
+    update_airing_polling, update_anilist_list, update_last_poll_time, update_quiet_hours,
+    update_match_threshold, update_nyaa_defaults, update_poll_interval,
+};
+pub use fansub_groups::{
+    create_fansub_group, delete_fansub_group, get_all_fansub_groups, update_fansub_group,
+    FansubGroup, SaveFansubGroup,
+};
 pub use filters::{
     create_filter, create_show_filter, delete_filter, delete_show_filter, get_all_filters,
     get_filter, get_global_filters, get_show_filters, toggle_filter, update_filter,
     CreateFilterRule, FilterAction, FilterRule, FilterType, ShowFilterOverride, UpdateFilterRule,
 };
-pub use history::{get_show_history, is_already_downloaded, record_download};
+pub use history::{get_download, get_show_history, is_already_downloaded, record_download};
+pub use poll_schedule::{get_poll_schedule, get_poll_schedules, record_show_poll, update_airing_times, PollSchedule};
+pub use provider_ids::{get_anilist_id, get_provider_id, record_provider_id, MetadataSource};
+pub use release_delays::{get_release_delay_stats, record_release_delay, ReleaseDelayStats};
 pub use schema::{init_database, migrate_from_json_if_needed};
+pub use sequels::{dismiss_sequel, get_pending_sequels, get_sequel, record_sequel, Sequel};
 pub use shows::{
-    delete_show, get_all_shows, get_show, get_tracked_shows, insert_show, update_last_downloaded,
-    update_show,
+    delete_show, get_all_shows, get_show, get_tracked_shows, insert_show, is_manual_show_id,
+    next_manual_show_id, update_last_downloaded, update_show,
+};
+pub use sync_schedules::{
+    create_sync_schedule, delete_sync_schedule, get_sync_schedules, toggle_sync_schedule,
+    SyncSchedule,
+};
+pub use trackers::{
+    create_tracker, delete_tracker, get_all_trackers, get_trackers_for_source, toggle_tracker,
+    CreateMagnetTracker,
 };
 
 /// Data models for the database layer
@@ -100,6 +148,36 @@ pub mod models {
         pub next_air_date: Option<String>,
         pub created_at: Option<String>,
         pub updated_at: Option<String>,
+        /// Nyaa category code (e.g. "1_3"); None uses the global default
+        pub nyaa_category: Option<String>,
+        /// Preferred subtitle language code (e.g. "es"); None uses the global default
+        pub language: Option<String>,
+        /// Nyaa search query with `{group}`, `{alternate}` and `{quality}` placeholders;
+        /// None uses "{group} {alternate}"
+        pub query_template: Option<String>,
+        /// Search the group's Nyaa user feed (`u=`) instead of putting the group in the query
+        pub nyaa_user_feed: bool,
+        /// Don't push download progress for this show to AniList
+        pub skip_anilist_progress: bool,
+    }
+
+    impl Show {
+        /// Whether the show was added by hand rather than from AniList
+        pub fn is_manual(&self) -> bool {
+            super::is_manual_show_id(self.id)
+        }
+
+        /// Nyaa category to search for this show
+        pub fn effective_category(&self, config: &RssConfig) -> String {
+            self.nyaa_category
+                .clone()
+                .unwrap_or_else(|| config.default_nyaa_category.clone())
+        }
+
+        /// Subtitle language to prefer for this show, if any
+        pub fn effective_language(&self, config: &RssConfig) -> Option<String> {
+            self.language.clone().or_else(|| config.default_language.clone())
+        }
     }
 
     impl Default for Show {
@@ -119,6 +197,11 @@ pub mod models {
                 next_air_date: None,
                 created_at: None,
                 updated_at: None,
+                nyaa_category: None,
+                language: None,
+                query_template: None,
+                nyaa_user_feed: false,
+                skip_anilist_progress: false,
             }
         }
     }
@@ -130,6 +213,37 @@ pub mod models {
         pub poll_times_per_day: u8,
         pub last_poll_time: Option<String>,
         pub enabled: bool,
+        /// Minimum title similarity (0.0-1.0) to accept a match without asking
+        pub match_confidence_threshold: f64,
+        /// Nyaa category searched for shows without their own
+        pub default_nyaa_category: String,
+        /// Subtitle language preferred for shows without their own
+        pub default_language: Option<String>,
+        /// AniList account whose watchlist is imported
+        pub anilist_username: Option<String>,
+        /// Re-sync the AniList watchlist periodically
+        pub anilist_list_sync: bool,
+        /// AniList API client used to authorize this app
+        pub anilist_client_id: Option<String>,
+        /// AniList OAuth access token
+        #[serde(skip_serializing)]
+        pub anilist_token: Option<String>,
+        /// Set the AniList list progress of downloaded shows
+        pub anilist_push_progress: bool,
+        /// Track sequels of our shows as soon as they're found
+        pub auto_track_sequels: bool,
+        /// Where the seasonal and airing grid comes from
+        pub metadata_provider: super::MetadataSource,
+        /// Poll each show around its airing times instead of all shows on a fixed interval
+        pub airing_aware_polling: bool,
+        /// Minutes between searches for a show in the hours after an episode airs
+        pub airing_poll_minutes: u16,
+        /// Most shows searched in one airing-aware poll
+        pub max_shows_per_poll: u16,
+        /// Start of the daily window in which scheduled syncs add no torrents ("HH:MM")
+        pub quiet_hours_start: Option<String>,
+        /// End of the quiet hours ("HH:MM")
+        pub quiet_hours_end: Option<String>,
     }
 
     impl Default for RssConfig {
@@ -139,6 +253,21 @@ pub mod models {
                 poll_times_per_day: 4,
                 last_poll_time: None,
                 enabled: true,
+                match_confidence_threshold: 0.9,
+                default_nyaa_category: "1_2".to_string(),
+                default_language: None,
+                anilist_username: None,
+                anilist_list_sync: false,
+                anilist_client_id: None,
+                anilist_token: None,
+                anilist_push_progress: false,
+                auto_track_sequels: false,
+                metadata_provider: super::MetadataSource::AniList,
+                airing_aware_polling: true,
+                airing_poll_minutes: 15,
+                max_shows_per_poll: 10,
+                quiet_hours_start: None,
+                quiet_hours_end: None,
             }
         }
     }
@@ -152,5 +281,7 @@ pub mod models {
         pub info_hash: String,
         pub torrent_url: Option<String>,
         pub downloaded_at: Option<String>,
+        /// Path of the archived .torrent file, if one was saved
+        pub torrent_file: Option<String>,
     }
 }
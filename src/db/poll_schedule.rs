@@ -0,0 +1,137 @@
+//! Airing times of tracked shows and when each was last searched for
+//!
+//! Times are Unix timestamps in seconds, as AniList reports them.
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// When a show's episodes air and when it was last searched for
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PollSchedule {
+    pub show_id: u32,
+    /// When the latest aired episode went out
+    pub last_aired_at: Option<i64>,
+    /// Number of the latest aired episode
+    pub last_aired_episode: Option<u16>,
+    /// When the next episode airs
+    pub next_airing_at: Option<i64>,
+    /// When the show was last searched for
+    pub last_polled_at: Option<i64>,
+}
+
+/// Store a show's airing times, keeping when it was last searched for
+pub fn update_airing_times(
+    conn: &Connection,
+    show_id: u32,
+    last_aired_at: Option<i64>,
+    last_aired_episode: Option<u16>,
+    next_airing_at: Option<i64>,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO poll_schedule (show_id, last_aired_at, last_aired_episode, next_airing_at)
+         VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(show_id) DO UPDATE SET
+            last_aired_at = ?2, last_aired_episode = ?3, next_airing_at = ?4",
+        params![show_id, last_aired_at, last_aired_episode, next_airing_at],
+    )
+    .context("Failed to update airing times")?;
+
+    Ok(())
+}
+
+/// Record that a show was searched for at `at`
+pub fn record_show_poll(conn: &Connection, show_id: u32, at: i64) -> Result<()> {
+    conn.execute(
+        "INSERT INTO poll_schedule (show_id, last_polled_at) VALUES (?1, ?2)
+         ON CONFLICT(show_id) DO UPDATE SET last_polled_at = ?2",
+        params![show_id, at],
+    )
+    .context("Failed to record show poll")?;
+
+    Ok(())
+}
+
+/// Poll schedule of a show, if it has one
+pub fn get_poll_schedule(conn: &Connection, show_id: u32) -> Result<Option<PollSchedule>> {
+    conn.query_row(
+        "SELECT show_id, last_aired_at, last_aired_episode, next_airing_at, last_polled_at
+         FROM poll_schedule
+         WHERE show_id = ?1",
+        [show_id],
+        row_to_schedule,
+    )
+    .optional()
+    .context("Failed to get poll schedule")
+}
+
+/// Poll schedules of all shows that have one, by show ID
+pub fn get_poll_schedules(conn: &Connection) -> Result<HashMap<u32, PollSchedule>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT show_id, last_aired_at, last_aired_episode, next_airing_at, last_polled_at
+             FROM poll_schedule",
+        )
+        .context("Failed to prepare get_poll_schedules query")?;
+
+    let schedules = stmt
+        .query_map([], row_to_schedule)
+        .context("Failed to query poll schedules")?
+        .map(|schedule| schedule.map(|s| (s.show_id, s)))
+        .collect::<rusqlite::Result<HashMap<_, _>>>()
+        .context("Failed to read poll schedules")?;
+
+    Ok(schedules)
+}
+
+fn row_to_schedule(row: &rusqlite::Row) -> rusqlite::Result<PollSchedule> {
+    Ok(PollSchedule {
+        show_id: row.get(0)?,
+        last_aired_at: row.get(1)?,
+        last_aired_episode: row.get(2)?,
+        next_airing_at: row.get(3)?,
+        last_polled_at: row.get(4)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{insert_show, models::Show, schema::init_database};
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        insert_show(&conn, &Show { id: 1, ..Default::default() }).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_airing_times_keep_last_poll() {
+        let conn = setup_test_db();
+
+        record_show_poll(&conn, 1, 500).unwrap();
+        update_airing_times(&conn, 1, Some(1000), Some(3), Some(2000)).unwrap();
+
+        let schedules = get_poll_schedules(&conn).unwrap();
+        assert_eq!(
+            schedules[&1],
+            PollSchedule {
+                show_id: 1,
+                last_aired_at: Some(1000),
+                last_aired_episode: Some(3),
+                next_airing_at: Some(2000),
+                last_polled_at: Some(500),
+            }
+        );
+
+        // A later poll leaves the airing times alone
+        record_show_poll(&conn, 1, 1500).unwrap();
+        let schedules = get_poll_schedules(&conn).unwrap();
+        assert_eq!(schedules[&1].last_polled_at, Some(1500));
+        assert_eq!(schedules[&1].next_airing_at, Some(2000));
+        assert_eq!(get_poll_schedule(&conn, 1).unwrap(), Some(schedules[&1].clone()));
+        assert_eq!(get_poll_schedule(&conn, 2).unwrap(), None);
+    }
+}
@@ -0,0 +1,169 @@
+//! IDs of shows on other metadata providers
+//!
+//! `Show.id` is the AniList ID; MyAnimeList and Kitsu IDs are mapped to it here so
+//! a show found on any provider can be matched to the same show on the others.
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+/// Where show metadata comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MetadataSource {
+    AniList,
+    /// MyAnimeList, through a Jikan API
+    MyAnimeList,
+    Kitsu,
+}
+
+impl MetadataSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MetadataSource::AniList => "anilist",
+            MetadataSource::MyAnimeList => "myanimelist",
+            MetadataSource::Kitsu => "kitsu",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            MetadataSource::AniList => "AniList",
+            MetadataSource::MyAnimeList => "MyAnimeList",
+            MetadataSource::Kitsu => "Kitsu",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "anilist" => Some(MetadataSource::AniList),
+            "myanimelist" => Some(MetadataSource::MyAnimeList),
+            "kitsu" => Some(MetadataSource::Kitsu),
+            _ => None,
+        }
+    }
+}
+
+/// Record that a show has the given ID on another provider
+///
+/// AniList IDs map to themselves and aren't stored.
+pub fn record_provider_id(
+    conn: &Connection,
+    anilist_id: u32,
+    source: MetadataSource,
+    external_id: u32,
+) -> Result<()> {
+    if source == MetadataSource::AniList {
+        return Ok(());
+    }
+
+    conn.execute(
+        "INSERT INTO provider_ids (anilist_id, provider, external_id) VALUES (?1, ?2, ?3)
+         ON CONFLICT(provider, external_id) DO UPDATE SET anilist_id = ?1",
+        params![anilist_id, source.as_str(), external_id],
+    )
+    .context("Failed to record provider id")?;
+
+    Ok(())
+}
+
+/// AniList ID of a show given its ID on a provider, if the mapping is known
+pub fn get_anilist_id(
+    conn: &Connection,
+    source: MetadataSource,
+    external_id: u32,
+) -> Result<Option<u32>> {
+    if source == MetadataSource::AniList {
+        return Ok(Some(external_id));
+    }
+
+    conn.query_row(
+        "SELECT anilist_id FROM provider_ids WHERE provider = ?1 AND external_id = ?2",
+        params![source.as_str(), external_id],
+        |row| row.get(0),
+    )
+    .optional()
+    .context("Failed to get AniList id")
+}
+
+/// ID of a show on a provider given its AniList ID, if the mapping is known
+pub fn get_provider_id(
+    conn: &Connection,
+    anilist_id: u32,
+    source: MetadataSource,
+) -> Result<Option<u32>> {
+    if source == MetadataSource::AniList {
+        return Ok(Some(anilist_id));
+    }
+
+    conn.query_row(
+        "SELECT external_id FROM provider_ids
+         WHERE anilist_id = ?1 AND provider = ?2
+         ORDER BY rowid DESC LIMIT 1",
+        params![anilist_id, source.as_str()],
+        |row| row.get(0),
+    )
+    .optional()
+    .context("Failed to get provider id")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::schema::init_database;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_provider_id_mappings() {
+        let conn = setup_test_db();
+
+        record_provider_id(&conn, 154587, MetadataSource::MyAnimeList, 52991).unwrap();
+        record_provider_id(&conn, 154587, MetadataSource::Kitsu, 46474).unwrap();
+
+        assert_eq!(
+            get_anilist_id(&conn, MetadataSource::MyAnimeList, 52991).unwrap(),
+            Some(154587)
+        );
+        assert_eq!(get_anilist_id(&conn, MetadataSource::Kitsu, 46474).unwrap(), Some(154587));
+        assert_eq!(get_anilist_id(&conn, MetadataSource::Kitsu, 1).unwrap(), None);
+        assert_eq!(
+            get_provider_id(&conn, 154587, MetadataSource::MyAnimeList).unwrap(),
+            Some(52991)
+        );
+        assert_eq!(get_provider_id(&conn, 1, MetadataSource::Kitsu).unwrap(), None);
+
+        // AniList IDs are their own mapping
+        assert_eq!(
+            get_provider_id(&conn, 154587, MetadataSource::AniList).unwrap(),
+            Some(154587)
+        );
+        assert_eq!(get_anilist_id(&conn, MetadataSource::AniList, 7).unwrap(), Some(7));
+    }
+
+    #[test]
+    fn test_remapped_provider_id() {
+        let conn = setup_test_db();
+
+        // A provider ID belongs to one show; recording it again moves it
+        record_provider_id(&conn, 1, MetadataSource::MyAnimeList, 100).unwrap();
+        record_provider_id(&conn, 2, MetadataSource::MyAnimeList, 100).unwrap();
+        assert_eq!(get_anilist_id(&conn, MetadataSource::MyAnimeList, 100).unwrap(), Some(2));
+        assert_eq!(get_provider_id(&conn, 1, MetadataSource::MyAnimeList).unwrap(), None);
+    }
+
+    #[test]
+    fn test_metadata_source_round_trip() {
+        for source in [
+            MetadataSource::AniList,
+            MetadataSource::MyAnimeList,
+            MetadataSource::Kitsu,
+        ] {
+            assert_eq!(MetadataSource::parse(source.as_str()), Some(source));
+        }
+        assert_eq!(MetadataSource::parse("simkl"), None);
+    }
+}
@@ -0,0 +1,149 @@
+//! How long after an episode airs each group releases it
+//!
+//! A delay is recorded whenever the tracker downloads an episode whose airing time
+//! is known, so polls can be timed to when a show's group usually releases.
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Release delay statistics of one show from one group
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReleaseDelayStats {
+    pub show_id: u32,
+    pub title: String,
+    /// Canonical group name
+    pub group: String,
+    /// Number of episodes measured
+    pub samples: usize,
+    pub average_secs: i64,
+    /// Median delay
+    pub p50_secs: i64,
+    pub p90_secs: i64,
+}
+
+/// Record when a group released an episode, relative to when it aired
+///
+/// Times are Unix timestamps in seconds. Recording an episode again replaces it.
+pub fn record_release_delay(
+    conn: &Connection,
+    show_id: u32,
+    group: &str,
+    episode: u16,
+    aired_at: i64,
+    released_at: i64,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO release_delays (show_id, fansub_group, episode, aired_at, released_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(show_id, fansub_group, episode) DO UPDATE SET
+            aired_at = ?4, released_at = ?5",
+        params![show_id, group, episode, aired_at, released_at],
+    )
+    .context("Failed to record release delay")?;
+
+    Ok(())
+}
+
+/// Delay statistics of every show and group with at least one measured episode
+pub fn get_release_delay_stats(conn: &Connection) -> Result<Vec<ReleaseDelayStats>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT d.show_id, s.title, d.fansub_group, d.released_at - d.aired_at
+             FROM release_delays d
+             JOIN shows s ON s.id = d.show_id
+             ORDER BY s.title, d.fansub_group",
+        )
+        .context("Failed to prepare get_release_delay_stats query")?;
+
+    let mut delays: BTreeMap<(String, String, u32), Vec<i64>> = BTreeMap::new();
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, u32>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, i64>(3)?,
+            ))
+        })
+        .context("Failed to query release delays")?;
+    for row in rows {
+        let (show_id, title, group, delay) = row.context("Failed to read release delay")?;
+        delays.entry((title, group, show_id)).or_default().push(delay);
+    }
+
+    Ok(delays
+        .into_iter()
+        .map(|((title, group, show_id), mut delays)| {
+            delays.sort_unstable();
+            ReleaseDelayStats {
+                show_id,
+                title,
+                group,
+                samples: delays.len(),
+                average_secs: delays.iter().sum::<i64>() / delays.len() as i64,
+                p50_secs: percentile(&delays, 50),
+                p90_secs: percentile(&delays, 90),
+            }
+        })
+        .collect())
+}
+
+/// Nearest-rank percentile of sorted, non-empty values
+fn percentile(sorted: &[i64], p: usize) -> i64 {
+    let rank = (sorted.len() * p).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{insert_show, models::Show, schema::init_database};
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        insert_show(
+            &conn,
+            &Show {
+                id: 1,
+                title: "Frieren".to_string(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_release_delay_stats() {
+        let conn = setup_test_db();
+
+        for (episode, delay) in [(1, 1800), (2, 2400), (3, 3000), (4, 36000)] {
+            record_release_delay(&conn, 1, "SubsPlease", episode, 0, delay).unwrap();
+        }
+        record_release_delay(&conn, 1, "Erai-raws", 1, 0, 7200).unwrap();
+        // A re-recorded episode replaces the earlier measurement
+        record_release_delay(&conn, 1, "SubsPlease", 1, 0, 1200).unwrap();
+
+        let stats = get_release_delay_stats(&conn).unwrap();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].group, "Erai-raws");
+        assert_eq!(stats[0].p50_secs, 7200);
+
+        let subsplease = &stats[1];
+        assert_eq!(subsplease.title, "Frieren");
+        assert_eq!(subsplease.samples, 4);
+        assert_eq!(subsplease.average_secs, (1200 + 2400 + 3000 + 36000) / 4);
+        assert_eq!(subsplease.p50_secs, 2400);
+        assert_eq!(subsplease.p90_secs, 36000);
+    }
+
+    #[test]
+    fn test_percentile() {
+        assert_eq!(percentile(&[5], 50), 5);
+        assert_eq!(percentile(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10], 50), 5);
+        assert_eq!(percentile(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10], 90), 9);
+    }
+}
@@ -28,6 +28,17 @@ pub fn init_database(conn: &Connection) -> Result<()> {
     )
     .context("Failed to create shows table")?;
 
+    // Per-show Nyaa category and subtitle language; NULL means the global default
+    add_column_if_missing(conn, "shows", "nyaa_category", "TEXT")?;
+    add_column_if_missing(conn, "shows", "language", "TEXT")?;
+
+    // Per-show Nyaa search query template and user feed option
+    add_column_if_missing(conn, "shows", "query_template", "TEXT")?;
+    add_column_if_missing(conn, "shows", "nyaa_user_feed", "INTEGER NOT NULL DEFAULT 0")?;
+
+    // Per-show opt-out of pushing download progress to AniList
+    add_column_if_missing(conn, "shows", "skip_anilist_progress", "INTEGER NOT NULL DEFAULT 0")?;
+
     // Create rss_config table
     conn.execute(
         "CREATE TABLE IF NOT EXISTS rss_config (
@@ -40,6 +51,69 @@ pub fn init_database(conn: &Connection) -> Result<()> {
     )
     .context("Failed to create rss_config table")?;
 
+    add_column_if_missing(
+        conn,
+        "rss_config",
+        "match_confidence_threshold",
+        "REAL NOT NULL DEFAULT 0.9",
+    )?;
+    add_column_if_missing(
+        conn,
+        "rss_config",
+        "default_nyaa_category",
+        "TEXT NOT NULL DEFAULT '1_2'",
+    )?;
+    add_column_if_missing(conn, "rss_config", "default_language", "TEXT")?;
+
+    // AniList account whose watchlist is imported, and whether to re-sync it periodically
+    add_column_if_missing(conn, "rss_config", "anilist_username", "TEXT")?;
+    add_column_if_missing(conn, "rss_config", "anilist_list_sync", "INTEGER NOT NULL DEFAULT 0")?;
+
+    // AniList OAuth client and access token, and whether downloads update the account's list
+    add_column_if_missing(conn, "rss_config", "anilist_client_id", "TEXT")?;
+    add_column_if_missing(conn, "rss_config", "anilist_token", "TEXT")?;
+    add_column_if_missing(
+        conn,
+        "rss_config",
+        "anilist_push_progress",
+        "INTEGER NOT NULL DEFAULT 0",
+    )?;
+
+    // Track sequels of our shows as soon as they're found
+    add_column_if_missing(conn, "rss_config", "auto_track_sequels", "INTEGER NOT NULL DEFAULT 0")?;
+
+    // Provider for the seasonal and airing grid
+    add_column_if_missing(
+        conn,
+        "rss_config",
+        "metadata_provider",
+        "TEXT NOT NULL DEFAULT 'anilist'",
+    )?;
+
+    // Poll shows around their airing times, and how often and how many at most
+    add_column_if_missing(
+        conn,
+        "rss_config",
+        "airing_aware_polling",
+        "INTEGER NOT NULL DEFAULT 1",
+    )?;
+    add_column_if_missing(
+        conn,
+        "rss_config",
+        "airing_poll_minutes",
+        "INTEGER NOT NULL DEFAULT 15",
+    )?;
+    add_column_if_missing(
+        conn,
+        "rss_config",
+        "max_shows_per_poll",
+        "INTEGER NOT NULL DEFAULT 10",
+    )?;
+
+    // Daily window ("HH:MM" local time) in which scheduled syncs add no torrents
+    add_column_if_missing(conn, "rss_config", "quiet_hours_start", "TEXT")?;
+    add_column_if_missing(conn, "rss_config", "quiet_hours_end", "TEXT")?;
+
     // Create download_history table
     conn.execute(
         "CREATE TABLE IF NOT EXISTS download_history (
@@ -55,6 +129,9 @@ pub fn init_database(conn: &Connection) -> Result<()> {
     )
     .context("Failed to create download_history table")?;
 
+    // Path of the archived .torrent file for re-adding a release later
+    add_column_if_missing(conn, "download_history", "torrent_file", "TEXT")?;
+
     // Insert default RSS config if it doesn't exist
     conn.execute(
         "INSERT OR IGNORE INTO rss_config (id, poll_times_per_day, enabled) VALUES (1, 4, 1)",
@@ -96,9 +173,176 @@ pub fn init_database(conn: &Connection) -> Result<()> {
     )
     .context("Failed to create show_filter_overrides table")?;
 
+    // Create magnet_trackers table for announce URLs appended to magnet links
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS magnet_trackers (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            url TEXT NOT NULL,
+            source TEXT,
+            enabled INTEGER NOT NULL DEFAULT 1
+        )",
+        [],
+    )
+    .context("Failed to create magnet_trackers table")?;
+
+    // Create fansub_groups table for the group registry
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS fansub_groups (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            aliases TEXT NOT NULL DEFAULT '',
+            default_feed TEXT,
+            trust_level INTEGER NOT NULL DEFAULT 1
+        )",
+        [],
+    )
+    .context("Failed to create fansub_groups table")?;
+
+    // Create show_aliases table for alternate titles searched by the tracker
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS show_aliases (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            show_id INTEGER NOT NULL,
+            alias TEXT NOT NULL COLLATE NOCASE,
+            origin TEXT NOT NULL DEFAULT 'manual',
+            UNIQUE (show_id, alias),
+            FOREIGN KEY (show_id) REFERENCES shows(id) ON DELETE CASCADE
+        )",
+        [],
+    )
+    .context("Failed to create show_aliases table")?;
+
+    // Create anilist_cache table for AniList query results; NULL expires_at never expires
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS anilist_cache (
+            key TEXT PRIMARY KEY,
+            body TEXT NOT NULL,
+            fetched_at TEXT NOT NULL DEFAULT (datetime('now')),
+            expires_at TEXT
+        )",
+        [],
+    )
+    .context("Failed to create anilist_cache table")?;
+
+    // Create anilist_list_shows table, marking shows tracked because of the AniList watchlist
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS anilist_list_shows (
+            show_id INTEGER PRIMARY KEY,
+            list_status TEXT NOT NULL,
+            synced_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY (show_id) REFERENCES shows(id) ON DELETE CASCADE
+        )",
+        [],
+    )
+    .context("Failed to create anilist_list_shows table")?;
+
+    // Create sequels table for upcoming sequels of shows we've tracked
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS sequels (
+            id INTEGER PRIMARY KEY,
+            prequel_id INTEGER NOT NULL,
+            title TEXT NOT NULL,
+            airing TEXT,
+            dismissed INTEGER NOT NULL DEFAULT 0,
+            detected_at TEXT NOT NULL DEFAULT (datetime('now')),
+            FOREIGN KEY (prequel_id) REFERENCES shows(id) ON DELETE CASCADE
+        )",
+        [],
+    )
+    .context("Failed to create sequels table")?;
+
+    // Create provider_ids table mapping MyAnimeList and Kitsu IDs to AniList IDs
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS provider_ids (
+            anilist_id INTEGER NOT NULL,
+            provider TEXT NOT NULL,
+            external_id INTEGER NOT NULL,
+            PRIMARY KEY (provider, external_id)
+        )",
+        [],
+    )
+    .context("Failed to create provider_ids table")?;
+
+    // Create poll_schedule table with each show's airing times and when it was last searched
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS poll_schedule (
+            show_id INTEGER PRIMARY KEY,
+            last_aired_at INTEGER,
+            last_aired_episode INTEGER,
+            next_airing_at INTEGER,
+            last_polled_at INTEGER,
+            FOREIGN KEY (show_id) REFERENCES shows(id) ON DELETE CASCADE
+        )",
+        [],
+    )
+    .context("Failed to create poll_schedule table")?;
+
+    // Create release_delays table with how long after air each group released an episode
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS release_delays (
+            show_id INTEGER NOT NULL,
+            fansub_group TEXT NOT NULL,
+            episode INTEGER NOT NULL,
+            aired_at INTEGER NOT NULL,
+            released_at INTEGER NOT NULL,
+            PRIMARY KEY (show_id, fansub_group, episode),
+            FOREIGN KEY (show_id) REFERENCES shows(id) ON DELETE CASCADE
+        )",
+        [],
+    )
+    .context("Failed to create release_delays table")?;
+
+    // Create sync_schedules table with cron expressions to sync on
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS sync_schedules (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            expression TEXT NOT NULL,
+            enabled INTEGER NOT NULL DEFAULT 1,
+            created_at TEXT DEFAULT (datetime('now'))
+        )",
+        [],
+    )
+    .context("Failed to create sync_schedules table")?;
+
     // Seed default filters if none exist
     seed_default_filters(conn)?;
 
+    // Seed default trackers if none exist
+    super::trackers::seed_default_trackers(conn)?;
+
+    // Seed built-in fansub groups if none exist
+    super::fansub_groups::seed_default_groups(conn)?;
+
+    Ok(())
+}
+
+/// Add a column to an existing table unless it's already there
+///
+/// `CREATE TABLE IF NOT EXISTS` leaves older databases untouched, so columns added
+/// after a table was first created have to be migrated in separately.
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<()> {
+    let mut stmt = conn
+        .prepare(&format!("PRAGMA table_info({})", table))
+        .with_context(|| format!("Failed to read columns of {}", table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<std::result::Result<Vec<_>, _>>()?
+        .iter()
+        .any(|name| name == column);
+
+    if !exists {
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            [],
+        )
+        .with_context(|| format!("Failed to add column {}.{}", table, column))?;
+    }
+
     Ok(())
 }
 
@@ -295,6 +539,16 @@ mod tests {
         assert!(tables.contains(&"shows".to_string()));
         assert!(tables.contains(&"rss_config".to_string()));
         assert!(tables.contains(&"download_history".to_string()));
+        assert!(tables.contains(&"magnet_trackers".to_string()));
+        assert!(tables.contains(&"fansub_groups".to_string()));
+        assert!(tables.contains(&"show_aliases".to_string()));
+        assert!(tables.contains(&"anilist_cache".to_string()));
+        assert!(tables.contains(&"anilist_list_shows".to_string()));
+        assert!(tables.contains(&"sequels".to_string()));
+        assert!(tables.contains(&"provider_ids".to_string()));
+        assert!(tables.contains(&"poll_schedule".to_string()));
+        assert!(tables.contains(&"release_delays".to_string()));
+        assert!(tables.contains(&"sync_schedules".to_string()));
     }
 
     #[test]
@@ -313,4 +567,34 @@ mod tests {
         assert_eq!(poll_times, 4);
         assert_eq!(enabled, 1);
     }
+
+    #[test]
+    fn test_add_column_to_existing_table() {
+        let conn = Connection::open_in_memory().unwrap();
+
+        // Simulate a database created before torrent_file existed
+        conn.execute(
+            "CREATE TABLE download_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                show_id INTEGER NOT NULL,
+                episode INTEGER NOT NULL,
+                info_hash TEXT NOT NULL UNIQUE,
+                torrent_url TEXT,
+                downloaded_at TEXT DEFAULT (datetime('now'))
+            )",
+            [],
+        )
+        .unwrap();
+
+        // Running init twice must not try to add the column again
+        init_database(&conn).unwrap();
+        init_database(&conn).unwrap();
+
+        conn.execute(
+            "INSERT INTO download_history (show_id, episode, info_hash, torrent_file)
+             VALUES (1, 1, 'hash', 'torrents/hash.torrent')",
+            [],
+        )
+        .unwrap();
+    }
 }
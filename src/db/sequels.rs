@@ -0,0 +1,148 @@
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+/// An upcoming sequel of a show we've tracked
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sequel {
+    /// AniList ID of the sequel
+    pub id: u32,
+    /// Show the sequel follows
+    pub prequel_id: u32,
+    pub title: String,
+    /// Season it airs in, e.g. "Spring 2025", if announced
+    pub airing: Option<String>,
+    /// Whether the user chose not to track it
+    pub dismissed: bool,
+    pub detected_at: Option<String>,
+}
+
+/// Record a detected sequel, refreshing its title and season if already known
+///
+/// Returns whether the sequel is new.
+pub fn record_sequel(conn: &Connection, sequel: &Sequel) -> Result<bool> {
+    let known: bool = conn
+        .query_row("SELECT 1 FROM sequels WHERE id = ?1", [sequel.id], |_| Ok(true))
+        .optional()
+        .context("Failed to look up sequel")?
+        .unwrap_or(false);
+
+    conn.execute(
+        "INSERT INTO sequels (id, prequel_id, title, airing) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(id) DO UPDATE SET title = ?3, airing = ?4",
+        params![sequel.id, sequel.prequel_id, sequel.title, sequel.airing],
+    )
+    .context("Failed to record sequel")?;
+
+    Ok(!known)
+}
+
+/// Get a sequel by its AniList ID
+pub fn get_sequel(conn: &Connection, id: u32) -> Result<Option<Sequel>> {
+    conn.query_row(
+        "SELECT id, prequel_id, title, airing, dismissed, detected_at FROM sequels WHERE id = ?1",
+        [id],
+        row_to_sequel,
+    )
+    .optional()
+    .context("Failed to get sequel")
+}
+
+/// Get sequels that are neither dismissed nor added as shows yet, newest first
+pub fn get_pending_sequels(conn: &Connection) -> Result<Vec<Sequel>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, prequel_id, title, airing, dismissed, detected_at
+             FROM sequels
+             WHERE dismissed = 0 AND id NOT IN (SELECT id FROM shows)
+             ORDER BY detected_at DESC, title",
+        )
+        .context("Failed to prepare get_pending_sequels query")?;
+
+    let sequels = stmt
+        .query_map([], row_to_sequel)
+        .context("Failed to execute get_pending_sequels query")?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .context("Failed to collect sequels")?;
+
+    Ok(sequels)
+}
+
+/// Stop suggesting a sequel
+pub fn dismiss_sequel(conn: &Connection, id: u32) -> Result<()> {
+    conn.execute("UPDATE sequels SET dismissed = 1 WHERE id = ?1", [id])
+        .context("Failed to dismiss sequel")?;
+
+    Ok(())
+}
+
+fn row_to_sequel(row: &rusqlite::Row) -> rusqlite::Result<Sequel> {
+    Ok(Sequel {
+        id: row.get(0)?,
+        prequel_id: row.get(1)?,
+        title: row.get(2)?,
+        airing: row.get(3)?,
+        dismissed: row.get::<_, i32>(4)? != 0,
+        detected_at: row.get(5)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{insert_show, models::Show, schema::init_database};
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let show = Show {
+            id: 1,
+            title: "Prequel".to_string(),
+            ..Default::default()
+        };
+        insert_show(&conn, &show).unwrap();
+        conn
+    }
+
+    fn sequel(id: u32, title: &str) -> Sequel {
+        Sequel {
+            id,
+            prequel_id: 1,
+            title: title.to_string(),
+            airing: Some("Spring 2025".to_string()),
+            dismissed: false,
+            detected_at: None,
+        }
+    }
+
+    #[test]
+    fn test_record_sequel() {
+        let conn = setup_test_db();
+
+        assert!(record_sequel(&conn, &sequel(2, "Prequel 2nd Season")).unwrap());
+        assert!(!record_sequel(&conn, &sequel(2, "Prequel Season 2")).unwrap());
+
+        let stored = get_sequel(&conn, 2).unwrap().unwrap();
+        assert_eq!(stored.title, "Prequel Season 2");
+        assert_eq!(stored.airing.as_deref(), Some("Spring 2025"));
+    }
+
+    #[test]
+    fn test_pending_sequels_skip_dismissed_and_tracked() {
+        let conn = setup_test_db();
+        record_sequel(&conn, &sequel(2, "Second")).unwrap();
+        record_sequel(&conn, &sequel(3, "Third")).unwrap();
+        record_sequel(&conn, &sequel(4, "Fourth")).unwrap();
+
+        dismiss_sequel(&conn, 3).unwrap();
+        let show = Show {
+            id: 4,
+            title: "Fourth".to_string(),
+            ..Default::default()
+        };
+        insert_show(&conn, &show).unwrap();
+
+        let pending: Vec<u32> = get_pending_sequels(&conn).unwrap().iter().map(|s| s.id).collect();
+        assert_eq!(pending, vec![2]);
+    }
+}
@@ -3,13 +3,38 @@ use rusqlite::{params, Connection, OptionalExtension};
 
 use super::models::Show;
 
+/// First ID of shows added by hand, which have no AniList entry
+///
+/// AniList media IDs are far below this, so the two ID spaces never collide.
+pub const MANUAL_SHOW_ID_START: u32 = 1_000_000_000;
+
+/// Whether a show ID belongs to a show added by hand
+pub fn is_manual_show_id(id: u32) -> bool {
+    id >= MANUAL_SHOW_ID_START
+}
+
+/// Next free ID for a show added by hand
+pub fn next_manual_show_id(conn: &Connection) -> Result<u32> {
+    let max: Option<u32> = conn
+        .query_row(
+            "SELECT MAX(id) FROM shows WHERE id >= ?1",
+            [MANUAL_SHOW_ID_START],
+            |row| row.get(0),
+        )
+        .context("Failed to get next manual show id")?;
+
+    Ok(max.map_or(MANUAL_SHOW_ID_START, |id| id + 1))
+}
+
 /// Get all shows from the database
 pub fn get_all_shows(conn: &Connection) -> Result<Vec<Show>> {
     let mut stmt = conn
         .prepare(
             "SELECT id, title, alternate, season, source, quality, download_path,
                     last_downloaded_episode, last_downloaded_hash, is_tracked,
-                    latest_episode, next_air_date, created_at, updated_at
+                    latest_episode, next_air_date, created_at, updated_at,
+                    nyaa_category, language, query_template, nyaa_user_feed,
+                    skip_anilist_progress
              FROM shows
              ORDER BY title",
         )
@@ -32,6 +57,11 @@ pub fn get_all_shows(conn: &Connection) -> Result<Vec<Show>> {
                 next_air_date: row.get(11)?,
                 created_at: row.get(12)?,
                 updated_at: row.get(13)?,
+                nyaa_category: row.get(14)?,
+                language: row.get(15)?,
+                query_template: row.get(16)?,
+                nyaa_user_feed: row.get::<_, i32>(17)? != 0,
+                skip_anilist_progress: row.get::<_, i32>(18)? != 0,
             })
         })
         .context("Failed to execute get_all_shows query")?
@@ -47,7 +77,9 @@ pub fn get_tracked_shows(conn: &Connection) -> Result<Vec<Show>> {
         .prepare(
             "SELECT id, title, alternate, season, source, quality, download_path,
                     last_downloaded_episode, last_downloaded_hash, is_tracked,
-                    latest_episode, next_air_date, created_at, updated_at
+                    latest_episode, next_air_date, created_at, updated_at,
+                    nyaa_category, language, query_template, nyaa_user_feed,
+                    skip_anilist_progress
              FROM shows
              WHERE is_tracked = 1
              ORDER BY title",
@@ -71,6 +103,11 @@ pub fn get_tracked_shows(conn: &Connection) -> Result<Vec<Show>> {
                 next_air_date: row.get(11)?,
                 created_at: row.get(12)?,
                 updated_at: row.get(13)?,
+                nyaa_category: row.get(14)?,
+                language: row.get(15)?,
+                query_template: row.get(16)?,
+                nyaa_user_feed: row.get::<_, i32>(17)? != 0,
+                skip_anilist_progress: row.get::<_, i32>(18)? != 0,
             })
         })
         .context("Failed to execute get_tracked_shows query")?
@@ -86,7 +123,9 @@ pub fn get_show(conn: &Connection, id: u32) -> Result<Option<Show>> {
         .prepare(
             "SELECT id, title, alternate, season, source, quality, download_path,
                     last_downloaded_episode, last_downloaded_hash, is_tracked,
-                    latest_episode, next_air_date, created_at, updated_at
+                    latest_episode, next_air_date, created_at, updated_at,
+                    nyaa_category, language, query_template, nyaa_user_feed,
+                    skip_anilist_progress
              FROM shows
              WHERE id = ?1",
         )
@@ -109,6 +148,11 @@ pub fn get_show(conn: &Connection, id: u32) -> Result<Option<Show>> {
                 next_air_date: row.get(11)?,
                 created_at: row.get(12)?,
                 updated_at: row.get(13)?,
+                nyaa_category: row.get(14)?,
+                language: row.get(15)?,
+                query_template: row.get(16)?,
+                nyaa_user_feed: row.get::<_, i32>(17)? != 0,
+                skip_anilist_progress: row.get::<_, i32>(18)? != 0,
             })
         })
         .optional()
@@ -122,8 +166,9 @@ pub fn insert_show(conn: &Connection, show: &Show) -> Result<()> {
     conn.execute(
         "INSERT INTO shows (id, title, alternate, season, source, quality, download_path,
                            last_downloaded_episode, last_downloaded_hash, is_tracked,
-                           latest_episode, next_air_date)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                           latest_episode, next_air_date, nyaa_category, language,
+                           query_template, nyaa_user_feed, skip_anilist_progress)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
         params![
             show.id,
             show.title,
@@ -137,6 +182,11 @@ pub fn insert_show(conn: &Connection, show: &Show) -> Result<()> {
             show.is_tracked as i32,
             show.latest_episode,
             show.next_air_date,
+            show.nyaa_category,
+            show.language,
+            show.query_template,
+            show.nyaa_user_feed as i32,
+            show.skip_anilist_progress as i32,
         ],
     )
     .context("Failed to insert show")?;
@@ -159,6 +209,11 @@ pub fn update_show(conn: &Connection, show: &Show) -> Result<()> {
             is_tracked = ?10,
             latest_episode = ?11,
             next_air_date = ?12,
+            nyaa_category = ?13,
+            language = ?14,
+            query_template = ?15,
+            nyaa_user_feed = ?16,
+            skip_anilist_progress = ?17,
             updated_at = datetime('now')
          WHERE id = ?1",
         params![
@@ -174,6 +229,11 @@ pub fn update_show(conn: &Connection, show: &Show) -> Result<()> {
             show.is_tracked as i32,
             show.latest_episode,
             show.next_air_date,
+            show.nyaa_category,
+            show.language,
+            show.query_template,
+            show.nyaa_user_feed as i32,
+            show.skip_anilist_progress as i32,
         ],
     )
     .context("Failed to update show")?;
@@ -235,11 +295,21 @@ mod tests {
             next_air_date: Some("2024-01-15".to_string()),
             created_at: None,
             updated_at: None,
+            nyaa_category: Some("1_3".to_string()),
+            language: Some("es".to_string()),
+            query_template: Some("{group} {alternate} -batch".to_string()),
+            nyaa_user_feed: true,
+            skip_anilist_progress: true,
         };
 
         insert_show(&conn, &show).unwrap();
 
         let retrieved = get_show(&conn, 12345).unwrap().unwrap();
+        assert_eq!(retrieved.nyaa_category.as_deref(), Some("1_3"));
+        assert_eq!(retrieved.language.as_deref(), Some("es"));
+        assert_eq!(retrieved.query_template.as_deref(), Some("{group} {alternate} -batch"));
+        assert!(retrieved.nyaa_user_feed);
+        assert!(retrieved.skip_anilist_progress);
         assert_eq!(retrieved.title, "Test Anime");
         assert_eq!(retrieved.alternate, "Test Anime Alt");
         assert!(retrieved.is_tracked);
@@ -309,6 +379,21 @@ mod tests {
         assert!(get_show(&conn, 1).unwrap().is_none());
     }
 
+    #[test]
+    fn test_next_manual_show_id() {
+        let conn = setup_test_db();
+
+        // AniList shows don't take up manual IDs
+        insert_show(&conn, &Show { id: 154587, ..Default::default() }).unwrap();
+        let first = next_manual_show_id(&conn).unwrap();
+        assert_eq!(first, MANUAL_SHOW_ID_START);
+        assert!(is_manual_show_id(first));
+        assert!(!is_manual_show_id(154587));
+
+        insert_show(&conn, &Show { id: first, ..Default::default() }).unwrap();
+        assert_eq!(next_manual_show_id(&conn).unwrap(), first + 1);
+    }
+
     #[test]
     fn test_update_last_downloaded() {
         let conn = setup_test_db();
@@ -0,0 +1,108 @@
+//! Cron schedules the tracker syncs on
+//!
+//! Expressions are stored as entered; they're validated before being saved and
+//! parsed again by the scheduler.
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+/// A cron expression the tracker syncs all shows on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncSchedule {
+    pub id: i64,
+    /// Five-field (minute hour day month weekday) or six-field (with seconds) cron expression
+    pub expression: String,
+    pub enabled: bool,
+    pub created_at: Option<String>,
+}
+
+/// Add a sync schedule, returning its ID
+pub fn create_sync_schedule(conn: &Connection, expression: &str) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO sync_schedules (expression) VALUES (?1)",
+        params![expression.trim()],
+    )
+    .context("Failed to create sync schedule")?;
+
+    Ok(conn.last_insert_rowid())
+}
+
+/// Get all sync schedules in the order they were added
+pub fn get_sync_schedules(conn: &Connection) -> Result<Vec<SyncSchedule>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, expression, enabled, created_at
+             FROM sync_schedules
+             ORDER BY id",
+        )
+        .context("Failed to prepare get_sync_schedules query")?;
+
+    let schedules = stmt
+        .query_map([], |row| {
+            Ok(SyncSchedule {
+                id: row.get(0)?,
+                expression: row.get(1)?,
+                enabled: row.get::<_, i32>(2)? != 0,
+                created_at: row.get(3)?,
+            })
+        })
+        .context("Failed to query sync schedules")?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .context("Failed to read sync schedules")?;
+
+    Ok(schedules)
+}
+
+/// Delete a sync schedule
+pub fn delete_sync_schedule(conn: &Connection, id: i64) -> Result<()> {
+    conn.execute("DELETE FROM sync_schedules WHERE id = ?1", [id])
+        .context("Failed to delete sync schedule")?;
+
+    Ok(())
+}
+
+/// Enable a sync schedule if it's disabled and vice versa
+pub fn toggle_sync_schedule(conn: &Connection, id: i64) -> Result<()> {
+    conn.execute(
+        "UPDATE sync_schedules SET enabled = 1 - enabled WHERE id = ?1",
+        [id],
+    )
+    .context("Failed to toggle sync schedule")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::schema::init_database;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_sync_schedule_lifecycle() {
+        let conn = setup_test_db();
+        assert!(get_sync_schedules(&conn).unwrap().is_empty());
+
+        let first = create_sync_schedule(&conn, " 0 5,17 * * * ").unwrap();
+        let second = create_sync_schedule(&conn, "30 */2 * * *").unwrap();
+
+        let schedules = get_sync_schedules(&conn).unwrap();
+        assert_eq!(schedules.len(), 2);
+        assert_eq!(schedules[0].expression, "0 5,17 * * *");
+        assert!(schedules[0].enabled);
+
+        toggle_sync_schedule(&conn, first).unwrap();
+        assert!(!get_sync_schedules(&conn).unwrap()[0].enabled);
+
+        delete_sync_schedule(&conn, second).unwrap();
+        let schedules = get_sync_schedules(&conn).unwrap();
+        assert_eq!(schedules.len(), 1);
+        assert_eq!(schedules[0].id, first);
+    }
+}
@@ -0,0 +1,227 @@
+//! Magnet tracker list database operations
+//!
+//! Announce URLs appended to constructed magnet links so Transmission doesn't have
+//! to rely on DHT alone. Trackers without a source form the default list; trackers
+//! tied to a fansub source replace the defaults for that source's releases.
+
+use anyhow::{Context, Result};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+/// Public trackers that carry most anime releases, seeded on first run
+pub const DEFAULT_TRACKERS: &[&str] = &[
+    "http://nyaa.tracker.wf:7777/announce",
+    "udp://open.stealth.si:80/announce",
+    "udp://tracker.opentrackr.org:1337/announce",
+    "udp://exodus.desync.com:6969/announce",
+    "udp://tracker.torrent.eu.org:451/announce",
+];
+
+/// An announce URL in the database
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MagnetTracker {
+    pub id: u32,
+    pub url: String,
+    /// Fansub source this tracker applies to; None for the default list
+    pub source: Option<String>,
+    pub enabled: bool,
+}
+
+/// Input for adding a tracker
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateMagnetTracker {
+    pub url: String,
+    pub source: Option<String>,
+}
+
+/// Get all trackers, defaults first
+pub fn get_all_trackers(conn: &Connection) -> Result<Vec<MagnetTracker>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, url, source, enabled
+             FROM magnet_trackers
+             ORDER BY source IS NOT NULL, source, id",
+        )
+        .context("Failed to prepare get_all_trackers statement")?;
+
+    let trackers = stmt
+        .query_map([], |row| {
+            Ok(MagnetTracker {
+                id: row.get(0)?,
+                url: row.get(1)?,
+                source: row.get(2)?,
+                enabled: row.get::<_, i32>(3)? != 0,
+            })
+        })
+        .context("Failed to query trackers")?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .context("Failed to read tracker rows")?;
+
+    Ok(trackers)
+}
+
+/// Get the announce URLs to use for a release from the given source
+///
+/// If the source has any enabled trackers of its own, those are used instead of the
+/// default list. Source names are compared case-insensitively.
+pub fn get_trackers_for_source(conn: &Connection, source: &str) -> Result<Vec<String>> {
+    let enabled_urls = |sql: &str, params: &[&dyn rusqlite::ToSql]| -> Result<Vec<String>> {
+        let mut stmt = conn
+            .prepare(sql)
+            .context("Failed to prepare tracker lookup")?;
+        let urls = stmt
+            .query_map(params, |row| row.get(0))
+            .context("Failed to query trackers")?
+            .collect::<std::result::Result<Vec<String>, _>>()
+            .context("Failed to read tracker urls")?;
+        Ok(urls)
+    };
+
+    let overrides = enabled_urls(
+        "SELECT url FROM magnet_trackers
+         WHERE enabled = 1 AND source = ?1 COLLATE NOCASE
+         ORDER BY id",
+        &[&source],
+    )?;
+    if !overrides.is_empty() {
+        return Ok(overrides);
+    }
+
+    enabled_urls(
+        "SELECT url FROM magnet_trackers
+         WHERE enabled = 1 AND source IS NULL
+         ORDER BY id",
+        &[],
+    )
+}
+
+/// Add a tracker, returning its ID
+pub fn create_tracker(conn: &Connection, tracker: &CreateMagnetTracker) -> Result<u32> {
+    // An empty source from a form means "default list"
+    let source = tracker
+        .source
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+
+    conn.execute(
+        "INSERT INTO magnet_trackers (url, source, enabled) VALUES (?1, ?2, 1)",
+        rusqlite::params![tracker.url.trim(), source],
+    )
+    .context("Failed to insert tracker")?;
+
+    Ok(conn.last_insert_rowid() as u32)
+}
+
+/// Delete a tracker
+pub fn delete_tracker(conn: &Connection, id: u32) -> Result<bool> {
+    let rows_affected = conn
+        .execute("DELETE FROM magnet_trackers WHERE id = ?1", [id])
+        .context("Failed to delete tracker")?;
+
+    Ok(rows_affected > 0)
+}
+
+/// Toggle a tracker's enabled status
+pub fn toggle_tracker(conn: &Connection, id: u32) -> Result<bool> {
+    let rows_affected = conn
+        .execute(
+            "UPDATE magnet_trackers SET enabled = NOT enabled WHERE id = ?1",
+            [id],
+        )
+        .context("Failed to toggle tracker")?;
+
+    Ok(rows_affected > 0)
+}
+
+/// Seed the default tracker list if the table is empty
+pub(crate) fn seed_default_trackers(conn: &Connection) -> Result<()> {
+    let count: i32 = conn
+        .query_row("SELECT COUNT(*) FROM magnet_trackers", [], |row| row.get(0))
+        .unwrap_or(0);
+
+    if count > 0 {
+        return Ok(());
+    }
+
+    for url in DEFAULT_TRACKERS {
+        conn.execute(
+            "INSERT INTO magnet_trackers (url, source, enabled) VALUES (?1, NULL, 1)",
+            [url],
+        )
+        .with_context(|| format!("Failed to insert default tracker: {}", url))?;
+    }
+
+    tracing::info!("Seeded {} default magnet trackers", DEFAULT_TRACKERS.len());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::schema::init_database;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_defaults_seeded() {
+        let conn = setup_test_db();
+
+        let urls = get_trackers_for_source(&conn, "subsplease").unwrap();
+        assert_eq!(urls, DEFAULT_TRACKERS);
+
+        // Seeding again must not duplicate the list
+        seed_default_trackers(&conn).unwrap();
+        assert_eq!(get_all_trackers(&conn).unwrap().len(), DEFAULT_TRACKERS.len());
+    }
+
+    #[test]
+    fn test_user_addition_extends_defaults() {
+        let conn = setup_test_db();
+
+        create_tracker(
+            &conn,
+            &CreateMagnetTracker {
+                url: "udp://tracker.example.org:1337/announce".to_string(),
+                source: Some("".to_string()),
+            },
+        )
+        .unwrap();
+
+        let urls = get_trackers_for_source(&conn, "subsplease").unwrap();
+        assert_eq!(urls.len(), DEFAULT_TRACKERS.len() + 1);
+        assert_eq!(urls.last().unwrap(), "udp://tracker.example.org:1337/announce");
+    }
+
+    #[test]
+    fn test_source_override_replaces_defaults() {
+        let conn = setup_test_db();
+
+        let id = create_tracker(
+            &conn,
+            &CreateMagnetTracker {
+                url: "http://erai.example/announce".to_string(),
+                source: Some("Erai-raws".to_string()),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            get_trackers_for_source(&conn, "erai-raws").unwrap(),
+            vec!["http://erai.example/announce"]
+        );
+        assert_eq!(get_trackers_for_source(&conn, "subsplease").unwrap(), DEFAULT_TRACKERS);
+
+        // Disabling the only override falls back to the defaults
+        assert!(toggle_tracker(&conn, id).unwrap());
+        assert_eq!(get_trackers_for_source(&conn, "Erai-raws").unwrap(), DEFAULT_TRACKERS);
+
+        assert!(delete_tracker(&conn, id).unwrap());
+        assert!(!delete_tracker(&conn, id).unwrap());
+    }
+}
@@ -11,22 +11,37 @@ use axum::{
     Router,
 };
 use pages::{
+    activity::{activity_stream, get_activity},
+    anilist_list::{anilist_list, import_anilist_list, run_watchlist_sync, save_anilist_account},
     anime::seasonal_anime,
+    manual_shows::{create_manual_show, manual_show_form},
+    sync_schedules::{
+        create_sync_schedule, delete_sync_schedule, get_sync_schedules, save_quiet_hours,
+        toggle_sync_schedule,
+    },
+    sequels::{
+        check_sequels_now, dismiss_sequel, get_sequels, run_sequel_check, set_auto_track_sequels,
+        track_sequel_handler,
+    },
     home::{
-        clear_transmission, close, confirm_match, create_filter, create_show_filter,
-        currently_airing_anime, delete_filter, delete_show_filter, download_from_link,
-        get_configuration, get_filters, get_rss_config, get_show_filters, get_source,
-        navigate_season_bar, navigate_seasonal_anime, save_configuration, save_rss_config,
-        search_matches, search_source,
-        set_tracker, show_table, skip_match_selection, sync_now, toggle_filter, update_filter,
-        update_user, view, UserState,
+        clear_transmission, close, confirm_match, cover_image, create_fansub_group, create_filter,
+        create_show_alias, create_show_filter, create_tracker, currently_airing_anime,
+        delete_fansub_group, delete_filter, delete_show_alias, delete_show_filter,
+        delete_tracker, download_from_link,
+        get_configuration, get_fansub_groups, get_filters, get_rss_config, get_show_filters,
+        get_source, get_trackers, manage_fansub_groups, release_delay_stats,
+        navigate_season_bar, navigate_seasonal_anime, open_on_provider, readd_download, save_configuration,
+        save_rss_config, search_anime, search_matches, search_source,
+        set_metadata_provider, set_tracker, show_aliases, show_history, show_table, skip_match_selection, sync_now, sync_job_progress, cancel_sync_job,
+        test_query, test_show_alias, toggle_filter, toggle_tracker, torrent_details, update_fansub_group,
+        update_filter, update_user, view, UserState,
     },
 };
 use scraper::tracker::run_tracker;
 use tokio::net::TcpListener;
 use tower_http::services::ServeDir;
 use tracing::info;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, Layer};
 
 use crate::pages::home::read_tracked_shows;
 
@@ -34,10 +49,13 @@ use crate::pages::home::read_tracked_shows;
 async fn main() -> anyhow::Result<()> {
     tracing_subscriber::registry()
         .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "axum_static_web_werver=debug".into()),
+            tracing_subscriber::fmt::layer().with_filter(
+                tracing_subscriber::EnvFilter::try_from_default_env()
+                    .unwrap_or_else(|_| "axum_static_web_werver=debug".into()),
+            ),
         )
-        .with(tracing_subscriber::fmt::layer())
+        // The activity panel gets the tracker's output whatever RUST_LOG says
+        .with(scraper::activity::ActivityLayer.with_filter(scraper::activity::log_filter()))
         .init();
 
     info!("initializing router and assets");
@@ -53,6 +71,11 @@ async fn main() -> anyhow::Result<()> {
 
     info!("database initialized successfully");
 
+    // Load the fansub group registry used for group detection
+    scraper::fansub::reload_groups()
+        .await
+        .context("Failed to load fansub groups")?;
+
     // Use port env if available
     let port = std::env::var("PORT").unwrap_or_else(|_| "42069".to_string());
     let port = port
@@ -72,6 +95,8 @@ async fn main() -> anyhow::Result<()> {
     };
 
     tokio::spawn(run_tracker());
+    tokio::spawn(run_watchlist_sync(state.user.clone()));
+    tokio::spawn(run_sequel_check(state.user.clone()));
 
     let listener = TcpListener::bind(&addr).await.context("failed to bind TCP listener")?;
     axum::serve(listener, router(state)?)
@@ -96,6 +121,7 @@ fn api_router(state: AppState) -> Router {
         .route("/download_from_link", post(download_from_link))
         .route("/search_source", post(search_source))
         .route("/save_configuration", post(save_configuration))
+        .route("/test_query", get(test_query))
         .route(
             "/show_table",
             get(show_table).with_state(state.user.clone()),
@@ -105,6 +131,14 @@ fn api_router(state: AppState) -> Router {
             get(navigate_seasonal_anime).with_state(state.user.clone()),
         )
         .route("/navigate_season_bar", get(navigate_season_bar))
+        .route(
+            "/search_anime",
+            get(search_anime).with_state(state.user.clone()),
+        )
+        .route(
+            "/metadata_provider",
+            post(set_metadata_provider).with_state(state.user.clone()),
+        )
         .route(
             "/currently_airing",
             get(currently_airing_anime).with_state(state.user.clone()),
@@ -121,11 +155,23 @@ fn api_router(state: AppState) -> Router {
             "/rss_config",
             get(get_rss_config).post(save_rss_config),
         )
+        .route(
+            "/anilist_list",
+            get(anilist_list)
+                .post(import_anilist_list)
+                .with_state(state.user.clone()),
+        )
+        .route("/anilist_account", post(save_anilist_account))
         .route("/anime", get(seasonal_anime))
         .route("/close", get(close))
         .route("/sync_now", post(sync_now))
+        .route("/sync_jobs/:id", get(sync_job_progress))
+        .route("/sync_jobs/:id/cancel", post(cancel_sync_job))
+        .route("/activity", get(get_activity))
+        .route("/activity/stream", get(activity_stream))
         .route("/clear_transmission", post(clear_transmission))
         .route("/search_matches", get(search_matches))
+        .route("/torrent_details", get(torrent_details))
         .route(
             "/confirm_match",
             post(confirm_match).with_state(state.user.clone()),
@@ -150,6 +196,63 @@ fn api_router(state: AppState) -> Router {
             "/shows/:show_id/filters/:filter_id",
             delete(delete_show_filter),
         )
+        // Magnet tracker routes
+        .route("/trackers", get(get_trackers).post(create_tracker))
+        .route("/trackers/:id", delete(delete_tracker))
+        .route("/trackers/:id/toggle", post(toggle_tracker))
+        // Fansub group registry routes
+        .route(
+            "/fansub_groups",
+            get(get_fansub_groups).post(create_fansub_group),
+        )
+        .route("/fansub_groups/manage", get(manage_fansub_groups))
+        .route(
+            "/fansub_groups/:id",
+            put(update_fansub_group).delete(delete_fansub_group),
+        )
+        // Show alias routes
+        .route(
+            "/shows/:show_id/aliases",
+            get(show_aliases).post(create_show_alias),
+        )
+        .route(
+            "/shows/:show_id/aliases/:alias_id",
+            delete(delete_show_alias),
+        )
+        .route(
+            "/shows/:show_id/aliases/:alias_id/test",
+            get(test_show_alias),
+        )
+        // Sequel routes
+        .route("/sequels", get(get_sequels))
+        .route(
+            "/sequels/check",
+            post(check_sequels_now).with_state(state.user.clone()),
+        )
+        .route("/sequels/auto_track", post(set_auto_track_sequels))
+        .route(
+            "/sequels/:id/track",
+            post(track_sequel_handler).with_state(state.user.clone()),
+        )
+        .route("/sequels/:id/dismiss", post(dismiss_sequel))
+        // Sync schedule routes
+        .route(
+            "/sync_schedules",
+            get(get_sync_schedules).post(create_sync_schedule),
+        )
+        .route("/sync_schedules/:id", delete(delete_sync_schedule))
+        .route("/sync_schedules/:id/toggle", post(toggle_sync_schedule))
+        .route("/quiet_hours", post(save_quiet_hours))
+        // Shows added by hand
+        .route(
+            "/manual_shows",
+            get(manual_show_form).post(create_manual_show).with_state(state.user.clone()),
+        )
+        // Download history routes
+        .route("/shows/:show_id/history", get(show_history))
+        .route("/shows/:show_id/on/:provider", get(open_on_provider))
+        .route("/stats/release_delays", get(release_delay_stats))
+        .route("/history/:id/readd", post(readd_download))
 }
 
 fn router(state: AppState) -> anyhow::Result<Router> {
@@ -161,6 +264,7 @@ fn router(state: AppState) -> anyhow::Result<Router> {
 
     Ok(Router::new()
         .route("/", get(view).with_state(state.user.clone()))
+        .route("/assets/covers/:id", get(cover_image))
         .nest(
             "/api",
             api_router(AppState {
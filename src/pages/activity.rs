@@ -0,0 +1,82 @@
+//! Live tracker activity panel
+//!
+//! The panel renders the recent history, then htmx's SSE extension prepends each
+//! new event as the server pushes it.
+
+use std::convert::Infallible;
+
+use askama::Template;
+use axum::response::{
+    sse::{Event, KeepAlive, Sse},
+    IntoResponse,
+};
+use chrono::{Local, TimeZone};
+use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
+
+use crate::{
+    pages::HtmlTemplate,
+    scraper::activity::{self, ActivityEvent, ActivityKind},
+};
+
+#[derive(Template)]
+#[template(path = "components/activity.html")]
+pub struct ActivityTemplate {
+    /// Rendered events, newest first
+    pub rows: Vec<String>,
+}
+
+#[derive(Template)]
+#[template(path = "components/activity_event.html")]
+pub struct ActivityEventTemplate {
+    pub event: ActivityEvent,
+}
+
+impl ActivityEventTemplate {
+    /// Local time of the event
+    pub fn time(&self) -> String {
+        Local
+            .timestamp_opt(self.event.at, 0)
+            .single()
+            .map(|at| at.format("%H:%M:%S").to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn color(&self) -> &'static str {
+        match self.event.kind {
+            ActivityKind::Feed => "text-blue-400",
+            ActivityKind::Filter => "text-gray-400",
+            ActivityKind::Grab => "text-green-400",
+            ActivityKind::Error => "text-red-400",
+            ActivityKind::Warning => "text-orange-400",
+            ActivityKind::Info => "text-gray-500",
+        }
+    }
+}
+
+fn render_event(event: ActivityEvent) -> String {
+    ActivityEventTemplate { event }.render().unwrap_or_else(|err| {
+        eprintln!("Failed to render activity event: {:?}", err);
+        String::new()
+    })
+}
+
+/// The activity panel with recent events
+#[axum::debug_handler]
+pub async fn get_activity() -> impl IntoResponse {
+    let rows = activity::recent().into_iter().map(render_event).collect();
+    HtmlTemplate::new(ActivityTemplate { rows })
+}
+
+/// Stream new events as rendered rows
+///
+/// Events missed by a slow client are skipped rather than ending the stream.
+#[axum::debug_handler]
+pub async fn activity_stream() -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let events = BroadcastStream::new(activity::subscribe()).filter_map(|event| {
+        event
+            .ok()
+            .map(|event| Ok(Event::default().event("activity").data(render_event(event))))
+    });
+
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
@@ -0,0 +1,395 @@
+//! Import of an AniList watchlist, and the AniList account settings
+//!
+//! Airing and upcoming shows on the user's CURRENT and PLANNING lists are tracked,
+//! matched against releases the same way `set_tracker` does. Shows tracked this way
+//! are untracked again once they leave the list, and tracked again if they come
+//! back. Shows tracked by hand are left alone, and shows the user untracked stay
+//! untracked while still on the list.
+
+use askama::Template;
+use axum::{extract::State, response::IntoResponse, Form};
+use serde::Deserialize;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::sync::Mutex;
+
+use super::home::{
+    find_confident_match, get_next_airing_episode, match_threshold, nyaa_category,
+    seed_show_aliases, TableEntry, UserState,
+};
+use crate::{
+    db::{
+        self,
+        models::{RssConfig, Show},
+    },
+    pages::HtmlTemplate,
+    scraper::{
+        anilist::{get_user_watchlist, ListEntry},
+        season_parser::detect_season,
+    },
+};
+
+/// How often the watchlist is re-synced when periodic sync is on
+const LIST_SYNC_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// What a watchlist sync changed
+#[derive(Debug, Default)]
+pub struct ListSyncReport {
+    /// Newly tracked shows with a confident release match, and shows back on the list
+    pub matched: Vec<String>,
+    /// Newly tracked shows searched under their AniList title, worth checking
+    pub unmatched: Vec<String>,
+    /// Shows untracked because they left the list
+    pub untracked: Vec<String>,
+}
+
+impl ListSyncReport {
+    pub fn is_empty(&self) -> bool {
+        self.matched.is_empty()
+            && self.unmatched.is_empty()
+            && self.untracked.is_empty()
+    }
+}
+
+/// Track the airing shows on a user's watchlist and untrack the ones that left it
+pub async fn sync_watchlist(
+    state: &Arc<Mutex<UserState>>,
+    username: &str,
+) -> anyhow::Result<ListSyncReport> {
+    let entries = get_user_watchlist(username).await?;
+    apply_watchlist(state, &entries).await
+}
+
+/// Bring the tracked shows in line with the entries of a watchlist
+async fn apply_watchlist(
+    state: &Arc<Mutex<UserState>>,
+    entries: &[ListEntry],
+) -> anyhow::Result<ListSyncReport> {
+    let previous: HashMap<u32, String> = db::with_db(db::get_list_shows)
+        .await?
+        .into_iter()
+        .collect();
+    let is_dropped = |id: &u32| previous.get(id).is_some_and(|status| status == db::anilist_list::DROPPED);
+    let threshold = match_threshold().await;
+    let mut report = ListSyncReport::default();
+
+    for entry in entries.iter().filter(|e| e.media.is_airing()) {
+        let Some(show_id) = entry.media.id else {
+            continue;
+        };
+        let title = entry
+            .media
+            .title
+            .as_ref()
+            .and_then(|t| t.romaji.clone().or_else(|| t.english.clone()))
+            .unwrap_or_else(|| show_id.to_string());
+        let (latest_episode, next_air_date) =
+            get_next_airing_episode(&entry.media.next_airing_episode);
+
+        match db::with_db(move |conn| db::get_show(conn, show_id)).await? {
+            // Tracked by hand; only keep the status of shows the list already owns
+            Some(show) if show.is_tracked => {
+                if !previous.contains_key(&show_id) {
+                    continue;
+                }
+            }
+            // Untracked by the list when it left, and now back on it
+            Some(mut show) if is_dropped(&show_id) => {
+                show.is_tracked = true;
+                db::with_db(move |conn| db::update_show(conn, &show)).await?;
+                report.matched.push(title.clone());
+            }
+            // Untracked by hand, or known from elsewhere; not the list's to track
+            Some(_) => continue,
+            None => {
+                let category = nyaa_category(None).await;
+                let matched = find_confident_match(&title, &category, threshold).await;
+                let (alternate, source) = matched
+                    .clone()
+                    .unwrap_or_else(|| (title.clone(), Show::default().source));
+
+                let show = Show {
+                    id: show_id,
+                    title: title.clone(),
+                    season: detect_season(&alternate).season,
+                    alternate,
+                    source,
+                    latest_episode: Some(latest_episode.clone()),
+                    next_air_date: Some(next_air_date.clone()),
+                    ..Default::default()
+                };
+                db::with_db(move |conn| db::insert_show(conn, &show)).await?;
+
+                let matched_title = matched.map(|(alternate, _)| alternate);
+                if matched_title.is_some() {
+                    report.matched.push(title.clone());
+                } else {
+                    report.unmatched.push(title.clone());
+                }
+                seed_show_aliases(show_id, matched_title).await;
+            }
+        }
+
+        let status = entry.status.clone();
+        db::with_db(move |conn| db::mark_list_show(conn, show_id, &status)).await?;
+        state.lock().await.tracker.insert(
+            show_id,
+            TableEntry {
+                title,
+                latest_episode,
+                next_air_date,
+                is_tracked: true,
+                id: show_id,
+            },
+        );
+    }
+
+    // Finished shows stay on the list for a while; only leaving the list untracks
+    let on_list: HashSet<u32> = entries.iter().filter_map(|e| e.media.id).collect();
+    let left: Vec<u32> = previous
+        .keys()
+        .filter(|id| !on_list.contains(id) && !is_dropped(id))
+        .copied()
+        .collect();
+    for show_id in left {
+        let untracked = db::with_db(move |conn| {
+            db::drop_list_show(conn, show_id)?;
+            let Some(mut show) = db::get_show(conn, show_id)?.filter(|s| s.is_tracked) else {
+                return Ok(None);
+            };
+            show.is_tracked = false;
+            db::update_show(conn, &show)?;
+            Ok(Some(show.title))
+        })
+        .await?;
+
+        state.lock().await.tracker.remove(&show_id);
+        report.untracked.extend(untracked);
+    }
+
+    Ok(report)
+}
+
+/// Re-sync the configured watchlist periodically, while periodic sync is on
+pub async fn run_watchlist_sync(state: Arc<Mutex<UserState>>) {
+    loop {
+        tokio::time::sleep(LIST_SYNC_INTERVAL).await;
+
+        let config = match db::with_db(db::get_rss_config).await {
+            Ok(config) => config,
+            Err(err) => {
+                tracing::error!("Failed to load AniList list settings: {:?}", err);
+                continue;
+            }
+        };
+        let Some(username) = config.anilist_username.filter(|_| config.anilist_list_sync) else {
+            continue;
+        };
+
+        match sync_watchlist(&state, &username).await {
+            Ok(report) => tracing::info!(
+                "AniList list sync for {}: {} tracked, {} untracked",
+                username,
+                report.matched.len() + report.unmatched.len(),
+                report.untracked.len()
+            ),
+            Err(err) => tracing::error!("AniList list sync for {} failed: {:?}", username, err),
+        }
+    }
+}
+
+#[derive(Template)]
+#[template(path = "components/anilist_list.html")]
+pub struct AniListListTemplate {
+    pub username: String,
+    pub sync: bool,
+    pub report: Option<ListSyncReport>,
+    pub error: Option<String>,
+    pub client_id: String,
+    pub has_token: bool,
+    pub push_progress: bool,
+    pub account_message: Option<String>,
+}
+
+impl AniListListTemplate {
+    fn new(config: RssConfig) -> Self {
+        Self {
+            username: config.anilist_username.unwrap_or_default(),
+            sync: config.anilist_list_sync,
+            report: None,
+            error: None,
+            client_id: config.anilist_client_id.unwrap_or_default(),
+            has_token: config.anilist_token.is_some(),
+            push_progress: config.anilist_push_progress,
+            account_message: None,
+        }
+    }
+
+    /// AniList page that authorizes this app and shows the access token
+    fn authorize_url(&self) -> String {
+        format!(
+            "https://anilist.co/api/v2/oauth/authorize?client_id={}&response_type=token",
+            urlencoding::encode(&self.client_id)
+        )
+    }
+}
+
+/// Current RSS config, or the defaults if it can't be loaded
+async fn load_config() -> RssConfig {
+    db::with_db(db::get_rss_config).await.unwrap_or_else(|err| {
+        eprintln!("Failed to load RSS config: {:?}", err);
+        RssConfig::default()
+    })
+}
+
+/// Form for importing a watchlist
+#[derive(Debug, Deserialize)]
+pub struct AniListListForm {
+    pub username: String,
+    #[serde(default)]
+    pub sync: bool,
+}
+
+/// Form for the AniList account used to push progress
+#[derive(Debug, Deserialize)]
+pub struct AniListAccountForm {
+    pub client_id: Option<String>,
+    pub token: Option<String>,
+    #[serde(default)]
+    pub push_progress: bool,
+}
+
+/// Show the AniList dialog
+#[axum::debug_handler]
+pub async fn anilist_list() -> impl IntoResponse {
+    HtmlTemplate::new(AniListListTemplate::new(load_config().await))
+}
+
+/// Save the AniList account and progress push setting
+#[axum::debug_handler]
+pub async fn save_anilist_account(Form(payload): Form<AniListAccountForm>) -> impl IntoResponse {
+    let result = db::with_db(move |conn| {
+        db::update_anilist_account(
+            conn,
+            payload.client_id.as_deref(),
+            payload.token.as_deref(),
+            payload.push_progress,
+        )
+    })
+    .await;
+
+    let mut template = AniListListTemplate::new(load_config().await);
+    template.account_message = Some(match result {
+        Ok(()) if template.push_progress && !template.has_token => {
+            "Saved, but progress can't be pushed without an access token".to_string()
+        }
+        Ok(()) => "Saved".to_string(),
+        Err(err) => {
+            eprintln!("Failed to save AniList account: {:?}", err);
+            format!("Failed to save: {}", err)
+        }
+    });
+
+    HtmlTemplate::new(template)
+}
+
+/// Save the watchlist settings and import the list right away
+#[axum::debug_handler]
+pub async fn import_anilist_list(
+    State(state): State<Arc<Mutex<UserState>>>,
+    Form(payload): Form<AniListListForm>,
+) -> impl IntoResponse {
+    let username = payload.username.trim().to_string();
+    let sync = payload.sync;
+
+    let saved_username = username.clone();
+    if let Err(err) = db::with_db(move |conn| {
+        db::update_anilist_list(conn, Some(&saved_username), sync)
+    })
+    .await
+    {
+        eprintln!("Failed to save AniList list settings: {:?}", err);
+    }
+
+    let (report, error) = if username.is_empty() {
+        (None, Some("Enter an AniList username".to_string()))
+    } else {
+        match sync_watchlist(&state, &username).await {
+            Ok(report) => (Some(report), None),
+            Err(err) => {
+                eprintln!("Failed to import AniList list for {}: {:?}", username, err);
+                (None, Some(err.to_string()))
+            }
+        }
+    };
+
+    let mut template = AniListListTemplate::new(load_config().await);
+    template.username = username;
+    template.sync = sync;
+    template.report = report;
+    template.error = error;
+
+    HtmlTemplate::new(template).with_header("HX-Trigger", "newTrackerStatus")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn tracked(id: u32) -> bool {
+        db::with_db(move |conn| db::get_show(conn, id))
+            .await
+            .unwrap()
+            .is_some_and(|show| show.is_tracked)
+    }
+
+    #[tokio::test]
+    async fn test_show_back_on_the_list_is_tracked_again() {
+        db::init_test_connection();
+        let state = Arc::new(Mutex::new(UserState::new(String::new(), HashMap::new())));
+        let (list_show, untracked_by_hand) = (1_000_000_700, 1_000_000_701);
+        db::with_db(move |conn| {
+            for id in [list_show, untracked_by_hand] {
+                let show = Show {
+                    id,
+                    title: format!("Show {}", id),
+                    is_tracked: id == list_show,
+                    ..Default::default()
+                };
+                db::insert_show(conn, &show)?;
+                db::mark_list_show(conn, id, "CURRENT")?;
+            }
+            Ok(())
+        })
+        .await
+        .unwrap();
+        let entries: Vec<ListEntry> = serde_json::from_str(&format!(
+            r#"[
+                {{"status": "CURRENT", "media": {{"id": {}, "status": "RELEASING"}}}},
+                {{"status": "CURRENT", "media": {{"id": {}, "status": "RELEASING"}}}}
+            ]"#,
+            list_show, untracked_by_hand
+        ))
+        .unwrap();
+
+        // Shows the user untracked stay untracked while on the list
+        let report = apply_watchlist(&state, &entries).await.unwrap();
+        assert!(report.is_empty());
+        assert!(tracked(list_show).await);
+        assert!(!tracked(untracked_by_hand).await);
+
+        // Dropped from the list
+        let report = apply_watchlist(&state, &entries[1..]).await.unwrap();
+        assert_eq!(report.untracked, vec![format!("Show {}", list_show)]);
+        assert!(!tracked(list_show).await);
+
+        // Added back
+        let report = apply_watchlist(&state, &entries).await.unwrap();
+        assert_eq!(report.matched.len(), 1);
+        assert!(tracked(list_show).await);
+        assert!(!tracked(untracked_by_hand).await);
+    }
+}
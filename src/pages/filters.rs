@@ -1,4 +1,4 @@
-use crate::scraper::{anilist::{AniShow, CoverImage, Season, Studio, Title}, nyaasi::Link};
+use crate::scraper::{anilist::{AniShow, Season, Studio, Title}, covers, nyaasi::Link};
 
 pub fn unwrap_or_na<T: std::fmt::Display>(value: &Option<T>) -> ::askama::Result<String> {
     Ok(value.as_ref().map_or("N/A".to_string(), |v| v.to_string()))
@@ -11,12 +11,11 @@ pub fn unwrap_title_romaji(title: &Option<Title>) -> ::askama::Result<String> {
         .map_or("N/A".to_string(), |v| v.to_string()))
 }
 
-pub fn unwrap_cover(cover: &Option<CoverImage>) -> ::askama::Result<String> {
-    Ok(cover
-       .as_ref()
-       .and_then(|c| c.large.as_ref())
-       .map_or("https://upload.wikimedia.org/wikipedia/commons/thumb/6/65/No-Image-Placeholder.svg/800px-No-Image-Placeholder.svg.png".into(), |v| v.to_string())
-        )
+/// Local URL of a show's cover thumbnail
+pub fn cover_path(id: &Option<u32>) -> ::askama::Result<String> {
+    Ok(id.map_or(covers::PLACEHOLDER_PATH.to_string(), |id| {
+        format!("/assets/covers/{}", id)
+    }))
 }
 
 pub fn unwrap_studio(studio: &Option<Studio>) -> ::askama::Result<String> {
@@ -89,3 +88,7 @@ pub fn short_season(season: &Season, year: &u16) -> ::askama::Result<String> {
     };
     Ok(format!("{}{}", code, year % 100))
 }
+
+pub fn human_size(bytes: &u64) -> ::askama::Result<String> {
+    Ok(crate::scraper::rss::format_size(*bytes))
+}
@@ -1,18 +1,41 @@
 use crate::{
-    db::{self, models::Show},
+    db::{
+        self,
+        models::{RssConfig, Show},
+        AliasOrigin, MetadataSource,
+    },
     pages::{filters, HtmlTemplate},
     scraper::{
-        anilist::{get_anilist_all_airing, get_anilist_data, AniShow, NextAiringEpisode, Season},
-        nyaasi::{fetch_sources, Link},
-        rss::{detect_fansub_source, fetch_rss_feed, parse_episode_info},
-        season_parser::detect_season,
-        transmission::{clear_all_torrents, upload_to_transmission_rpc},
+        anilist::{
+            get_anilist_all_airing_cached, get_anilist_data_cached, get_anilist_titles,
+            AniListResult, AniShow, NextAiringEpisode, Season,
+        },
+        language::LANGUAGES,
+        metadata::{self, AniListProvider, MetadataProvider, ProviderShow},
+        nyaasi::{
+            fetch_sources, fetch_torrent_details, is_nyaa_view_url, Link, TorrentDetails,
+            DEFAULT_CATEGORY, NYAA_CATEGORIES,
+        },
+        archive::load_torrent,
+        covers,
+        fansub,
+        rss::{
+            construct_magnet_url, detect_fansub_source, fetch_nyaa_rss, fetch_rss_feed,
+            parse_episode_info, NyaaSearch, RssItem,
+        },
+        schedule,
+        sync_jobs::{self, JobId, JobState, SyncProgress},
+        tracker::SyncResult,
+        season_parser::{detect_season, SeasonInfo},
+        title_matcher,
+        transmission::{clear_all_torrents, upload_to_transmission_rpc, upload_torrent_file},
     },
 };
-use askama::Template;
+use askama::{MarkupDisplay, Template};
 use axum::{
     extract::{Query, State},
-    response::{Html, IntoResponse, Json},
+    http::header,
+    response::{Html, IntoResponse, Json, Redirect, Response},
     Form,
 };
 use chrono::{DateTime, Datelike, Utc};
@@ -44,6 +67,8 @@ pub struct AnimeIdQuery {
 pub struct AnimeKeywordQuery {
     pub keyword: String,
     pub source: String,
+    /// Nyaa category code (uses the default category if not provided)
+    pub category: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -65,6 +90,16 @@ pub struct TrackerDataEntry {
     pub download_path: Option<String>,
     #[serde(default)]
     pub last_downloaded_episode: u16,
+    /// Nyaa category code, blank for the default
+    pub nyaa_category: Option<String>,
+    /// Preferred subtitle language code, blank for the default
+    pub language: Option<String>,
+    /// Nyaa query template, blank for the default
+    pub query_template: Option<String>,
+    #[serde(default)]
+    pub nyaa_user_feed: bool,
+    #[serde(default)]
+    pub skip_anilist_progress: bool,
 }
 
 fn default_quality() -> String {
@@ -75,6 +110,12 @@ fn default_quality() -> String {
 pub struct RssConfigForm {
     pub poll_times_per_day: u8,
     pub enabled: bool,
+    pub match_confidence_threshold: Option<f64>,
+    pub default_nyaa_category: Option<String>,
+    pub default_language: Option<String>,
+    pub airing_aware_polling: Option<bool>,
+    pub airing_poll_minutes: Option<u16>,
+    pub max_shows_per_poll: Option<u16>,
 }
 
 impl UserState {
@@ -111,6 +152,8 @@ pub struct CardTemplate {
 #[template(path = "components/grid.html")]
 pub struct GridTemplate {
     pub cards: Vec<CardTemplate>,
+    /// Banner shown when AniList couldn't be reached
+    pub notice: Option<String>,
 }
 
 #[derive(Template)]
@@ -118,12 +161,75 @@ pub struct GridTemplate {
 pub struct NavBarTemplate {
     pub seasons: Vec<(Season, u16)>,
     pub offset: i32, // Offset from current season (negative = past, positive = future)
+    /// Where the grid comes from
+    pub provider: MetadataSource,
+}
+
+impl NavBarTemplate {
+    /// Value, label and whether it's selected, for each grid provider
+    fn provider_options(&self) -> Vec<(&'static str, &'static str, bool)> {
+        [
+            MetadataSource::AniList,
+            MetadataSource::MyAnimeList,
+            MetadataSource::Kitsu,
+        ]
+        .into_iter()
+        .map(|source| (source.as_str(), source.label(), source == self.provider))
+        .collect()
+    }
 }
 
 #[derive(Template)]
 #[template(path = "components/table.html")]
 pub struct TableTemplate {
     shows: Vec<TableEntry>,
+    /// How long after air each show usually becomes available, by show ID
+    release_delays: HashMap<u32, String>,
+}
+
+impl TableTemplate {
+    /// Table of the given shows with their usual release delays
+    pub async fn new(shows: Vec<TableEntry>) -> Self {
+        Self {
+            shows,
+            release_delays: release_delay_labels().await,
+        }
+    }
+
+    /// How long after air a show usually becomes available, e.g. "~45m"
+    pub fn usual_delay(&self, id: &u32) -> Option<&String> {
+        self.release_delays.get(id)
+    }
+}
+
+/// Median release delays of tracked shows from their groups, formatted for the table
+async fn release_delay_labels() -> HashMap<u32, String> {
+    let result = db::with_db(|conn| {
+        Ok((db::get_tracked_shows(conn)?, db::get_release_delay_stats(conn)?))
+    })
+    .await;
+
+    match result {
+        Ok((shows, stats)) => schedule::expected_delays(&shows, stats)
+            .into_iter()
+            .map(|(id, stats)| (id, format_delay(stats.p50_secs)))
+            .collect(),
+        Err(err) => {
+            eprintln!("Failed to load release delays: {:?}", err);
+            HashMap::new()
+        }
+    }
+}
+
+/// Rough duration like "~45m", "~2h 10m" or "~2d"
+fn format_delay(secs: i64) -> String {
+    let minutes = (secs.max(0) + 30) / 60;
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("~{}m", m.max(1)),
+        (h, _) if h >= 48 => format!("~{}d", (h + 12) / 24),
+        (h, 0) => format!("~{}h", h),
+        (h, m) => format!("~{}h {}m", h, m),
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -135,6 +241,13 @@ pub struct TableEntry {
     pub id: u32,
 }
 
+impl TableEntry {
+    /// Whether the show was added by hand rather than from AniList
+    pub fn is_manual(&self) -> bool {
+        db::is_manual_show_id(self.id)
+    }
+}
+
 #[derive(Template)]
 #[template(path = "components/tracked.html")]
 pub struct TrackedTemplate {
@@ -152,9 +265,16 @@ pub struct TrackedTableTemplate {
 #[template(path = "components/source_table.html")]
 pub struct SourceTableTemplate {
     pub keyword: String,
+    pub category: String,
     pub links: Vec<Link>,
 }
 
+impl SourceTableTemplate {
+    pub fn category_options(&self) -> Vec<SourceOption> {
+        category_options(&self.category)
+    }
+}
+
 #[derive(Template)]
 #[template(path = "components/configure.html")]
 pub struct ConfigureTemplate {
@@ -166,6 +286,122 @@ pub struct ConfigureTemplate {
     pub quality: String,
     pub download_path: Option<String>,
     pub last_downloaded_episode: u16,
+    pub nyaa_category: Option<String>,
+    pub language: Option<String>,
+    pub query_template: Option<String>,
+    pub nyaa_user_feed: bool,
+    pub skip_anilist_progress: bool,
+    pub aliases: AliasesTemplate,
+}
+
+/// Alias list in the configure modal, swapped in place when aliases change
+#[derive(Template)]
+#[template(path = "components/aliases.html")]
+pub struct AliasesTemplate {
+    pub show_id: u32,
+    pub aliases: Vec<db::ShowAlias>,
+}
+
+/// Result of searching the show's source under a single alias
+#[derive(Template)]
+#[template(path = "components/alias_test.html")]
+pub struct AliasTestTemplate {
+    pub matches: Vec<MatchCandidate>,
+}
+
+/// An entry in the configure modal's source dropdown
+pub struct SourceOption {
+    pub value: String,
+    pub label: String,
+    pub selected: bool,
+}
+
+impl ConfigureTemplate {
+    /// Source dropdown entries, one per registered fansub group
+    pub fn source_options(&self) -> Vec<SourceOption> {
+        source_options(&self.source)
+    }
+
+    /// Whether the show was added by hand rather than from AniList
+    pub fn is_manual(&self) -> bool {
+        db::is_manual_show_id(self.id)
+    }
+
+    /// Category dropdown entries, led by "Default" for the global default category
+    pub fn category_options(&self) -> Vec<SourceOption> {
+        let selected = self.nyaa_category.as_deref().unwrap_or_default();
+        with_default_option(category_options(selected), selected)
+    }
+
+    /// Subtitle language dropdown entries, led by "Default" for the global default
+    pub fn language_options(&self) -> Vec<SourceOption> {
+        let selected = self.language.as_deref().unwrap_or_default();
+        with_default_option(language_options(selected), selected)
+    }
+}
+
+/// Source dropdown entries, one per registered fansub group
+///
+/// A source that isn't exactly a group name (e.g. `subsplease_direct`, which picks
+/// a different feed) is kept as its own entry so saving doesn't rewrite it.
+pub(crate) fn source_options(selected: &str) -> Vec<SourceOption> {
+    let mut options: Vec<SourceOption> = fansub::all_groups()
+        .into_iter()
+        .map(|group| SourceOption {
+            selected: group.name.eq_ignore_ascii_case(selected),
+            value: group.name.clone(),
+            label: group.name,
+        })
+        .collect();
+
+    if !selected.is_empty() && !options.iter().any(|o| o.selected) {
+        options.push(SourceOption {
+            value: selected.to_string(),
+            label: format!("{} ({})", fansub::canonical_group(selected), selected),
+            selected: true,
+        });
+    }
+
+    options
+}
+
+/// Nyaa category dropdown entries, by Nyaa's own category names
+fn category_options(selected: &str) -> Vec<SourceOption> {
+    NYAA_CATEGORIES
+        .iter()
+        .map(|(code, name)| SourceOption {
+            value: code.to_string(),
+            label: name.to_string(),
+            selected: *code == selected,
+        })
+        .collect()
+}
+
+/// Subtitle language dropdown entries
+fn language_options(selected: &str) -> Vec<SourceOption> {
+    LANGUAGES
+        .iter()
+        .map(|language| SourceOption {
+            value: language.code.to_string(),
+            label: language.name.to_string(),
+            selected: language.code.eq_ignore_ascii_case(selected),
+        })
+        .collect()
+}
+
+/// Put a blank "Default" entry in front of the options, selected if nothing else is
+fn with_default_option(options: Vec<SourceOption>, selected: &str) -> Vec<SourceOption> {
+    let default = SourceOption {
+        value: String::new(),
+        label: "Default".to_string(),
+        selected: selected.is_empty(),
+    };
+    std::iter::once(default).chain(options).collect()
+}
+
+/// Treat a blank form field as unset
+fn non_blank(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
 }
 
 /// Represents a potential match from RSS/nyaasi search
@@ -176,6 +412,16 @@ pub struct MatchCandidate {
     pub quality: String,
     pub latest_episode: u16,
     pub source: String, // Detected fansub source (e.g., "subsplease", "Erai-raws")
+    pub view_url: Option<String>, // Nyaa page of the latest release, for inspecting contents
+    pub score: f64,               // Title similarity to the searched title, 0.0-1.0
+    pub season: SeasonInfo,       // Season suggested by the release title
+}
+
+impl MatchCandidate {
+    /// Similarity score as a whole percentage, for display
+    pub fn score_percent(&self) -> u32 {
+        (self.score * 100.0).round() as u32
+    }
 }
 
 #[derive(Template)]
@@ -190,6 +436,13 @@ pub struct MatchSelectionTemplate {
     pub next_air_date: String,
 }
 
+impl MatchSelectionTemplate {
+    /// Season suggested by the original title, for a custom title or skipping
+    pub fn suggested_season(&self) -> SeasonInfo {
+        detect_season(&self.original_title)
+    }
+}
+
 #[derive(Deserialize)]
 pub struct SearchMatchesQuery {
     pub id: u32,
@@ -202,21 +455,42 @@ pub struct SearchMatchesQuery {
 }
 
 #[derive(Deserialize)]
-pub struct ConfirmMatchQuery {
+pub struct TorrentDetailsQuery {
+    pub url: String,
+}
+
+#[derive(Template)]
+#[template(path = "components/torrent_details.html")]
+pub struct TorrentDetailsTemplate {
+    pub url: String,
+    pub details: Option<TorrentDetails>,
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ConfirmMatchForm {
     pub id: u32,
     pub title: String,
     pub alternate: String,
     pub latest_episode: String,
     pub next_air_date: String,
     pub source: Option<String>, // Detected fansub source from match selection
+    pub season: Option<u8>,     // Season picked in the match selection
+}
+
+#[derive(Deserialize)]
+pub struct SearchAnimeQuery {
+    #[serde(default)]
+    pub q: String,
 }
 
 #[derive(Deserialize)]
-pub struct SkipMatchQuery {
+pub struct SkipMatchForm {
     pub id: u32,
     pub title: String,
     pub latest_episode: String,
     pub next_air_date: String,
+    pub season: Option<u8>,
 }
 
 /// Empty template for returning just headers
@@ -224,22 +498,84 @@ pub struct SkipMatchQuery {
 #[template(source = "", ext = "html")]
 pub struct EmptyTemplate;
 
-async fn get_seasonal(season: Season, year: u16) -> anyhow::Result<Vec<AniShow>> {
-    match get_anilist_data(season, year).await {
-        std::result::Result::Ok(res) => Ok(res),
-        std::result::Result::Err(err) => {
-            println!("Failed to fetch seasonal anime. Error: {}", err);
-            Ok(Vec::new())
+/// Provider chosen for the grid
+async fn metadata_source() -> MetadataSource {
+    match db::with_db(db::get_rss_config).await {
+        Ok(config) => config.metadata_provider,
+        Err(err) => {
+            eprintln!("Failed to load metadata provider: {:?}", err);
+            MetadataSource::AniList
+        }
+    }
+}
+
+async fn get_seasonal(season: Season, year: u16) -> (Vec<AniShow>, Option<String>) {
+    match metadata_source().await {
+        MetadataSource::AniList => grid_shows(get_anilist_data_cached(season, year).await),
+        source => {
+            let result = metadata::provider(source).seasonal(season, year).await;
+            provider_grid_shows(source, result).await
+        }
+    }
+}
+
+async fn get_currently_airing() -> (Vec<AniShow>, Option<String>) {
+    match metadata_source().await {
+        MetadataSource::AniList => grid_shows(get_anilist_all_airing_cached().await),
+        source => {
+            let result = metadata::provider(source).airing().await;
+            provider_grid_shows(source, result).await
+        }
+    }
+}
+
+/// Shows for the grid from a provider other than AniList, under their AniList IDs
+async fn provider_grid_shows(
+    source: MetadataSource,
+    result: anyhow::Result<Vec<ProviderShow>>,
+) -> (Vec<AniShow>, Option<String>) {
+    match result {
+        Ok(shows) => {
+            let shows = metadata::with_anilist_ids(shows).await;
+            covers::remember_covers(&shows);
+            (shows, None)
+        }
+        Err(err) => {
+            println!("Failed to fetch shows from {}. Error: {}", source.label(), err);
+            (Vec::new(), Some(format!("{} couldn't be reached", source.label())))
         }
     }
 }
 
-async fn get_currently_airing() -> anyhow::Result<Vec<AniShow>> {
-    match get_anilist_all_airing().await {
-        std::result::Result::Ok(res) => Ok(res),
-        std::result::Result::Err(err) => {
+/// Shows for the grid, with a notice when they're missing or out of date
+fn grid_shows(result: anyhow::Result<AniListResult>) -> (Vec<AniShow>, Option<String>) {
+    match result {
+        Ok(AniListResult {
+            shows,
+            stale_since: None,
+        }) => {
+            covers::remember_covers(&shows);
+            (shows, None)
+        }
+        Ok(AniListResult {
+            shows,
+            stale_since: Some(fetched_at),
+        }) => {
+            covers::remember_covers(&shows);
+            (
+                shows,
+                Some(format!(
+                    "AniList couldn't be reached, showing data from {} UTC",
+                    fetched_at
+                )),
+            )
+        }
+        Err(err) => {
             println!("Failed to fetch seasonal anime. Error: {}", err);
-            Ok(Vec::new())
+            (
+                Vec::new(),
+                Some("AniList couldn't be reached and nothing is cached yet".to_string()),
+            )
         }
     }
 }
@@ -357,7 +693,7 @@ fn get_seasons_around(season: Season, year: u16) -> Vec<(Season, u16)> {
     seasons
 }
 
-fn get_next_airing_episode(next_airing_episode: &Option<NextAiringEpisode>) -> (String, String) {
+pub(crate) fn get_next_airing_episode(next_airing_episode: &Option<NextAiringEpisode>) -> (String, String) {
     let nae = match next_airing_episode {
         Some(ep) => ep,
         None => return ("N/A".into(), "N/A".into()),
@@ -432,9 +768,7 @@ fn build_card_templates(shows: &[AniShow], lock: &UserState) -> Vec<CardTemplate
 
 pub async fn view(State(state): State<Arc<Mutex<UserState>>>) -> impl IntoResponse {
     let lock = state.lock().await;
-    let mut shows: Vec<AniShow> = get_seasonal(lock.season, lock.year)
-        .await
-        .unwrap_or_default();
+    let (mut shows, notice) = get_seasonal(lock.season, lock.year).await;
     shows.sort_by(|a, b| {
         calculate_sort_score(b)
             .partial_cmp(&calculate_sort_score(a))
@@ -443,6 +777,7 @@ pub async fn view(State(state): State<Arc<Mutex<UserState>>>) -> impl IntoRespon
     let card_templates: Vec<CardTemplate> = build_card_templates(&shows, &lock);
     let grid_template = GridTemplate {
         cards: card_templates,
+        notice,
     };
     let seasons = get_seasons_around(lock.season, lock.year);
     let template = HomeTemplate {
@@ -450,10 +785,12 @@ pub async fn view(State(state): State<Arc<Mutex<UserState>>>) -> impl IntoRespon
         grid: grid_template,
         season: lock.season,
         year: lock.year,
-        navbar: NavBarTemplate { seasons, offset: 0 },
-        table: TableTemplate {
-            shows: lock.tracker.values().cloned().collect(),
+        navbar: NavBarTemplate {
+            seasons,
+            offset: 0,
+            provider: metadata_source().await,
         },
+        table: TableTemplate::new(lock.tracker.values().cloned().collect()).await,
     };
     HtmlTemplate::new(template)
 }
@@ -479,9 +816,7 @@ pub async fn navigate_seasonal_anime(
     };
     lock.year = payload.year;
 
-    let mut cards: Vec<AniShow> = get_seasonal(lock.season, lock.year)
-        .await
-        .unwrap_or_default();
+    let (mut cards, notice) = get_seasonal(lock.season, lock.year).await;
     cards.sort_by(|a, b| {
         calculate_sort_score(b)
             .partial_cmp(&calculate_sort_score(a))
@@ -492,6 +827,7 @@ pub async fn navigate_seasonal_anime(
 
     let grid = GridTemplate {
         cards: card_templates,
+        notice,
     };
 
     HtmlTemplate::new(grid)
@@ -502,7 +838,89 @@ pub async fn currently_airing_anime(
     State(state): State<Arc<Mutex<UserState>>>,
 ) -> impl IntoResponse {
     let lock = state.lock().await;
-    let mut cards: Vec<AniShow> = get_currently_airing().await.unwrap_or_default();
+    let (mut cards, notice) = get_currently_airing().await;
+    cards.sort_by(|a, b| {
+        calculate_sort_score(b)
+            .partial_cmp(&calculate_sort_score(a))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let card_templates: Vec<CardTemplate> = build_card_templates(&cards, &lock);
+    let grid = GridTemplate {
+        cards: card_templates,
+        notice,
+    };
+    HtmlTemplate::new(grid)
+}
+
+/// Search AniList by title, so shows outside the seasonal grid can be tracked
+///
+/// An empty search brings back the season being viewed.
+#[axum::debug_handler]
+pub async fn search_anime(
+    State(state): State<Arc<Mutex<UserState>>>,
+    Query(payload): Query<SearchAnimeQuery>,
+) -> impl IntoResponse {
+    let query = payload.q.trim().to_string();
+    let lock = state.lock().await;
+
+    let (cards, notice) = if query.is_empty() {
+        let (mut cards, notice) = get_seasonal(lock.season, lock.year).await;
+        cards.sort_by(|a, b| {
+            calculate_sort_score(b)
+                .partial_cmp(&calculate_sort_score(a))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        (cards, notice)
+    } else {
+        // Keep AniList's best-match order
+        match AniListProvider.search(&query).await {
+            Ok(results) => {
+                metadata::remember_ids(&results).await;
+                let cards: Vec<AniShow> = results.into_iter().map(|r| r.show).collect();
+                covers::remember_covers(&cards);
+                let notice = cards
+                    .is_empty()
+                    .then(|| format!("No shows on AniList match \"{}\"", query));
+                (cards, notice)
+            }
+            Err(err) => {
+                eprintln!("AniList search for {} failed: {:?}", query, err);
+                (Vec::new(), Some("AniList couldn't be reached".to_string()))
+            }
+        }
+    };
+
+    let grid = GridTemplate {
+        cards: build_card_templates(&cards, &lock),
+        notice,
+    };
+    HtmlTemplate::new(grid)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MetadataProviderForm {
+    pub provider: String,
+}
+
+/// Switch the provider of the seasonal and airing grid, then reload the grid
+#[axum::debug_handler]
+pub async fn set_metadata_provider(
+    State(state): State<Arc<Mutex<UserState>>>,
+    Form(payload): Form<MetadataProviderForm>,
+) -> impl IntoResponse {
+    match MetadataSource::parse(&payload.provider) {
+        Some(source) => {
+            if let Err(err) =
+                db::with_db(move |conn| db::set_metadata_provider(conn, source)).await
+            {
+                eprintln!("Failed to save metadata provider: {:?}", err);
+            }
+        }
+        None => eprintln!("Unknown metadata provider {}", payload.provider),
+    }
+
+    let lock = state.lock().await;
+    let (mut cards, notice) = get_seasonal(lock.season, lock.year).await;
     cards.sort_by(|a, b| {
         calculate_sort_score(b)
             .partial_cmp(&calculate_sort_score(a))
@@ -511,10 +929,82 @@ pub async fn currently_airing_anime(
     let card_templates: Vec<CardTemplate> = build_card_templates(&cards, &lock);
     let grid = GridTemplate {
         cards: card_templates,
+        notice,
     };
     HtmlTemplate::new(grid)
 }
 
+/// Path parameters for opening a show on a metadata provider
+#[derive(Deserialize)]
+pub struct ShowProviderPath {
+    pub show_id: u32,
+    pub provider: String,
+}
+
+/// Open one of our shows on AniList, MyAnimeList or Kitsu
+///
+/// The show's ID on the provider is looked up the first time and remembered.
+#[axum::debug_handler]
+pub async fn open_on_provider(
+    axum::extract::Path(path): axum::extract::Path<ShowProviderPath>,
+) -> Response {
+    let Some(source) = MetadataSource::parse(&path.provider) else {
+        return (axum::http::StatusCode::NOT_FOUND, "Unknown provider").into_response();
+    };
+    if db::is_manual_show_id(path.show_id) {
+        return (axum::http::StatusCode::NOT_FOUND, "Shows added by hand have no provider entry")
+            .into_response();
+    }
+
+    match metadata::resolve_provider_id(path.show_id, source).await {
+        Ok(Some(id)) => Redirect::temporary(&metadata::show_url(source, id)).into_response(),
+        Ok(None) => (
+            axum::http::StatusCode::NOT_FOUND,
+            format!("This show isn't on {}", source.label()),
+        )
+            .into_response(),
+        Err(err) => {
+            eprintln!("Failed to look up show {} on {}: {:?}", path.show_id, source.label(), err);
+            (
+                axum::http::StatusCode::BAD_GATEWAY,
+                format!("{} couldn't be reached", source.label()),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Path parameter for a cover image
+#[derive(Deserialize)]
+pub struct CoverIdPath {
+    pub id: u32,
+}
+
+/// Serve a show's cover thumbnail from the local cache, fetching it on first use
+///
+/// Falls back to the bundled placeholder when the show has no cover or the
+/// download fails.
+#[axum::debug_handler]
+pub async fn cover_image(
+    axum::extract::Path(path): axum::extract::Path<CoverIdPath>,
+) -> Response {
+    match covers::get_cover(path.id).await {
+        Ok(Some(bytes)) => (
+            [
+                (header::CONTENT_TYPE, "image/jpeg"),
+                (header::CACHE_CONTROL, "public, max-age=604800"),
+            ],
+            bytes,
+        )
+            .into_response(),
+        Ok(None) => Redirect::temporary(covers::PLACEHOLDER_PATH).into_response(),
+        Err(err) => {
+            eprintln!("Failed to fetch cover for show {}: {:?}", path.id, err);
+            Redirect::temporary(covers::PLACEHOLDER_PATH).into_response()
+        }
+    }
+}
+
 #[axum::debug_handler]
 pub async fn update_user(
     State(state): State<Arc<Mutex<UserState>>>,
@@ -540,6 +1030,7 @@ pub async fn navigate_season_bar(
     let template = NavBarTemplate {
         seasons,
         offset: payload.offset,
+        provider: metadata_source().await,
     };
     HtmlTemplate::new(template)
 }
@@ -610,11 +1101,15 @@ pub async fn set_tracker(
     let title = new_payload.title.clone();
 
     // Search SubsPlease RSS first
-    let matches = search_rss_matches("subsplease", &title).await;
+    let category = nyaa_category(Some(new_payload.id)).await;
+    let matches = search_rss_matches("subsplease", &title, &category).await;
+    let threshold = match_threshold().await;
 
-    // Check for exact match
-    if let Some((exact_match, detected_source)) = has_exact_match(&title, &matches) {
-        // Exact match found - save directly with matched title as alternate
+    // Check for a confident match; a guessed season needs the user's say
+    if let Some((exact_match, detected_source)) = best_match(&matches, threshold)
+        .filter(|(title, _)| !detect_season(title).is_guess())
+    {
+        // Confident match found - save directly with matched title as alternate
         let mut lock = state.lock().await;
         lock.tracker.insert(new_payload.id, new_payload.clone());
 
@@ -627,6 +1122,7 @@ pub async fn set_tracker(
         let latest_episode = new_payload.latest_episode.clone();
         let next_air_date = new_payload.next_air_date.clone();
 
+        let matched_title = exact_match.clone();
         let db_result = db::with_db(move |conn| {
             if let Some(mut existing_show) = db::get_show(conn, show_id)? {
                 existing_show.is_tracked = true;
@@ -652,6 +1148,11 @@ pub async fn set_tracker(
                     next_air_date: Some(next_air_date),
                     created_at: None,
                     updated_at: None,
+                    nyaa_category: None,
+                    language: None,
+                    query_template: None,
+                    nyaa_user_feed: false,
+                    skip_anilist_progress: false,
                 };
                 db::insert_show(conn, &new_show)?;
             }
@@ -661,6 +1162,10 @@ pub async fn set_tracker(
 
         if let Err(err) = db_result {
             eprintln!("Could not save to database: {:?}", err);
+        } else {
+            // AniList lookup is slow, don't hold up other requests
+            drop(lock);
+            seed_show_aliases(show_id, Some(matched_title)).await;
         }
 
         let template = TrackedTemplate { entry: new_payload };
@@ -669,7 +1174,7 @@ pub async fn set_tracker(
             .into_response();
     }
 
-    // No exact match - check if we have partial matches or need to search Nyaa.si
+    // No confident match - check if we have partial matches or need to search Nyaa.si
     if !matches.is_empty() {
         // Show modal with SubsPlease matches
         let template = MatchSelectionTemplate {
@@ -689,11 +1194,13 @@ pub async fn set_tracker(
     }
 
     // No SubsPlease results - try Nyaa.si
-    let nyaasi_matches = search_nyaasi_matches(&title).await;
+    let nyaasi_matches = search_nyaasi_matches(&title, &category).await;
 
     if !nyaasi_matches.is_empty() {
-        // Check for exact match in Nyaa.si results
-        if let Some((exact_match, detected_source)) = has_exact_match(&title, &nyaasi_matches) {
+        // Check for a confident match in Nyaa.si results
+        if let Some((exact_match, detected_source)) = best_match(&nyaasi_matches, threshold)
+            .filter(|(title, _)| !detect_season(title).is_guess())
+        {
             let mut lock = state.lock().await;
             lock.tracker.insert(new_payload.id, new_payload.clone());
 
@@ -706,6 +1213,7 @@ pub async fn set_tracker(
             let latest_episode = new_payload.latest_episode.clone();
             let next_air_date = new_payload.next_air_date.clone();
 
+            let matched_title = exact_match.clone();
             let db_result = db::with_db(move |conn| {
                 if let Some(mut existing_show) = db::get_show(conn, show_id)? {
                     existing_show.is_tracked = true;
@@ -731,6 +1239,11 @@ pub async fn set_tracker(
                         next_air_date: Some(next_air_date),
                         created_at: None,
                         updated_at: None,
+                        nyaa_category: None,
+                        language: None,
+                        query_template: None,
+                        nyaa_user_feed: false,
+                        skip_anilist_progress: false,
                     };
                     db::insert_show(conn, &new_show)?;
                 }
@@ -740,6 +1253,9 @@ pub async fn set_tracker(
 
             if let Err(err) = db_result {
                 eprintln!("Could not save to database: {:?}", err);
+            } else {
+                drop(lock);
+                seed_show_aliases(show_id, Some(matched_title)).await;
             }
 
             let template = TrackedTemplate { entry: new_payload };
@@ -801,6 +1317,11 @@ pub async fn set_tracker(
                 next_air_date: Some(next_air_date),
                 created_at: None,
                 updated_at: None,
+                nyaa_category: None,
+                language: None,
+                query_template: None,
+                nyaa_user_feed: false,
+                skip_anilist_progress: false,
             };
             db::insert_show(conn, &new_show)?;
         }
@@ -810,6 +1331,9 @@ pub async fn set_tracker(
 
     if let Err(err) = db_result {
         eprintln!("Could not save to database: {:?}", err);
+    } else {
+        drop(lock);
+        seed_show_aliases(show_id, None).await;
     }
 
     let template = TrackedTemplate { entry: new_payload };
@@ -822,9 +1346,7 @@ pub async fn set_tracker(
 pub async fn show_table(State(state): State<Arc<Mutex<UserState>>>) -> impl IntoResponse {
     let lock = state.lock().await;
 
-    let template = TableTemplate {
-        shows: lock.tracker.values().cloned().collect(),
-    };
+    let template = TableTemplate::new(lock.tracker.values().cloned().collect()).await;
     HtmlTemplate::new(template)
 }
 
@@ -834,10 +1356,11 @@ pub async fn get_source(
     Query(payload): Query<AnimeIdQuery>,
 ) -> impl IntoResponse {
     println!("Get Source!");
+    let category = nyaa_category(Some(payload.id)).await;
     let lock = state.lock().await;
     let show = lock.tracker.get(&payload.id);
     let title = &show.unwrap().title;
-    let links = match fetch_sources(title, "subsplease").await {
+    let links = match fetch_sources(title, "subsplease", &category).await {
         anyhow::Result::Ok(val) => val,
         Err(err) => {
             println!("Couldn't fetch source for {}, {:?}", title, err);
@@ -846,6 +1369,7 @@ pub async fn get_source(
     };
     let template = SourceTableTemplate {
         keyword: title.clone(),
+        category,
         links,
     };
     HtmlTemplate::new(template)
@@ -854,7 +1378,11 @@ pub async fn get_source(
 #[axum::debug_handler]
 pub async fn search_source(Form(payload): Form<AnimeKeywordQuery>) -> impl IntoResponse {
     println!("Search!");
-    let links = match fetch_sources(&payload.keyword, &payload.source).await {
+    let category = match non_blank(payload.category) {
+        Some(category) => category,
+        None => nyaa_category(None).await,
+    };
+    let links = match fetch_sources(&payload.keyword, &payload.source, &category).await {
         anyhow::Result::Ok(val) => val,
         Err(err) => {
             println!("Couldn't fetch source for {}, {:?}", &payload.keyword, err);
@@ -863,6 +1391,7 @@ pub async fn search_source(Form(payload): Form<AnimeKeywordQuery>) -> impl IntoR
     };
     let template = SourceTableTemplate {
         keyword: payload.keyword.clone(),
+        category,
         links,
     };
     HtmlTemplate::new(template)
@@ -888,6 +1417,10 @@ pub async fn get_configuration(
 ) -> impl IntoResponse {
     let show_id = payload.id;
     let db_show = db::with_db(move |conn| db::get_show(conn, show_id)).await;
+    let aliases = AliasesTemplate {
+        show_id,
+        aliases: load_show_aliases(show_id).await,
+    };
 
     let template = match db_show {
         Ok(Some(show)) => ConfigureTemplate {
@@ -899,6 +1432,12 @@ pub async fn get_configuration(
             quality: show.quality,
             download_path: show.download_path,
             last_downloaded_episode: show.last_downloaded_episode,
+            nyaa_category: show.nyaa_category,
+            language: show.language,
+            query_template: show.query_template,
+            nyaa_user_feed: show.nyaa_user_feed,
+            skip_anilist_progress: show.skip_anilist_progress,
+            aliases,
         },
         _ => {
             // Fall back to in-memory tracker if not in database
@@ -913,6 +1452,12 @@ pub async fn get_configuration(
                     quality: "1080p".into(),
                     download_path: None,
                     last_downloaded_episode: 0,
+                    nyaa_category: None,
+                    language: None,
+                    query_template: None,
+                    nyaa_user_feed: false,
+                    skip_anilist_progress: false,
+                    aliases,
                 }
             } else {
                 // Default template for unknown show
@@ -925,6 +1470,12 @@ pub async fn get_configuration(
                     quality: "1080p".into(),
                     download_path: None,
                     last_downloaded_episode: 0,
+                    nyaa_category: None,
+                    language: None,
+                    query_template: None,
+                    nyaa_user_feed: false,
+                    skip_anilist_progress: false,
+                    aliases,
                 }
             }
         }
@@ -942,6 +1493,11 @@ pub async fn save_configuration(Form(payload): Form<TrackerDataEntry>) -> impl I
     let source = payload.source.clone();
     let quality = payload.quality.clone();
     let download_path = payload.download_path.clone();
+    let nyaa_category = non_blank(payload.nyaa_category);
+    let language = non_blank(payload.language);
+    let query_template = non_blank(payload.query_template);
+    let nyaa_user_feed = payload.nyaa_user_feed;
+    let skip_anilist_progress = payload.skip_anilist_progress;
 
     let db_result = db::with_db(move |conn| {
         // Check if show exists
@@ -952,6 +1508,11 @@ pub async fn save_configuration(Form(payload): Form<TrackerDataEntry>) -> impl I
             existing_show.source = source;
             existing_show.quality = quality;
             existing_show.download_path = download_path;
+            existing_show.nyaa_category = nyaa_category;
+            existing_show.language = language;
+            existing_show.query_template = query_template;
+            existing_show.nyaa_user_feed = nyaa_user_feed;
+            existing_show.skip_anilist_progress = skip_anilist_progress;
             db::update_show(conn, &existing_show)?;
         } else {
             // Insert new show
@@ -970,6 +1531,11 @@ pub async fn save_configuration(Form(payload): Form<TrackerDataEntry>) -> impl I
                 next_air_date: None,
                 created_at: None,
                 updated_at: None,
+                nyaa_category,
+                language,
+                query_template,
+                nyaa_user_feed,
+                skip_anilist_progress,
             };
             db::insert_show(conn, &new_show)?;
         }
@@ -990,46 +1556,128 @@ pub async fn close() -> impl IntoResponse {
     Html("")
 }
 
-#[axum::debug_handler]
-pub async fn sync_now() -> impl IntoResponse {
-    use crate::scraper::tracker::download_shows;
+#[derive(Template)]
+#[template(path = "components/sync_progress.html")]
+struct SyncProgressTemplate {
+    progress: SyncProgress,
+}
 
-    match download_shows().await {
-        Ok(result) => {
-            let mut msg = String::new();
+impl SyncProgressTemplate {
+    /// Share of shows searched, in percent
+    fn percent(&self) -> usize {
+        (self.progress.done * 100).checked_div(self.progress.total).unwrap_or(0)
+    }
 
-            if result.shows_processed == 0 {
-                return Html("<span class=\"text-yellow-400\">No tracked shows</span>".to_string());
+    /// What the sync did, once it has stopped
+    fn summary(&self) -> String {
+        let progress = &self.progress;
+        match (progress.state, &progress.result) {
+            (JobState::Failed, _) => format!(
+                "<span class=\"text-red-400\">Sync failed: {}</span>",
+                MarkupDisplay::new_unsafe(progress.error.as_deref().unwrap_or_default(), askama::Html)
+            ),
+            (JobState::Cancelled, Some(result)) => {
+                let cancelled = format!(
+                    "<span class=\"text-yellow-400\">Cancelled after {} of {} show(s)</span>",
+                    result.shows_processed, progress.total
+                );
+                match sync_result_message(result) {
+                    Some(msg) if result.shows_processed > 0 => format!("{} | {}", cancelled, msg),
+                    _ => cancelled,
+                }
             }
-
-            if result.episodes_downloaded > 0 {
-                msg.push_str(&format!(
-                    "<span class=\"text-green-400\">Downloaded {} episode(s)</span>",
-                    result.episodes_downloaded
-                ));
-            } else if result.errors.is_empty() {
-                msg.push_str("<span class=\"text-gray-400\">No new episodes</span>");
+            (_, Some(result)) if result.shows_processed == 0 => {
+                "<span class=\"text-yellow-400\">No tracked shows</span>".to_string()
             }
+            (_, Some(result)) => sync_result_message(result).unwrap_or_default(),
+            (_, None) => String::new(),
+        }
+    }
+}
 
-            if !result.errors.is_empty() {
-                if !msg.is_empty() {
-                    msg.push_str(" | ");
-                }
-                msg.push_str(&format!(
-                    "<span class=\"text-red-400\">{} error(s)</span>",
-                    result.errors.len()
-                ));
-            }
+/// Describe what a sync downloaded and learned, and how many errors it hit
+fn sync_result_message(result: &SyncResult) -> Option<String> {
+    let mut msg = String::new();
+
+    if result.episodes_downloaded > 0 {
+        msg.push_str(&format!(
+            "<span class=\"text-green-400\">Downloaded {} episode(s)</span>",
+            result.episodes_downloaded
+        ));
+    } else if result.errors.is_empty() {
+        msg.push_str("<span class=\"text-gray-400\">No new episodes</span>");
+    }
 
-            Html(msg)
+    if !result.learned_titles.is_empty() {
+        if !msg.is_empty() {
+            msg.push_str(" | ");
         }
-        Err(e) => {
-            eprintln!("Sync failed: {:?}", e);
-            Html(format!("<span class=\"text-red-400\">Sync failed: {}</span>", e))
+        let learned: Vec<String> = result
+            .learned_titles
+            .iter()
+            .map(|(title, alternate)| {
+                let escape = |text: &str| MarkupDisplay::new_unsafe(text, askama::Html).to_string();
+                format!("{} &rarr; {}", escape(title), escape(alternate))
+            })
+            .collect();
+        msg.push_str(&format!(
+            "<span class=\"text-yellow-400\" title=\"{}\">Learned {} search title(s)</span>",
+            learned.join(", "),
+            result.learned_titles.len()
+        ));
+    }
+
+    if !result.errors.is_empty() {
+        if !msg.is_empty() {
+            msg.push_str(" | ");
         }
+        msg.push_str(&format!(
+            "<span class=\"text-red-400\">{} error(s)</span>",
+            result.errors.len()
+        ));
+    }
+
+    (!msg.is_empty()).then_some(msg)
+}
+
+/// Render a sync job's progress, refreshing the show table once it has stopped
+fn sync_progress_response(progress: SyncProgress) -> Response {
+    let stopped = !progress.is_running();
+    let template = HtmlTemplate::new(SyncProgressTemplate { progress });
+    if stopped {
+        template.with_header("HX-Trigger", "newTrackerStatus").into_response()
+    } else {
+        template.into_response()
+    }
+}
+
+/// Start syncing all tracked shows in the background, or join the sync already running
+#[axum::debug_handler]
+pub async fn sync_now() -> impl IntoResponse {
+    sync_progress_response(sync_jobs::start_manual_sync().progress())
+}
+
+/// Progress of a sync job
+#[axum::debug_handler]
+pub async fn sync_job_progress(
+    axum::extract::Path(id): axum::extract::Path<JobId>,
+) -> impl IntoResponse {
+    match sync_jobs::get_job(id) {
+        Some(job) => sync_progress_response(job.progress()),
+        // Replaced by a later sync or lost on restart; answer so the poll stops
+        None => Html("").into_response(),
     }
 }
 
+/// Cancel a sync job after the show it's searching
+#[axum::debug_handler]
+pub async fn cancel_sync_job(
+    axum::extract::Path(id): axum::extract::Path<JobId>,
+) -> impl IntoResponse {
+    sync_jobs::cancel(id);
+    sync_job_progress(axum::extract::Path(id)).await
+}
+
 /// Get the current RSS configuration
 #[axum::debug_handler]
 pub async fn get_rss_config() -> impl IntoResponse {
@@ -1046,20 +1694,60 @@ pub async fn get_rss_config() -> impl IntoResponse {
     }
 }
 
+/// Release delay statistics per show and group
+#[axum::debug_handler]
+pub async fn release_delay_stats() -> impl IntoResponse {
+    match db::with_db(db::get_release_delay_stats).await {
+        Ok(stats) => Json(stats).into_response(),
+        Err(err) => {
+            eprintln!("Failed to get release delay stats: {:?}", err);
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to get release delay stats",
+            )
+                .into_response()
+        }
+    }
+}
+
 /// Save the RSS configuration
 #[axum::debug_handler]
 pub async fn save_rss_config(Form(payload): Form<RssConfigForm>) -> impl IntoResponse {
     let poll_times = payload.poll_times_per_day;
     let enabled = payload.enabled;
+    let threshold = payload.match_confidence_threshold;
+    let default_category = non_blank(payload.default_nyaa_category);
+    let default_language = payload.default_language;
+    let airing_polling = payload.airing_aware_polling;
+    let airing_poll_minutes = payload.airing_poll_minutes;
+    let max_shows_per_poll = payload.max_shows_per_poll;
 
     let result = db::with_db(move |conn| {
         db::update_poll_interval(conn, poll_times)?;
+        if let Some(enabled) = airing_polling {
+            let current = db::get_rss_config(conn)?;
+            db::update_airing_polling(
+                conn,
+                enabled,
+                airing_poll_minutes.unwrap_or(current.airing_poll_minutes),
+                max_shows_per_poll.unwrap_or(current.max_shows_per_poll),
+            )?;
+        }
+        if let Some(threshold) = threshold {
+            db::update_match_threshold(conn, threshold)?;
+        }
+        if let Some(category) = default_category {
+            db::update_nyaa_defaults(conn, &category, default_language.as_deref())?;
+        }
         db::set_rss_enabled(conn, enabled)
     })
     .await;
 
     match result {
-        Ok(_) => Json(serde_json::json!({"status": "ok"})).into_response(),
+        Ok(_) => {
+            crate::scraper::tracker::reschedule();
+            Json(serde_json::json!({"status": "ok"})).into_response()
+        }
         Err(err) => {
             eprintln!("Failed to save RSS config: {:?}", err);
             (
@@ -1111,28 +1799,67 @@ pub async fn get_filters() -> impl IntoResponse {
     }
 }
 
-/// Search RSS feed and aggregate results by show title
-async fn search_rss_matches(source: &str, title: &str) -> Vec<MatchCandidate> {
-    let rss_items = match fetch_rss_feed(source, title).await {
-        Ok(items) => items,
-        Err(e) => {
-            eprintln!("Failed to fetch RSS feed for '{}': {:?}", title, e);
-            return Vec::new();
+/// Seed a newly tracked show's aliases from AniList and the release title it was matched to
+///
+/// Failures are only logged; the show is tracked either way.
+pub(crate) async fn seed_show_aliases(show_id: u32, matched_title: Option<String>) {
+    // Shows added by hand have no AniList titles
+    let anilist_titles = if db::is_manual_show_id(show_id) {
+        Vec::new()
+    } else {
+        match get_anilist_titles(show_id).await {
+            Ok(Some(media)) => media.search_titles(),
+            Ok(None) => Vec::new(),
+            Err(err) => {
+                eprintln!("Failed to fetch AniList titles for {}: {:?}", show_id, err);
+                Vec::new()
+            }
         }
     };
 
-    // Group by show title, track (count, latest_episode, quality, source)
-    let mut show_map: HashMap<String, (u16, u16, String, String)> = HashMap::new();
-
-    for item in &rss_items {
+    let result = db::with_db(move |conn| {
+        if let Some(title) = matched_title {
+            db::add_show_alias(conn, show_id, &title, AliasOrigin::Match)?;
+        }
+        for title in anilist_titles {
+            db::add_show_alias(conn, show_id, &title, AliasOrigin::Anilist)?;
+        }
+        Ok(())
+    })
+    .await;
+
+    if let Err(err) = result {
+        eprintln!("Failed to seed aliases for {}: {:?}", show_id, err);
+    }
+}
+
+/// Search RSS feed and aggregate results by show title
+async fn search_rss_matches(source: &str, title: &str, category: &str) -> Vec<MatchCandidate> {
+    let rss_items = match fetch_rss_feed(&fansub::feed_name(source), title, category).await {
+        Ok(items) => items,
+        Err(e) => {
+            eprintln!("Failed to fetch RSS feed for '{}': {:?}", title, e);
+            return Vec::new();
+        }
+    };
+
+    // Group by show title, track (count, latest_episode, quality, source, view_url)
+    let mut show_map: HashMap<String, (u16, u16, String, String, String)> = HashMap::new();
+
+    for item in &rss_items {
         if let Some((show_title, episode, quality)) = parse_episode_info(&item.title) {
             let detected_source = detect_fansub_source(&item.title);
-            let entry = show_map
-                .entry(show_title)
-                .or_insert((0, 0, quality.clone(), detected_source.clone()));
+            let entry = show_map.entry(show_title).or_insert((
+                0,
+                0,
+                quality.clone(),
+                detected_source.clone(),
+                item.view_url.clone(),
+            ));
             entry.0 += 1; // increment count
             if episode > entry.1 {
                 entry.1 = episode; // track latest episode
+                entry.4 = item.view_url.clone();
             }
             if entry.2.is_empty() {
                 entry.2 = quality;
@@ -1141,21 +1868,30 @@ async fn search_rss_matches(source: &str, title: &str) -> Vec<MatchCandidate> {
         }
     }
 
-    show_map
+    let candidates = show_map
         .into_iter()
-        .map(|(show_title, (episode_count, latest_episode, quality, source))| MatchCandidate {
-            show_title,
-            episode_count,
-            latest_episode,
-            quality,
-            source,
-        })
-        .collect()
+        .map(
+            |(show_title, (episode_count, latest_episode, quality, source, view_url))| {
+                MatchCandidate {
+                    score: title_matcher::similarity(title, &show_title),
+                    season: detect_season(&show_title),
+                    show_title,
+                    episode_count,
+                    latest_episode,
+                    quality,
+                    source,
+                    view_url: Some(view_url).filter(|u| is_nyaa_view_url(u)),
+                }
+            },
+        )
+        .collect();
+
+    rank_by_score(candidates)
 }
 
 /// Search Nyaa.si HTTP and aggregate results by show title
-async fn search_nyaasi_matches(title: &str) -> Vec<MatchCandidate> {
-    let links = match fetch_sources(title, "default").await {
+async fn search_nyaasi_matches(title: &str, category: &str) -> Vec<MatchCandidate> {
+    let links = match fetch_sources(title, "default", category).await {
         Ok(l) => l,
         Err(e) => {
             eprintln!("Failed to fetch Nyaa.si for '{}': {:?}", title, e);
@@ -1163,41 +1899,127 @@ async fn search_nyaasi_matches(title: &str) -> Vec<MatchCandidate> {
         }
     };
 
-    // Group by show title, track (count, latest_episode, source)
-    let mut show_map: HashMap<String, (u16, u16, String)> = HashMap::new();
+    // Group by show title, track (count, latest_episode, source, view_url)
+    let mut show_map: HashMap<String, (u16, u16, String, Option<String>)> = HashMap::new();
 
     for link in &links {
         let episode: u16 = link.episode.parse().unwrap_or(0);
-        let entry = show_map
-            .entry(link.title.clone())
-            .or_insert((0, 0, link.source.clone()));
+        let entry = show_map.entry(link.title.clone()).or_insert((
+            0,
+            0,
+            link.source.clone(),
+            link.view_link.clone(),
+        ));
         entry.0 += 1;
         if episode > entry.1 {
             entry.1 = episode;
+            entry.3 = link.view_link.clone();
         }
         // Keep first detected source
     }
 
-    show_map
+    let candidates = show_map
         .into_iter()
-        .map(|(show_title, (episode_count, latest_episode, source))| MatchCandidate {
-            show_title,
-            episode_count,
-            latest_episode,
-            quality: "1080p".to_string(), // Nyaa.si results are pre-filtered to 1080p
-            source,
-        })
-        .collect()
+        .map(
+            |(show_title, (episode_count, latest_episode, source, view_url))| MatchCandidate {
+                score: title_matcher::similarity(title, &show_title),
+                season: detect_season(&show_title),
+                show_title,
+                episode_count,
+                latest_episode,
+                quality: "1080p".to_string(), // Nyaa.si results are pre-filtered to 1080p
+                source,
+                view_url,
+            },
+        )
+        .collect();
+
+    rank_by_score(candidates)
 }
 
-/// Check if any match equals the title (case-insensitive)
-/// Returns (show_title, source) if exact match found
-fn has_exact_match(title: &str, matches: &[MatchCandidate]) -> Option<(String, String)> {
-    let title_lower = title.to_lowercase();
-    matches
-        .iter()
-        .find(|m| m.show_title.to_lowercase() == title_lower)
-        .map(|m| (m.show_title.clone(), m.source.clone()))
+/// Sort candidates best match first, breaking ties by episode count
+fn rank_by_score(mut candidates: Vec<MatchCandidate>) -> Vec<MatchCandidate> {
+    candidates.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then(b.episode_count.cmp(&a.episode_count))
+    });
+    candidates
+}
+
+/// Pick the candidate to accept without asking the user
+///
+/// The top-ranked candidate must reach the confidence threshold, and no other
+/// candidate may score within a hair of it, otherwise the choice is ambiguous.
+/// Expects `matches` sorted by score. Returns (show_title, source).
+fn best_match(matches: &[MatchCandidate], threshold: f64) -> Option<(String, String)> {
+    const AMBIGUITY_MARGIN: f64 = 0.01;
+
+    let best = matches.first().filter(|m| m.score >= threshold)?;
+    let ambiguous = matches.get(1).is_some_and(|runner_up| {
+        best.score < 1.0 && best.score - runner_up.score < AMBIGUITY_MARGIN
+    });
+    if ambiguous {
+        return None;
+    }
+
+    Some((best.show_title.clone(), best.source.clone()))
+}
+
+/// Search SubsPlease, then Nyaa.si, for a match that can be accepted without asking
+///
+/// Like `set_tracker`, a match whose season is only a guess isn't accepted.
+/// Returns (show_title, source).
+pub(crate) async fn find_confident_match(
+    title: &str,
+    category: &str,
+    threshold: f64,
+) -> Option<(String, String)> {
+    let confident = |matches: &[MatchCandidate]| {
+        best_match(matches, threshold).filter(|(title, _)| !detect_season(title).is_guess())
+    };
+
+    let matches = search_rss_matches("subsplease", title, category).await;
+    if let Some(found) = confident(&matches) {
+        return Some(found);
+    }
+    if !matches.is_empty() {
+        return None;
+    }
+
+    confident(&search_nyaasi_matches(title, category).await)
+}
+
+/// Confidence required to auto-accept a match, from the RSS config
+pub(crate) async fn match_threshold() -> f64 {
+    match db::with_db(db::get_rss_config).await {
+        Ok(config) => config.match_confidence_threshold,
+        Err(err) => {
+            eprintln!("Failed to load RSS config: {:?}", err);
+            RssConfig::default().match_confidence_threshold
+        }
+    }
+}
+
+/// Nyaa category to search for a show, or the default category if it has none
+pub(crate) async fn nyaa_category(show_id: Option<u32>) -> String {
+    let result = db::with_db(move |conn| {
+        let config = db::get_rss_config(conn)?;
+        let show = match show_id {
+            Some(id) => db::get_show(conn, id)?,
+            None => None,
+        };
+        Ok(match show {
+            Some(show) => show.effective_category(&config),
+            None => config.default_nyaa_category,
+        })
+    })
+    .await;
+
+    result.unwrap_or_else(|err| {
+        eprintln!("Failed to load Nyaa category: {:?}", err);
+        DEFAULT_CATEGORY.to_string()
+    })
 }
 
 /// Handler to search for matches (called when user clicks "Search Nyaa.si Instead")
@@ -1205,11 +2027,12 @@ fn has_exact_match(title: &str, matches: &[MatchCandidate]) -> Option<(String, S
 pub async fn search_matches(Query(payload): Query<SearchMatchesQuery>) -> impl IntoResponse {
     // Use custom query if provided, otherwise use title
     let search_term = payload.query.as_ref().unwrap_or(&payload.title);
+    let category = nyaa_category(Some(payload.id)).await;
 
     let matches = if payload.source == "nyaasi" {
-        search_nyaasi_matches(search_term).await
+        search_nyaasi_matches(search_term, &category).await
     } else {
-        search_rss_matches(&payload.source, search_term).await
+        search_rss_matches(&payload.source, search_term, &category).await
     };
 
     let fallback_available = payload.source != "nyaasi";
@@ -1227,11 +2050,33 @@ pub async fn search_matches(Query(payload): Query<SearchMatchesQuery>) -> impl I
     HtmlTemplate::new(template)
 }
 
+/// Show the contents of a release (file list, description, submitter) in the match modal
+#[axum::debug_handler]
+pub async fn torrent_details(Query(payload): Query<TorrentDetailsQuery>) -> impl IntoResponse {
+    let template = match fetch_torrent_details(&payload.url).await {
+        Ok(details) => TorrentDetailsTemplate {
+            url: payload.url,
+            details: Some(details),
+            error: None,
+        },
+        Err(err) => {
+            eprintln!("Failed to fetch torrent details for {}: {:?}", payload.url, err);
+            TorrentDetailsTemplate {
+                url: payload.url,
+                details: None,
+                error: Some(err.to_string()),
+            }
+        }
+    };
+
+    HtmlTemplate::new(template)
+}
+
 /// Save show with selected alternate name
 #[axum::debug_handler]
 pub async fn confirm_match(
     State(state): State<Arc<Mutex<UserState>>>,
-    Query(payload): Query<ConfirmMatchQuery>,
+    Form(payload): Form<ConfirmMatchForm>,
 ) -> impl IntoResponse {
     let mut lock = state.lock().await;
 
@@ -1245,9 +2090,10 @@ pub async fn confirm_match(
     };
     lock.tracker.insert(payload.id, entry);
 
-    // Auto-detect season from the alternate title
-    let season_info = detect_season(&payload.alternate);
-    let detected_season = season_info.season;
+    // Use the season picked in the match selection, or detect it from the alternate title
+    let detected_season = payload
+        .season
+        .unwrap_or_else(|| detect_season(&payload.alternate).season);
 
     // Use detected source from match selection, or default to "subsplease"
     let detected_source = payload
@@ -1259,6 +2105,7 @@ pub async fn confirm_match(
     let show_id = payload.id;
     let title = payload.title.clone();
     let alternate = payload.alternate.clone();
+    let matched_title = payload.alternate.clone();
     let latest_episode = payload.latest_episode.clone();
     let next_air_date = payload.next_air_date.clone();
 
@@ -1287,6 +2134,11 @@ pub async fn confirm_match(
                 next_air_date: Some(next_air_date),
                 created_at: None,
                 updated_at: None,
+                nyaa_category: None,
+                language: None,
+                query_template: None,
+                nyaa_user_feed: false,
+                skip_anilist_progress: false,
             };
             db::insert_show(conn, &new_show)?;
         }
@@ -1296,6 +2148,9 @@ pub async fn confirm_match(
 
     if let Err(err) = db_result {
         eprintln!("Could not save to database: {:?}", err);
+    } else {
+        drop(lock);
+        seed_show_aliases(show_id, Some(matched_title)).await;
     }
 
     // Return empty HTML with trigger to update the tracker table
@@ -1306,7 +2161,7 @@ pub async fn confirm_match(
 #[axum::debug_handler]
 pub async fn skip_match_selection(
     State(state): State<Arc<Mutex<UserState>>>,
-    Query(payload): Query<SkipMatchQuery>,
+    Form(payload): Form<SkipMatchForm>,
 ) -> impl IntoResponse {
     let mut lock = state.lock().await;
 
@@ -1320,9 +2175,10 @@ pub async fn skip_match_selection(
     };
     lock.tracker.insert(payload.id, entry);
 
-    // Auto-detect season from the title
-    let season_info = detect_season(&payload.title);
-    let detected_season = season_info.season;
+    // Use the season picked in the match selection, or detect it from the title
+    let detected_season = payload
+        .season
+        .unwrap_or_else(|| detect_season(&payload.title).season);
 
     // Save to database with original title as alternate
     let show_id = payload.id;
@@ -1353,6 +2209,11 @@ pub async fn skip_match_selection(
                 next_air_date: Some(next_air_date),
                 created_at: None,
                 updated_at: None,
+                nyaa_category: None,
+                language: None,
+                query_template: None,
+                nyaa_user_feed: false,
+                skip_anilist_progress: false,
             };
             db::insert_show(conn, &new_show)?;
         }
@@ -1362,6 +2223,9 @@ pub async fn skip_match_selection(
 
     if let Err(err) = db_result {
         eprintln!("Could not save to database: {:?}", err);
+    } else {
+        drop(lock);
+        seed_show_aliases(show_id, None).await;
     }
 
     // Return empty HTML with trigger to update the tracker table
@@ -1630,3 +2494,521 @@ pub async fn delete_show_filter(
 
 
 
+// ============================================================================
+// Show Aliases
+// ============================================================================
+
+/// Form data for adding an alias
+#[derive(Debug, Deserialize)]
+pub struct AliasForm {
+    pub alias: String,
+}
+
+/// Path parameters for a show's alias
+#[derive(Debug, Deserialize)]
+pub struct ShowAliasIdPath {
+    pub show_id: u32,
+    pub alias_id: u32,
+}
+
+/// Load a show's aliases, logging and returning none on failure
+async fn load_show_aliases(show_id: u32) -> Vec<db::ShowAlias> {
+    db::with_db(move |conn| db::get_show_aliases(conn, show_id))
+        .await
+        .unwrap_or_else(|err| {
+            eprintln!("Failed to get aliases for show {}: {:?}", show_id, err);
+            Vec::new()
+        })
+}
+
+/// Render the alias list of a show
+#[axum::debug_handler]
+pub async fn show_aliases(
+    axum::extract::Path(path): axum::extract::Path<ShowIdPath>,
+) -> impl IntoResponse {
+    HtmlTemplate::new(AliasesTemplate {
+        show_id: path.show_id,
+        aliases: load_show_aliases(path.show_id).await,
+    })
+}
+
+/// Add an alias to a show by hand
+#[axum::debug_handler]
+pub async fn create_show_alias(
+    axum::extract::Path(path): axum::extract::Path<ShowIdPath>,
+    Form(payload): Form<AliasForm>,
+) -> impl IntoResponse {
+    let show_id = path.show_id;
+    let result = db::with_db(move |conn| {
+        db::add_show_alias(conn, show_id, &payload.alias, AliasOrigin::Manual)
+    })
+    .await;
+
+    if let Err(err) = result {
+        eprintln!("Failed to add alias to show {}: {:?}", show_id, err);
+    }
+
+    HtmlTemplate::new(AliasesTemplate {
+        show_id,
+        aliases: load_show_aliases(show_id).await,
+    })
+}
+
+/// Remove an alias from a show
+#[axum::debug_handler]
+pub async fn delete_show_alias(
+    axum::extract::Path(path): axum::extract::Path<ShowAliasIdPath>,
+) -> impl IntoResponse {
+    let (show_id, alias_id) = (path.show_id, path.alias_id);
+    if let Err(err) = db::with_db(move |conn| db::delete_show_alias(conn, show_id, alias_id)).await {
+        eprintln!("Failed to delete alias {}: {:?}", alias_id, err);
+    }
+
+    HtmlTemplate::new(AliasesTemplate {
+        show_id: path.show_id,
+        aliases: load_show_aliases(path.show_id).await,
+    })
+}
+
+/// Search the show's source under a single alias, to check it finds releases
+#[axum::debug_handler]
+pub async fn test_show_alias(
+    axum::extract::Path(path): axum::extract::Path<ShowAliasIdPath>,
+) -> impl IntoResponse {
+    let (show_id, alias_id) = (path.show_id, path.alias_id);
+    let lookup = db::with_db(move |conn| {
+        let alias = db::get_show_alias(conn, alias_id)?.filter(|a| a.show_id == show_id);
+        let source = db::get_show(conn, show_id)?.map(|show| show.source);
+        Ok(alias.map(|a| (a.alias, source.unwrap_or_else(|| "subsplease".to_string()))))
+    })
+    .await;
+
+    let (alias, source) = match lookup {
+        Ok(Some(found)) => found,
+        Ok(None) => return Html("<span class=\"text-red-400\">Not found</span>".to_string()).into_response(),
+        Err(err) => {
+            eprintln!("Failed to look up alias {}: {:?}", alias_id, err);
+            return Html("<span class=\"text-red-400\">Failed</span>".to_string()).into_response();
+        }
+    };
+
+    let category = nyaa_category(Some(show_id)).await;
+    let matches = search_rss_matches(&source, &alias, &category).await;
+    HtmlTemplate::new(AliasTestTemplate { matches }).into_response()
+}
+
+/// Raw results of the configure modal's Nyaa query, before any filtering
+#[derive(Template)]
+#[template(path = "components/query_test.html")]
+pub struct QueryTestTemplate {
+    pub query: String,
+    pub items: Vec<RssItem>,
+    pub error: Option<String>,
+}
+
+/// Run the configure modal's (unsaved) Nyaa query for the search title and show what it finds
+#[axum::debug_handler]
+pub async fn test_query(Query(payload): Query<TrackerDataEntry>) -> impl IntoResponse {
+    let category = match non_blank(payload.nyaa_category) {
+        Some(category) => category,
+        None => nyaa_category(None).await,
+    };
+    let search = NyaaSearch {
+        category,
+        query_template: non_blank(payload.query_template),
+        user_feed: payload.nyaa_user_feed,
+    };
+    let group = fansub::feed_name(&payload.source);
+    let url = search.feed_url(&group, &payload.alternate, &payload.quality);
+
+    let (items, error) = match fetch_nyaa_rss(&url).await {
+        Ok(items) => (items, None),
+        Err(err) => {
+            eprintln!("Query test failed for '{}': {:?}", payload.alternate, err);
+            (Vec::new(), Some(format!("Search failed: {}", err)))
+        }
+    };
+
+    HtmlTemplate::new(QueryTestTemplate {
+        query: search.query(&group, &payload.alternate, &payload.quality),
+        items,
+        error,
+    })
+}
+
+// ============================================================================
+// Download History
+// ============================================================================
+
+#[derive(Template)]
+#[template(path = "components/history.html")]
+pub struct HistoryTemplate {
+    pub title: String,
+    pub records: Vec<db::models::DownloadRecord>,
+}
+
+/// Path parameter for a download history record
+#[derive(Debug, Deserialize)]
+pub struct HistoryIdPath {
+    pub id: u32,
+}
+
+/// Show the download history of a show, with re-add actions
+#[axum::debug_handler]
+pub async fn show_history(
+    axum::extract::Path(path): axum::extract::Path<ShowIdPath>,
+) -> impl IntoResponse {
+    let show_id = path.show_id;
+    let result = db::with_db(move |conn| {
+        let title = db::get_show(conn, show_id)?
+            .map(|show| show.title)
+            .unwrap_or_else(|| "Unknown".to_string());
+        let records = db::get_show_history(conn, show_id)?;
+        Ok((title, records))
+    })
+    .await;
+
+    let (title, records) = result.unwrap_or_else(|err| {
+        eprintln!("Failed to get download history: {:?}", err);
+        ("Unknown".to_string(), Vec::new())
+    });
+
+    HtmlTemplate::new(HistoryTemplate { title, records })
+}
+
+/// Re-add a previously downloaded release to Transmission
+///
+/// Uses the archived .torrent when there is one, otherwise falls back to a magnet
+/// built from the recorded hash or the original torrent URL.
+#[axum::debug_handler]
+pub async fn readd_download(
+    axum::extract::Path(path): axum::extract::Path<HistoryIdPath>,
+) -> impl IntoResponse {
+    let id = path.id;
+    let lookup = db::with_db(move |conn| {
+        let Some(record) = db::get_download(conn, id)? else {
+            return Ok(None);
+        };
+        let show = db::get_show(conn, record.show_id)?;
+        Ok(Some((record, show)))
+    })
+    .await;
+
+    let (record, show) = match lookup {
+        Ok(Some(found)) => found,
+        Ok(None) => return Html("<span class=\"text-red-400\">Not found</span>".to_string()),
+        Err(err) => {
+            eprintln!("Failed to look up download {}: {:?}", id, err);
+            return Html("<span class=\"text-red-400\">Failed</span>".to_string());
+        }
+    };
+
+    let source = show.as_ref().map(|s| s.source.clone()).unwrap_or_default();
+    let (show_name, season) = match show {
+        Some(show) if !show.alternate.is_empty() => (show.alternate, Some(show.season)),
+        Some(show) => (show.title, Some(show.season)),
+        None => (format!("Show {}", record.show_id), None),
+    };
+
+    let archived = record
+        .torrent_file
+        .as_deref()
+        .and_then(|file| match load_torrent(file) {
+            Ok(data) => Some(data),
+            Err(err) => {
+                eprintln!("Archived torrent unavailable, falling back: {:?}", err);
+                None
+            }
+        });
+
+    let result = match archived {
+        Some(data) => upload_torrent_file(&data, &show_name, season).await,
+        None => {
+            // Hashes recorded as "subsplease:<url>" aren't real info hashes
+            let link = if record.info_hash.contains(':') {
+                record.torrent_url.clone().unwrap_or_default()
+            } else {
+                let trackers = db::with_db(move |conn| db::get_trackers_for_source(conn, &source))
+                    .await
+                    .unwrap_or_default();
+                construct_magnet_url(&record.info_hash, &show_name, &trackers)
+            };
+            if link.is_empty() {
+                return Html("<span class=\"text-red-400\">No source to re-add from</span>".to_string());
+            }
+            upload_to_transmission_rpc(vec![link], &show_name, season).await
+        }
+    };
+
+    match result {
+        Ok(_) => Html("<span class=\"text-green-400\">Re-added</span>".to_string()),
+        Err(err) => {
+            eprintln!("Failed to re-add download {}: {:?}", id, err);
+            Html(format!(
+                "<span class=\"text-red-400\">Failed: {}</span>",
+                MarkupDisplay::new_unsafe(err.to_string(), askama::Html)
+            ))
+        }
+    }
+}
+
+// ============================================================================
+// Magnet Tracker API Endpoints
+// ============================================================================
+
+/// Get all magnet trackers
+#[axum::debug_handler]
+pub async fn get_trackers() -> impl IntoResponse {
+    match db::with_db(db::get_all_trackers).await {
+        Ok(trackers) => Json(trackers).into_response(),
+        Err(err) => {
+            eprintln!("Failed to get trackers: {:?}", err);
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to get trackers",
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Add a magnet tracker, optionally scoped to a fansub source
+#[axum::debug_handler]
+pub async fn create_tracker(Form(payload): Form<db::CreateMagnetTracker>) -> impl IntoResponse {
+    if payload.url.trim().is_empty() {
+        return (axum::http::StatusCode::BAD_REQUEST, "Tracker URL is required").into_response();
+    }
+
+    match db::with_db(move |conn| db::create_tracker(conn, &payload)).await {
+        Ok(id) => Json(serde_json::json!({"status": "ok", "id": id})).into_response(),
+        Err(err) => {
+            eprintln!("Failed to create tracker: {:?}", err);
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to create tracker",
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Path parameter for magnet tracker ID
+#[derive(Debug, Deserialize)]
+pub struct TrackerIdPath {
+    pub id: u32,
+}
+
+/// Delete a magnet tracker
+#[axum::debug_handler]
+pub async fn delete_tracker(
+    axum::extract::Path(path): axum::extract::Path<TrackerIdPath>,
+) -> impl IntoResponse {
+    let tracker_id = path.id;
+    match db::with_db(move |conn| db::delete_tracker(conn, tracker_id)).await {
+        Ok(true) => Json(serde_json::json!({"status": "ok"})).into_response(),
+        Ok(false) => (axum::http::StatusCode::NOT_FOUND, "Tracker not found").into_response(),
+        Err(err) => {
+            eprintln!("Failed to delete tracker: {:?}", err);
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to delete tracker",
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Toggle a magnet tracker's enabled status
+#[axum::debug_handler]
+pub async fn toggle_tracker(
+    axum::extract::Path(path): axum::extract::Path<TrackerIdPath>,
+) -> impl IntoResponse {
+    let tracker_id = path.id;
+    match db::with_db(move |conn| db::toggle_tracker(conn, tracker_id)).await {
+        Ok(true) => Json(serde_json::json!({"status": "ok"})).into_response(),
+        Ok(false) => (axum::http::StatusCode::NOT_FOUND, "Tracker not found").into_response(),
+        Err(err) => {
+            eprintln!("Failed to toggle tracker: {:?}", err);
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to toggle tracker",
+            )
+                .into_response()
+        }
+    }
+}
+
+// ============================================================================
+// Fansub Group API Endpoints
+// ============================================================================
+
+#[derive(Template)]
+#[template(path = "components/fansub_groups.html")]
+pub struct FansubGroupsTemplate {
+    pub groups: Vec<db::FansubGroup>,
+}
+
+/// Path parameter for fansub group ID
+#[derive(Debug, Deserialize)]
+pub struct FansubGroupIdPath {
+    pub id: u32,
+}
+
+/// Reload the in-memory registry after an edit and tell the page to refresh
+async fn fansub_groups_changed(body: serde_json::Value) -> axum::response::Response {
+    if let Err(err) = fansub::reload_groups().await {
+        eprintln!("Failed to reload fansub groups: {:?}", err);
+    }
+    ([("HX-Trigger", "fansubGroupsChanged")], Json(body)).into_response()
+}
+
+/// Get all fansub groups
+#[axum::debug_handler]
+pub async fn get_fansub_groups() -> impl IntoResponse {
+    match db::with_db(db::get_all_fansub_groups).await {
+        Ok(groups) => Json(groups).into_response(),
+        Err(err) => {
+            eprintln!("Failed to get fansub groups: {:?}", err);
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to get fansub groups",
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Render the fansub group manager modal
+#[axum::debug_handler]
+pub async fn manage_fansub_groups() -> impl IntoResponse {
+    let groups = db::with_db(db::get_all_fansub_groups)
+        .await
+        .unwrap_or_else(|err| {
+            eprintln!("Failed to get fansub groups: {:?}", err);
+            fansub::all_groups()
+        });
+
+    HtmlTemplate::new(FansubGroupsTemplate { groups })
+}
+
+/// Create a fansub group
+#[axum::debug_handler]
+pub async fn create_fansub_group(Form(payload): Form<db::SaveFansubGroup>) -> impl IntoResponse {
+    if payload.name.trim().is_empty() {
+        return (axum::http::StatusCode::BAD_REQUEST, "Group name is required").into_response();
+    }
+
+    match db::with_db(move |conn| db::create_fansub_group(conn, &payload)).await {
+        Ok(id) => fansub_groups_changed(serde_json::json!({"status": "ok", "id": id})).await,
+        Err(err) => {
+            eprintln!("Failed to create fansub group: {:?}", err);
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to create fansub group",
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Update a fansub group
+#[axum::debug_handler]
+pub async fn update_fansub_group(
+    axum::extract::Path(path): axum::extract::Path<FansubGroupIdPath>,
+    Form(payload): Form<db::SaveFansubGroup>,
+) -> impl IntoResponse {
+    if payload.name.trim().is_empty() {
+        return (axum::http::StatusCode::BAD_REQUEST, "Group name is required").into_response();
+    }
+
+    let group_id = path.id;
+    match db::with_db(move |conn| db::update_fansub_group(conn, group_id, &payload)).await {
+        Ok(true) => fansub_groups_changed(serde_json::json!({"status": "ok"})).await,
+        Ok(false) => (axum::http::StatusCode::NOT_FOUND, "Fansub group not found").into_response(),
+        Err(err) => {
+            eprintln!("Failed to update fansub group: {:?}", err);
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to update fansub group",
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Delete a fansub group
+#[axum::debug_handler]
+pub async fn delete_fansub_group(
+    axum::extract::Path(path): axum::extract::Path<FansubGroupIdPath>,
+) -> impl IntoResponse {
+    let group_id = path.id;
+    match db::with_db(move |conn| db::delete_fansub_group(conn, group_id)).await {
+        Ok(true) => fansub_groups_changed(serde_json::json!({"status": "ok"})).await,
+        Ok(false) => (axum::http::StatusCode::NOT_FOUND, "Fansub group not found").into_response(),
+        Err(err) => {
+            eprintln!("Failed to delete fansub group: {:?}", err);
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to delete fansub group",
+            )
+                .into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request, routing::post, Router};
+    use tower::ServiceExt;
+
+    async fn post_form(uri: &str, body: &str) {
+        let state = Arc::new(Mutex::new(UserState::new(String::new(), HashMap::new())));
+        let app = Router::new()
+            .route("/api/confirm_match", post(confirm_match))
+            .route("/api/skip_match_selection", post(skip_match_selection))
+            .with_state(state);
+        let response = app
+            .oneshot(
+                Request::post(uri)
+                    .header("Content-Type", "application/x-www-form-urlencoded")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+    }
+
+    async fn saved_season(id: u32) -> u8 {
+        db::with_db(move |conn| db::get_show(conn, id)).await.unwrap().unwrap().season
+    }
+
+    #[tokio::test]
+    async fn test_match_selection_uses_posted_season() {
+        db::init_test_connection();
+        // Shows added by hand, so no AniList titles are fetched for their aliases
+        let (skipped, confirmed) = (db::shows::MANUAL_SHOW_ID_START + 500, db::shows::MANUAL_SHOW_ID_START + 501);
+
+        // The title says season 2, the user picked specials
+        post_form(
+            "/api/skip_match_selection",
+            &format!(
+                "id={}&title=Frieren+Season+2&latest_episode=1&next_air_date=N%2FA&alternate=Frieren&season=0",
+                skipped
+            ),
+        )
+        .await;
+        assert_eq!(saved_season(skipped).await, 0);
+
+        post_form(
+            "/api/confirm_match",
+            &format!(
+                "id={}&title=Frieren&alternate=Sousou+no+Frieren+S2&latest_episode=1&next_air_date=N%2FA&season=14",
+                confirmed
+            ),
+        )
+        .await;
+        assert_eq!(saved_season(confirmed).await, 14);
+    }
+}
@@ -0,0 +1,127 @@
+//! Shows added by hand
+//!
+//! Shows that AniList doesn't list can still be tracked by entering their title and
+//! search settings directly. They get IDs from [`db::MANUAL_SHOW_ID_START`] upwards
+//! so they never collide with AniList IDs, and are left out of everything that
+//! would look them up on AniList.
+
+use askama::Template;
+use axum::{
+    extract::State,
+    response::{Html, IntoResponse},
+    Form,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use super::home::{source_options, SourceOption, TableEntry, UserState};
+use crate::{
+    db::{self, models::Show},
+    pages::HtmlTemplate,
+};
+
+#[derive(Template)]
+#[template(path = "components/manual_show.html")]
+pub struct ManualShowTemplate {
+    pub source: String,
+    pub error: Option<String>,
+}
+
+impl ManualShowTemplate {
+    /// Source dropdown entries, one per registered fansub group
+    pub fn source_options(&self) -> Vec<SourceOption> {
+        source_options(&self.source)
+    }
+}
+
+/// Form for adding a show by hand
+#[derive(Debug, Deserialize)]
+pub struct ManualShowForm {
+    pub title: String,
+    /// Title to search Nyaa for; the title is used when empty
+    #[serde(default)]
+    pub alternate: String,
+    pub source: String,
+    pub season: u8,
+    pub quality: String,
+}
+
+/// Add a tracked show that has no AniList entry
+pub async fn add_manual_show(
+    state: &Arc<Mutex<UserState>>,
+    form: ManualShowForm,
+) -> anyhow::Result<u32> {
+    let title = form.title.trim().to_string();
+    if title.is_empty() {
+        anyhow::bail!("Title is required");
+    }
+    if form.season == 0 {
+        anyhow::bail!("Season must be 1 or higher");
+    }
+    let alternate = match form.alternate.trim() {
+        "" => title.clone(),
+        alternate => alternate.to_string(),
+    };
+
+    let show = Show {
+        title: title.clone(),
+        alternate,
+        season: form.season,
+        source: form.source,
+        quality: form.quality,
+        is_tracked: true,
+        latest_episode: Some("N/A".to_string()),
+        next_air_date: Some("N/A".to_string()),
+        ..Default::default()
+    };
+    let id = db::with_db(move |conn| {
+        let id = db::next_manual_show_id(conn)?;
+        db::insert_show(conn, &Show { id, ..show })?;
+        Ok(id)
+    })
+    .await?;
+
+    state.lock().await.tracker.insert(
+        id,
+        TableEntry {
+            title,
+            latest_episode: "N/A".to_string(),
+            next_air_date: "N/A".to_string(),
+            is_tracked: true,
+            id,
+        },
+    );
+
+    Ok(id)
+}
+
+/// Show the form for adding a show by hand
+#[axum::debug_handler]
+pub async fn manual_show_form() -> impl IntoResponse {
+    HtmlTemplate::new(ManualShowTemplate {
+        source: Show::default().source,
+        error: None,
+    })
+}
+
+/// Add a show by hand and start tracking it
+#[axum::debug_handler]
+pub async fn create_manual_show(
+    State(state): State<Arc<Mutex<UserState>>>,
+    Form(payload): Form<ManualShowForm>,
+) -> impl IntoResponse {
+    let source = payload.source.clone();
+    match add_manual_show(&state, payload).await {
+        // Closes the modal and refreshes the show table
+        Ok(_) => ([("HX-Trigger", "newTrackerStatus")], Html("")).into_response(),
+        Err(err) => {
+            eprintln!("Failed to add show: {:?}", err);
+            HtmlTemplate::new(ManualShowTemplate {
+                source,
+                error: Some(format!("Failed to add show: {}", err)),
+            })
+            .into_response()
+        }
+    }
+}
@@ -1,6 +1,11 @@
 mod html_template;
 mod filters;
 pub mod home;
+pub mod anilist_list;
+pub mod sequels;
+pub mod manual_shows;
+pub mod sync_schedules;
+pub mod activity;
 pub mod anime;
 
 pub use html_template::*;
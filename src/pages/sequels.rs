@@ -0,0 +1,329 @@
+//! Detection of upcoming sequels
+//!
+//! Once a day the AniList relations of our shows are checked for sequels that are
+//! airing or announced. New ones are listed above the show table, and tracked right
+//! away when auto-tracking is on, with the predecessor's source and search settings.
+
+use askama::Template;
+use axum::{extract::State, response::IntoResponse, Form};
+use serde::Deserialize;
+use std::{collections::HashSet, sync::Arc, time::Duration};
+use tokio::sync::Mutex;
+
+use super::home::{get_next_airing_episode, seed_show_aliases, TableEntry, UserState};
+use crate::{
+    db::{self, models::Show, MetadataSource, Sequel},
+    pages::HtmlTemplate,
+    scraper::{
+        anilist::{get_anilist_shows, MediaStatus},
+        metadata,
+        season_parser::detect_season,
+    },
+};
+
+/// How often relations are checked for new sequels
+const SEQUEL_CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// What a sequel check found
+#[derive(Debug, Default)]
+pub struct SequelCheckReport {
+    /// Titles of newly found sequels
+    pub found: Vec<String>,
+    /// Titles of sequels that were tracked automatically
+    pub tracked: Vec<String>,
+}
+
+/// Season number for a sequel
+///
+/// Goes by the sequel's title when it has a reliable season marker, otherwise
+/// follows on from the predecessor's season.
+fn sequel_season(title: &str, prequel_season: u8) -> u8 {
+    let info = detect_season(title);
+    if info.is_confident() {
+        info.season
+    } else {
+        prequel_season.saturating_add(1)
+    }
+}
+
+/// Look for airing or announced sequels of tracked and finished shows
+pub async fn check_sequels(state: &Arc<Mutex<UserState>>) -> anyhow::Result<SequelCheckReport> {
+    let (shows, config) =
+        db::with_db(|conn| Ok((db::get_all_shows(conn)?, db::get_rss_config(conn)?))).await?;
+    let known: HashSet<u32> = shows.iter().map(|s| s.id).collect();
+    let tracked: HashSet<u32> = shows.iter().filter(|s| s.is_tracked).map(|s| s.id).collect();
+
+    let ids: Vec<u32> = shows.iter().filter(|s| !s.is_manual()).map(|s| s.id).collect();
+    let media = if ids.is_empty() {
+        Vec::new()
+    } else {
+        get_anilist_shows(&ids).await?
+    };
+
+    let mut report = SequelCheckReport::default();
+    for show in &media {
+        let Some(prequel_id) = show.id else {
+            continue;
+        };
+        if !tracked.contains(&prequel_id) && show.status != Some(MediaStatus::Finished) {
+            continue;
+        }
+
+        for related in show.related("SEQUEL") {
+            let Some(id) = related.id else {
+                continue;
+            };
+            let upcoming = matches!(
+                related.status,
+                Some(MediaStatus::Releasing | MediaStatus::NotYetReleased)
+            );
+            // Movies, specials and music videos aren't new seasons to track
+            let episodic = related.format.is_none_or(|format| format.is_episodic());
+            if !upcoming || !episodic || known.contains(&id) {
+                continue;
+            }
+
+            let title = related
+                .title
+                .as_ref()
+                .and_then(|t| t.romaji.clone().or_else(|| t.english.clone()))
+                .unwrap_or_else(|| id.to_string());
+            let sequel = Sequel {
+                id,
+                prequel_id,
+                title: title.clone(),
+                airing: match (related.season, related.season_year) {
+                    (Some(season), Some(year)) => Some(format!("{} {}", season, year)),
+                    (None, Some(year)) => Some(year.to_string()),
+                    _ => None,
+                },
+                dismissed: false,
+                detected_at: None,
+            };
+
+            let is_new = db::with_db(move |conn| db::record_sequel(conn, &sequel)).await?;
+            if !is_new {
+                continue;
+            }
+            report.found.push(title.clone());
+
+            if config.auto_track_sequels {
+                match track_sequel(state, id).await {
+                    Ok(_) => report.tracked.push(title),
+                    Err(err) => eprintln!("Failed to track sequel {}: {:?}", title, err),
+                }
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Track a detected sequel with its predecessor's source, quality and search settings
+pub async fn track_sequel(state: &Arc<Mutex<UserState>>, sequel_id: u32) -> anyhow::Result<String> {
+    let (sequel, prequel) = db::with_db(move |conn| {
+        let sequel = db::get_sequel(conn, sequel_id)?
+            .ok_or_else(|| anyhow::anyhow!("Unknown sequel {}", sequel_id))?;
+        let prequel = db::get_show(conn, sequel.prequel_id)?.unwrap_or_default();
+        Ok((sequel, prequel))
+    })
+    .await?;
+
+    // Airing info isn't part of the relation, so fetch the sequel itself
+    let media = metadata::provider(MetadataSource::AniList)
+        .details(sequel_id)
+        .await
+        .unwrap_or_else(|err| {
+            eprintln!("Failed to fetch AniList data for sequel {}: {:?}", sequel_id, err);
+            None
+        });
+    let (latest_episode, next_air_date) =
+        get_next_airing_episode(&media.and_then(|m| m.show.next_airing_episode));
+
+    let show = Show {
+        id: sequel_id,
+        title: sequel.title.clone(),
+        alternate: sequel.title.clone(),
+        season: sequel_season(&sequel.title, prequel.season),
+        is_tracked: true,
+        latest_episode: Some(latest_episode.clone()),
+        next_air_date: Some(next_air_date.clone()),
+        last_downloaded_episode: 0,
+        last_downloaded_hash: None,
+        created_at: None,
+        updated_at: None,
+        // Same source, quality and search settings as the predecessor
+        ..prequel
+    };
+    db::with_db(move |conn| db::insert_show(conn, &show)).await?;
+    seed_show_aliases(sequel_id, None).await;
+
+    state.lock().await.tracker.insert(
+        sequel_id,
+        TableEntry {
+            title: sequel.title.clone(),
+            latest_episode,
+            next_air_date,
+            is_tracked: true,
+            id: sequel_id,
+        },
+    );
+
+    Ok(sequel.title)
+}
+
+/// Check for sequels once a day
+pub async fn run_sequel_check(state: Arc<Mutex<UserState>>) {
+    loop {
+        tokio::time::sleep(SEQUEL_CHECK_INTERVAL).await;
+
+        match check_sequels(&state).await {
+            Ok(report) => tracing::info!(
+                "Sequel check: {} found, {} tracked",
+                report.found.len(),
+                report.tracked.len()
+            ),
+            Err(err) => tracing::error!("Sequel check failed: {:?}", err),
+        }
+    }
+}
+
+/// Sequel with the title of the show it follows
+pub struct SequelRow {
+    pub sequel: Sequel,
+    pub prequel_title: String,
+}
+
+#[derive(Template)]
+#[template(path = "components/sequels.html")]
+pub struct SequelsTemplate {
+    pub sequels: Vec<SequelRow>,
+    pub auto_track: bool,
+    pub message: Option<String>,
+}
+
+/// Render the sequel panel with an optional status message
+async fn sequels_panel(message: Option<String>) -> SequelsTemplate {
+    let result = db::with_db(|conn| {
+        let config = db::get_rss_config(conn)?;
+        let sequels = db::get_pending_sequels(conn)?
+            .into_iter()
+            .map(|sequel| {
+                let prequel_title = db::get_show(conn, sequel.prequel_id)?
+                    .map(|s| s.title)
+                    .unwrap_or_default();
+                Ok(SequelRow {
+                    sequel,
+                    prequel_title,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok((sequels, config.auto_track_sequels))
+    })
+    .await;
+
+    let (sequels, auto_track) = result.unwrap_or_else(|err| {
+        eprintln!("Failed to load sequels: {:?}", err);
+        (Vec::new(), false)
+    });
+
+    SequelsTemplate {
+        sequels,
+        auto_track,
+        message,
+    }
+}
+
+/// Path parameter for sequel ID
+#[derive(Debug, Deserialize)]
+pub struct SequelIdPath {
+    pub id: u32,
+}
+
+/// Form for the auto-track toggle
+#[derive(Debug, Deserialize)]
+pub struct AutoTrackForm {
+    #[serde(default)]
+    pub auto_track: bool,
+}
+
+/// Show upcoming sequels
+#[axum::debug_handler]
+pub async fn get_sequels() -> impl IntoResponse {
+    HtmlTemplate::new(sequels_panel(None).await)
+}
+
+/// Check for sequels now
+#[axum::debug_handler]
+pub async fn check_sequels_now(State(state): State<Arc<Mutex<UserState>>>) -> impl IntoResponse {
+    let message = match check_sequels(&state).await {
+        Ok(report) if report.found.is_empty() => "No new sequels".to_string(),
+        Ok(report) => format!(
+            "Found {} sequel(s), tracked {}",
+            report.found.len(),
+            report.tracked.len()
+        ),
+        Err(err) => {
+            eprintln!("Sequel check failed: {:?}", err);
+            format!("Sequel check failed: {}", err)
+        }
+    };
+
+    HtmlTemplate::new(sequels_panel(Some(message)).await)
+        .with_header("HX-Trigger", "newTrackerStatus")
+}
+
+/// Track a sequel
+#[axum::debug_handler]
+pub async fn track_sequel_handler(
+    State(state): State<Arc<Mutex<UserState>>>,
+    axum::extract::Path(path): axum::extract::Path<SequelIdPath>,
+) -> impl IntoResponse {
+    let message = match track_sequel(&state, path.id).await {
+        Ok(title) => format!("Tracking {}", title),
+        Err(err) => {
+            eprintln!("Failed to track sequel {}: {:?}", path.id, err);
+            format!("Failed to track sequel: {}", err)
+        }
+    };
+
+    HtmlTemplate::new(sequels_panel(Some(message)).await)
+        .with_header("HX-Trigger", "newTrackerStatus")
+}
+
+/// Stop suggesting a sequel
+#[axum::debug_handler]
+pub async fn dismiss_sequel(
+    axum::extract::Path(path): axum::extract::Path<SequelIdPath>,
+) -> impl IntoResponse {
+    let id = path.id;
+    if let Err(err) = db::with_db(move |conn| db::dismiss_sequel(conn, id)).await {
+        eprintln!("Failed to dismiss sequel {}: {:?}", id, err);
+    }
+
+    HtmlTemplate::new(sequels_panel(None).await)
+}
+
+/// Turn sequel auto-tracking on or off
+#[axum::debug_handler]
+pub async fn set_auto_track_sequels(Form(payload): Form<AutoTrackForm>) -> impl IntoResponse {
+    let enabled = payload.auto_track;
+    if let Err(err) = db::with_db(move |conn| db::set_auto_track_sequels(conn, enabled)).await {
+        eprintln!("Failed to save sequel auto-tracking: {:?}", err);
+    }
+
+    HtmlTemplate::new(sequels_panel(None).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequel_season() {
+        // A reliable marker in the sequel's title wins
+        assert_eq!(sequel_season("Spy x Family Season 3", 1), 3);
+        // Otherwise the sequel follows on from its predecessor
+        assert_eq!(sequel_season("Kimetsu no Yaiba: Yuukaku-hen", 2), 3);
+    }
+}
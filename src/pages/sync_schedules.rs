@@ -0,0 +1,160 @@
+//! Tracker sync schedules and quiet hours
+//!
+//! Cron schedules replace the fixed polls-per-day interval when there are any.
+//! Changes wake the tracker so they apply to its next run straight away.
+
+use askama::Template;
+use axum::{response::IntoResponse, Form};
+use chrono::NaiveTime;
+use serde::Deserialize;
+
+use crate::{
+    db::{self, SyncSchedule},
+    pages::HtmlTemplate,
+    scraper::{schedule, tracker},
+};
+
+/// Upcoming runs listed in the panel
+const UPCOMING_RUNS: usize = 5;
+
+#[derive(Template)]
+#[template(path = "components/sync_schedules.html")]
+pub struct SyncSchedulesTemplate {
+    pub schedules: Vec<SyncSchedule>,
+    pub quiet_start: String,
+    pub quiet_end: String,
+    /// How the tracker currently polls
+    pub mode: String,
+    pub upcoming: Vec<String>,
+    pub error: Option<String>,
+}
+
+impl SyncSchedulesTemplate {
+    /// Ready-made schedules, as (label, cron expression)
+    pub fn presets(&self) -> &'static [(&'static str, &'static str)] {
+        schedule::SCHEDULE_PRESETS
+    }
+}
+
+/// Render the schedule panel with an optional error
+async fn schedules_panel(error: Option<String>) -> SyncSchedulesTemplate {
+    let result = db::with_db(|conn| Ok((db::get_sync_schedules(conn)?, db::get_rss_config(conn)?))).await;
+    let (schedules, config) = result.unwrap_or_else(|err| {
+        eprintln!("Failed to load sync schedules: {:?}", err);
+        (Vec::new(), Default::default())
+    });
+
+    let (mode, upcoming) = match tracker::upcoming_syncs(UPCOMING_RUNS).await {
+        Ok(syncs) => (
+            syncs.mode.label().to_string(),
+            syncs
+                .runs
+                .iter()
+                .map(|run| run.format("%a %d %b %H:%M").to_string())
+                .collect(),
+        ),
+        Err(err) => {
+            eprintln!("Failed to work out upcoming syncs: {:?}", err);
+            (String::new(), Vec::new())
+        }
+    };
+
+    SyncSchedulesTemplate {
+        schedules,
+        quiet_start: config.quiet_hours_start.unwrap_or_default(),
+        quiet_end: config.quiet_hours_end.unwrap_or_default(),
+        mode,
+        upcoming,
+        error,
+    }
+}
+
+/// Path parameter for sync schedule ID
+#[derive(Debug, Deserialize)]
+pub struct SyncScheduleIdPath {
+    pub id: i64,
+}
+
+/// Form for adding a sync schedule
+#[derive(Debug, Deserialize)]
+pub struct SyncScheduleForm {
+    pub expression: String,
+}
+
+/// Form for the quiet hours; both blank turns them off
+#[derive(Debug, Deserialize)]
+pub struct QuietHoursForm {
+    #[serde(default)]
+    pub start: String,
+    #[serde(default)]
+    pub end: String,
+}
+
+/// Show the sync schedules, quiet hours and upcoming runs
+#[axum::debug_handler]
+pub async fn get_sync_schedules() -> impl IntoResponse {
+    HtmlTemplate::new(schedules_panel(None).await)
+}
+
+/// Add a sync schedule
+#[axum::debug_handler]
+pub async fn create_sync_schedule(Form(payload): Form<SyncScheduleForm>) -> impl IntoResponse {
+    if let Err(err) = schedule::parse_cron(&payload.expression) {
+        return HtmlTemplate::new(schedules_panel(Some(format!("{:#}", err))).await);
+    }
+
+    let expression = payload.expression;
+    match db::with_db(move |conn| db::create_sync_schedule(conn, &expression)).await {
+        Ok(_) => tracker::reschedule(),
+        Err(err) => eprintln!("Failed to create sync schedule: {:?}", err),
+    }
+
+    HtmlTemplate::new(schedules_panel(None).await)
+}
+
+/// Enable or disable a sync schedule
+#[axum::debug_handler]
+pub async fn toggle_sync_schedule(
+    axum::extract::Path(path): axum::extract::Path<SyncScheduleIdPath>,
+) -> impl IntoResponse {
+    let id = path.id;
+    match db::with_db(move |conn| db::toggle_sync_schedule(conn, id)).await {
+        Ok(_) => tracker::reschedule(),
+        Err(err) => eprintln!("Failed to toggle sync schedule {}: {:?}", id, err),
+    }
+
+    HtmlTemplate::new(schedules_panel(None).await)
+}
+
+/// Delete a sync schedule
+#[axum::debug_handler]
+pub async fn delete_sync_schedule(
+    axum::extract::Path(path): axum::extract::Path<SyncScheduleIdPath>,
+) -> impl IntoResponse {
+    let id = path.id;
+    match db::with_db(move |conn| db::delete_sync_schedule(conn, id)).await {
+        Ok(_) => tracker::reschedule(),
+        Err(err) => eprintln!("Failed to delete sync schedule {}: {:?}", id, err),
+    }
+
+    HtmlTemplate::new(schedules_panel(None).await)
+}
+
+/// Save the quiet hours
+#[axum::debug_handler]
+pub async fn save_quiet_hours(Form(payload): Form<QuietHoursForm>) -> impl IntoResponse {
+    let (start, end) = (payload.start.trim().to_string(), payload.end.trim().to_string());
+    let invalid = [&start, &end]
+        .into_iter()
+        .find(|time| !time.is_empty() && NaiveTime::parse_from_str(time, "%H:%M").is_err());
+    if let Some(time) = invalid {
+        return HtmlTemplate::new(schedules_panel(Some(format!("Invalid time '{}', use HH:MM", time))).await);
+    }
+
+    match db::with_db(move |conn| db::update_quiet_hours(conn, Some(&start), Some(&end))).await {
+        Ok(_) => tracker::reschedule(),
+        Err(err) => eprintln!("Failed to save quiet hours: {:?}", err),
+    }
+
+    HtmlTemplate::new(schedules_panel(None).await)
+}
@@ -0,0 +1,198 @@
+//! Tracker activity
+//!
+//! The crate's `tracing` events at info and above are kept in a short history and
+//! broadcast to the live activity panel. Tracker steps are tagged with an
+//! `activity` field (`"feed"`, `"filter"` or `"grab"`) and a `show` field naming
+//! the show they concern; everything else shows up by its level.
+
+use chrono::Utc;
+use serde::Serialize;
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, OnceLock,
+    },
+};
+use tokio::sync::broadcast;
+use tracing::{
+    field::{Field, Visit},
+    Level, Subscriber,
+};
+use tracing_subscriber::{filter::Targets, layer::Context, Layer};
+
+/// Number of events kept for a freshly opened activity panel
+pub const HISTORY_LEN: usize = 200;
+
+/// What kind of thing the tracker did
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ActivityKind {
+    /// Fetching a show's feed
+    Feed,
+    /// Filtering a show's releases
+    Filter,
+    /// Sending a release to Transmission
+    Grab,
+    Error,
+    Warning,
+    Info,
+}
+
+impl ActivityKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ActivityKind::Feed => "feed",
+            ActivityKind::Filter => "filter",
+            ActivityKind::Grab => "grabbed",
+            ActivityKind::Error => "error",
+            ActivityKind::Warning => "warning",
+            ActivityKind::Info => "info",
+        }
+    }
+}
+
+/// Something the tracker did
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ActivityEvent {
+    pub id: u64,
+    /// Unix timestamp of the event
+    pub at: i64,
+    pub kind: ActivityKind,
+    /// Title of the show the event concerns
+    pub show: Option<String>,
+    pub message: String,
+}
+
+struct Feed {
+    sender: broadcast::Sender<ActivityEvent>,
+    history: Mutex<VecDeque<ActivityEvent>>,
+    next_id: AtomicU64,
+}
+
+static FEED: OnceLock<Feed> = OnceLock::new();
+
+fn feed() -> &'static Feed {
+    FEED.get_or_init(|| Feed {
+        sender: broadcast::channel(HISTORY_LEN).0,
+        history: Mutex::new(VecDeque::with_capacity(HISTORY_LEN)),
+        next_id: AtomicU64::new(1),
+    })
+}
+
+/// Add an event to the history and send it to subscribers
+pub fn record(kind: ActivityKind, show: Option<String>, message: String) {
+    let feed = feed();
+    let event = ActivityEvent {
+        id: feed.next_id.fetch_add(1, Ordering::Relaxed),
+        at: Utc::now().timestamp(),
+        kind,
+        show,
+        message,
+    };
+
+    {
+        let mut history = feed.history.lock().unwrap_or_else(|e| e.into_inner());
+        if history.len() == HISTORY_LEN {
+            history.pop_front();
+        }
+        history.push_back(event.clone());
+    }
+
+    // No subscribers just means no panel is open
+    let _ = feed.sender.send(event);
+}
+
+/// Recent events, newest first
+pub fn recent() -> Vec<ActivityEvent> {
+    let history = feed().history.lock().unwrap_or_else(|e| e.into_inner());
+    history.iter().rev().cloned().collect()
+}
+
+/// Receive events as they happen
+pub fn subscribe() -> broadcast::Receiver<ActivityEvent> {
+    feed().sender.subscribe()
+}
+
+/// Which `tracing` events make it into the activity feed
+pub fn log_filter() -> Targets {
+    Targets::new().with_target(env!("CARGO_CRATE_NAME"), Level::INFO)
+}
+
+/// Forwards `tracing` events to the activity feed
+pub struct ActivityLayer;
+
+impl<S: Subscriber> Layer<S> for ActivityLayer {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        let mut fields = ActivityFields::default();
+        event.record(&mut fields);
+
+        let kind = match (fields.activity.as_deref(), *event.metadata().level()) {
+            (Some("feed"), _) => ActivityKind::Feed,
+            (Some("filter"), _) => ActivityKind::Filter,
+            (Some("grab"), _) => ActivityKind::Grab,
+            (_, Level::ERROR) => ActivityKind::Error,
+            (_, Level::WARN) => ActivityKind::Warning,
+            _ => ActivityKind::Info,
+        };
+        record(kind, fields.show, fields.message);
+    }
+}
+
+#[derive(Default)]
+struct ActivityFields {
+    activity: Option<String>,
+    show: Option<String>,
+    message: String,
+}
+
+impl Visit for ActivityFields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "activity" => self.activity = Some(value.to_string()),
+            "show" => self.show = Some(value.to_string()),
+            "message" => self.message = value.to_string(),
+            _ => {}
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        match field.name() {
+            "activity" => self.activity = Some(format!("{:?}", value)),
+            "show" => self.show = Some(format!("{:?}", value)),
+            "message" => self.message = format!("{:?}", value),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_tracing_events_reach_the_feed() {
+        let subscriber = tracing_subscriber::registry().with(ActivityLayer.with_filter(log_filter()));
+        let mut events = subscribe();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let title = "Sousou no Frieren";
+            tracing::info!(activity = "grab", show = %title, "Downloaded: [SubsPlease] Frieren - 01");
+            tracing::error!("Failed to fetch RSS feed for 'Frieren'");
+            tracing::debug!("Not in the activity feed");
+        });
+
+        let grab = events.try_recv().unwrap();
+        assert_eq!(grab.kind, ActivityKind::Grab);
+        assert_eq!(grab.show.as_deref(), Some("Sousou no Frieren"));
+        assert_eq!(grab.message, "Downloaded: [SubsPlease] Frieren - 01");
+
+        let error = events.try_recv().unwrap();
+        assert_eq!(error.kind, ActivityKind::Error);
+        assert_eq!(error.show, None);
+        assert!(events.try_recv().is_err());
+
+        assert!(recent().iter().any(|event| event.id == error.id));
+    }
+}
@@ -1,94 +1,222 @@
+use chrono::{Datelike, Utc};
 use serde::{de, Deserialize, Deserializer, Serialize};
 use serde_json::json;
-use std::fmt;
+use std::{collections::HashMap, fmt};
+
+use super::query_cache;
+use super::title_matcher::normalize_title;
+
+/// Results per page; AniList's maximum
+const PER_PAGE: u32 = 50;
+
+/// Upper bound on pages fetched for one query, in case `hasNextPage` never ends
+const MAX_PAGES: u32 = 20;
+
+/// How long the current and upcoming seasons stay cached; past seasons never expire
+const SEASONAL_TTL_SECS: i64 = 6 * 60 * 60;
+
+/// How long the currently airing list stays cached
+pub const AIRING_TTL_SECS: i64 = 60 * 60;
 
 const SEASONAL: &str = "
-query ($season: MediaSeason, $seasonYear: Int){
-  Page {
+query ($season: MediaSeason, $seasonYear: Int, $page: Int, $perPage: Int){
+  Page (page: $page, perPage: $perPage) {
+    pageInfo {
+      hasNextPage
+    }
     media (season: $season, seasonYear: $seasonYear, type: ANIME){
-      id
-      title {
-        romaji
-        english
-        native
-      }
-      description
-      episodes
-      duration
-      averageScore
-      meanScore
-      popularity
-      genres
-      studios (isMain: true) {
-          nodes {
-              name
-          }
-      }
-      coverImage {
-          medium
-          large
-          extraLarge
-      }
-      startDate {
-          year
-          month
-          day
-      }
-      nextAiringEpisode {
-          episode
-          airingAt
+      ...mediaFields
+    }
+  }
+}
+";
+
+const MEDIA_BY_ID: &str = "
+query ($ids: [Int], $page: Int, $perPage: Int){
+  Page (page: $page, perPage: $perPage) {
+    pageInfo {
+      hasNextPage
+    }
+    media (id_in: $ids, type: ANIME){
+      ...mediaFields
+    }
+  }
+}
+";
+
+const MEDIA_TITLES: &str = "
+query ($id: Int) {
+  Media (id: $id, type: ANIME) {
+    id
+    title {
+      romaji
+      english
+      native
+    }
+    synonyms
+    coverImage {
+      large
+      extraLarge
+    }
+  }
+}
+";
+
+const USER_LIST: &str = "
+query ($userName: String) {
+  MediaListCollection (userName: $userName, type: ANIME, status_in: [CURRENT, PLANNING]) {
+    lists {
+      entries {
+        status
+        media {
+          ...mediaFields
+        }
       }
     }
+  }
+}
+";
+
+const SEARCH: &str = "
+query ($search: String, $page: Int, $perPage: Int) {
+  Page (page: $page, perPage: $perPage) {
+    pageInfo {
+      hasNextPage
+    }
+    media (search: $search, type: ANIME, sort: SEARCH_MATCH) {
+      ...mediaFields
+    }
+  }
+}
+";
 
+const MEDIA_BY_MAL_ID: &str = "
+query ($idMal: [Int], $page: Int, $perPage: Int){
+  Page (page: $page, perPage: $perPage) {
+    pageInfo {
+      hasNextPage
+    }
+    media (idMal_in: $idMal, type: ANIME){
+      ...mediaFields
+    }
+  }
+}
+";
+
+/// Aired episodes of the given shows, latest first
+const LAST_AIRED: &str = "
+query ($ids: [Int], $after: Int, $before: Int, $page: Int, $perPage: Int) {
+  Page (page: $page, perPage: $perPage) {
+    pageInfo {
+      hasNextPage
+    }
+    airingSchedules (mediaId_in: $ids, airingAt_greater: $after, airingAt_lesser: $before, sort: TIME_DESC) {
+      mediaId
+      episode
+      airingAt
+    }
   }
 }
 ";
 
+/// How far back to look for a show's last aired episode
+const LAST_AIRED_WINDOW_SECS: i64 = 30 * 24 * 60 * 60;
+
 const CURRENTLY_AIRING: &str = "
-query {
-  Page {
+query ($page: Int, $perPage: Int) {
+  Page (page: $page, perPage: $perPage) {
+    pageInfo {
+      hasNextPage
+    }
     media (status: RELEASING, type: ANIME) {
-      id
-      title {
-        romaji
-        english
-        native
-      }
-      description
-      episodes
-      duration
-      averageScore
-      meanScore
-      popularity
-      genres
-      studios (isMain: true) {
-          nodes {
-              name
-          }
-      }
-      coverImage {
-          medium
-          large
-          extraLarge
-      }
-      startDate {
-          year
-          month
-          day
+      ...mediaFields
+    }
+  }
+}
+";
+
+/// Fields requested for every show in a page of results
+const MEDIA_FIELDS: &str = "
+fragment mediaFields on Media {
+  id
+  idMal
+  title {
+    romaji
+    english
+    native
+  }
+  synonyms
+  description
+  format
+  status
+  season
+  seasonYear
+  isAdult
+  episodes
+  duration
+  averageScore
+  meanScore
+  popularity
+  genres
+  studios (isMain: true) {
+      nodes {
+          name
       }
-      nextAiringEpisode {
+  }
+  coverImage {
+      medium
+      large
+      extraLarge
+  }
+  startDate {
+      year
+      month
+      day
+  }
+  nextAiringEpisode {
+      episode
+      airingAt
+  }
+  airingSchedule (notYetAired: true, perPage: 25) {
+      nodes {
           episode
           airingAt
       }
-    }
+  }
+  relations {
+      edges {
+          relationType
+          node {
+              id
+              type
+              format
+              status
+              season
+              seasonYear
+              title {
+                  romaji
+                  english
+                  native
+              }
+          }
+      }
+  }
+  externalLinks {
+      site
+      url
+      type
   }
 }
 ";
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Debug, Clone, Default)]
 pub struct AniShow {
     pub id: Option<u32>,
+    /// MyAnimeList ID of the show
+    #[serde(rename = "idMal")]
+    pub id_mal: Option<u32>,
     pub title: Option<Title>,
+    pub synonyms: Option<Vec<String>>,
     #[serde(rename = "averageScore")]
     pub average_score: Option<u8>,
     #[serde(rename = "meanScore")]
@@ -97,7 +225,7 @@ pub struct AniShow {
     pub genres: Option<Vec<String>>,
     #[serde(rename = "coverImage")]
     pub cover_image: Option<CoverImage>,
-    #[serde(deserialize_with = "sanitize_html_string")]
+    #[serde(default, deserialize_with = "sanitize_html_string")]
     pub description: Option<String>,
     #[serde(rename = "startDate")]
     pub start_date: Option<FuzzyDate>,
@@ -106,6 +234,193 @@ pub struct AniShow {
     pub studios: Option<Studio>,
     #[serde(rename = "nextAiringEpisode")]
     pub next_airing_episode: Option<NextAiringEpisode>,
+    pub format: Option<MediaFormat>,
+    pub status: Option<MediaStatus>,
+    pub season: Option<Season>,
+    #[serde(rename = "seasonYear")]
+    pub season_year: Option<u16>,
+    #[serde(rename = "isAdult")]
+    pub is_adult: Option<bool>,
+    /// Known airing times of the show's upcoming episodes
+    #[serde(rename = "airingSchedule")]
+    pub airing_schedule: Option<AiringSchedule>,
+    pub relations: Option<Relations>,
+    #[serde(rename = "externalLinks")]
+    pub external_links: Option<Vec<ExternalLink>>,
+}
+
+impl AniShow {
+    /// Season the show started airing in, e.g. "Fall 2023"
+    pub fn season_label(&self) -> Option<String> {
+        Some(format!("{} {}", self.season?, self.season_year?))
+    }
+
+    /// Titles a release group might use for this show: romaji, english, then synonyms
+    ///
+    /// Native titles and synonyms in non-Latin scripts are left out since release
+    /// titles on Nyaa and SubsPlease are romanized.
+    pub fn search_titles(&self) -> Vec<String> {
+        let title = self.title.as_ref();
+        let candidates = title
+            .and_then(|t| t.romaji.clone())
+            .into_iter()
+            .chain(title.and_then(|t| t.english.clone()))
+            .chain(self.synonyms.clone().unwrap_or_default());
+
+        let mut titles: Vec<String> = Vec::new();
+        for candidate in candidates {
+            let candidate = candidate.trim().to_string();
+            let romanized = normalize_title(&candidate).is_ascii();
+            if !candidate.is_empty()
+                && romanized
+                && !titles.iter().any(|t| t.eq_ignore_ascii_case(&candidate))
+            {
+                titles.push(candidate);
+            }
+        }
+        titles
+    }
+
+    /// Upcoming episodes in the airing schedule, in episode order
+    pub fn airing_episodes(&self) -> Vec<NextAiringEpisode> {
+        let mut episodes = self
+            .airing_schedule
+            .as_ref()
+            .and_then(|s| s.nodes.clone())
+            .unwrap_or_default();
+        episodes.sort_by_key(|e| e.episode);
+        episodes
+    }
+
+    /// Whether the show is airing or has yet to start
+    pub fn is_airing(&self) -> bool {
+        matches!(
+            self.status,
+            Some(MediaStatus::Releasing | MediaStatus::NotYetReleased)
+        )
+    }
+
+    /// Streaming sites the show is available on
+    pub fn streaming_links(&self) -> Vec<&ExternalLink> {
+        self.external_links
+            .iter()
+            .flatten()
+            .filter(|link| link.link_type.as_deref() == Some("STREAMING") && link.url.is_some())
+            .collect()
+    }
+
+    /// Related anime of the given relation type (e.g. "SEQUEL", "PREQUEL")
+    pub fn related(&self, relation_type: &str) -> Vec<&RelatedMedia> {
+        self.relations
+            .iter()
+            .flat_map(|r| r.edges.iter().flatten())
+            .filter(|edge| edge.relation_type.as_deref() == Some(relation_type))
+            .filter_map(|edge| edge.node.as_ref())
+            .filter(|node| node.media_type.as_deref() == Some("ANIME"))
+            .collect()
+    }
+}
+
+/// Release format of a show
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MediaFormat {
+    Tv,
+    TvShort,
+    Movie,
+    Special,
+    Ova,
+    Ona,
+    Music,
+    #[serde(other)]
+    Other,
+}
+
+impl MediaFormat {
+    pub fn label(&self) -> &'static str {
+        match self {
+            MediaFormat::Tv => "TV",
+            MediaFormat::TvShort => "TV Short",
+            MediaFormat::Movie => "Movie",
+            MediaFormat::Special => "Special",
+            MediaFormat::Ova => "OVA",
+            MediaFormat::Ona => "ONA",
+            MediaFormat::Music => "Music",
+            MediaFormat::Other => "Other",
+        }
+    }
+
+    /// Whether the format airs as a series of episodes rather than a one-off
+    pub fn is_episodic(&self) -> bool {
+        matches!(self, MediaFormat::Tv | MediaFormat::TvShort | MediaFormat::Ona)
+    }
+}
+
+/// Release status of a show
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MediaStatus {
+    Finished,
+    Releasing,
+    NotYetReleased,
+    Cancelled,
+    Hiatus,
+    #[serde(other)]
+    Other,
+}
+
+impl MediaStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            MediaStatus::Finished => "Finished",
+            MediaStatus::Releasing => "Airing",
+            MediaStatus::NotYetReleased => "Upcoming",
+            MediaStatus::Cancelled => "Cancelled",
+            MediaStatus::Hiatus => "Hiatus",
+            MediaStatus::Other => "Unknown",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AiringSchedule {
+    pub nodes: Option<Vec<NextAiringEpisode>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Relations {
+    pub edges: Option<Vec<RelationEdge>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RelationEdge {
+    #[serde(rename = "relationType")]
+    pub relation_type: Option<String>,
+    pub node: Option<RelatedMedia>,
+}
+
+/// A show (or manga, novel...) related to another
+#[derive(Debug, Clone, Deserialize)]
+pub struct RelatedMedia {
+    pub id: Option<u32>,
+    /// "ANIME" or "MANGA"
+    #[serde(rename = "type")]
+    pub media_type: Option<String>,
+    pub format: Option<MediaFormat>,
+    pub status: Option<MediaStatus>,
+    pub season: Option<Season>,
+    #[serde(rename = "seasonYear")]
+    pub season_year: Option<u16>,
+    pub title: Option<Title>,
+}
+
+/// A link to the show on another site (streaming services, MAL, official site...)
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExternalLink {
+    pub site: Option<String>,
+    pub url: Option<String>,
+    #[serde(rename = "type")]
+    pub link_type: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -115,7 +430,7 @@ pub struct NextAiringEpisode {
     pub airing_at: Option<i64>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct Studio {
     pub nodes: Option<Vec<Node>>,
 }
@@ -125,7 +440,7 @@ pub struct Node {
     pub name: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct FuzzyDate {
     pub year: Option<u16>,
     #[serde(deserialize_with = "deserialize_month")]
@@ -148,7 +463,7 @@ impl fmt::Display for FuzzyDate {
     }
 }
 
-fn sanitize_html(input: &str) -> String {
+pub(crate) fn sanitize_html(input: &str) -> String {
     let mut sanitized = String::with_capacity(input.len());
     let mut i_tag_open = false;
     let mut in_comment = false;
@@ -242,34 +557,30 @@ fn deserialize_month<'de, D>(deserializer: D) -> Result<Option<String>, D::Error
 where
     D: Deserializer<'de>,
 {
-    let month_number = Option::<u8>::deserialize(deserializer)?;
-    let month_str = match month_number {
-        Some(1) => Some("Jan".to_string()),
-        Some(2) => Some("Feb".to_string()),
-        Some(3) => Some("Mar".to_string()),
-        Some(4) => Some("Apr".to_string()),
-        Some(5) => Some("May".to_string()),
-        Some(6) => Some("Jun".to_string()),
-        Some(7) => Some("Jul".to_string()),
-        Some(8) => Some("Aug".to_string()),
-        Some(9) => Some("Sep".to_string()),
-        Some(10) => Some("Oct".to_string()),
-        Some(11) => Some("Nov".to_string()),
-        Some(12) => Some("Dec".to_string()),
-        Some(_) => return Err(de::Error::custom("Invalid month")),
-        None => None,
-    };
-    Ok(month_str)
+    match Option::<u8>::deserialize(deserializer)? {
+        Some(month) => month_name(month)
+            .map(|name| Some(name.to_string()))
+            .ok_or_else(|| de::Error::custom("Invalid month")),
+        None => Ok(None),
+    }
 }
 
-#[derive(Deserialize, Debug, Clone)]
+/// Short name of a month number (1-12), as shown in `FuzzyDate`
+pub(crate) fn month_name(month: u8) -> Option<&'static str> {
+    let names = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    names.get(usize::from(month).checked_sub(1)?).copied()
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
 pub struct Title {
     pub romaji: Option<String>,
     pub english: Option<String>,
     pub native: Option<String>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Debug, Clone, Default)]
 pub struct CoverImage {
     pub medium: Option<String>,
     pub large: Option<String>,
@@ -278,22 +589,123 @@ pub struct CoverImage {
 }
 
 #[derive(Deserialize, Debug)]
-struct MediaPage {
-    media: Vec<AniShow>,
+struct MediaPage<M> {
+    #[serde(rename = "pageInfo")]
+    page_info: Option<PageInfo>,
+    media: Vec<M>,
 }
 
 #[derive(Deserialize, Debug)]
-struct Data {
+struct AiringPage {
+    #[serde(rename = "pageInfo")]
+    page_info: Option<PageInfo>,
+    #[serde(rename = "airingSchedules")]
+    airing_schedules: Vec<AiredEpisode>,
+}
+
+#[derive(Deserialize, Debug)]
+struct AiringData {
+    #[serde(rename = "Page")]
+    page: AiringPage,
+}
+
+#[derive(Deserialize, Debug)]
+struct AiringResponse {
+    data: AiringData,
+}
+
+/// An episode that has aired
+#[derive(Deserialize, Debug, Clone)]
+pub struct AiredEpisode {
+    #[serde(rename = "mediaId")]
+    pub media_id: u32,
+    pub episode: Option<u16>,
+    #[serde(rename = "airingAt")]
+    pub airing_at: Option<i64>,
+}
+
+#[derive(Deserialize, Debug)]
+struct PageInfo {
+    #[serde(rename = "hasNextPage")]
+    has_next_page: Option<bool>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Data<M> {
     #[serde(rename = "Page")]
-    page: MediaPage,
+    page: MediaPage<M>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Response<M> {
+    data: Data<M>,
+}
+
+#[derive(Deserialize, Debug)]
+struct MediaData {
+    #[serde(rename = "Media")]
+    media: Option<AniShow>,
+}
+
+#[derive(Deserialize, Debug)]
+struct MediaResponse {
+    data: MediaData,
+}
+
+/// A show on a user's AniList watchlist
+#[derive(Deserialize, Debug, Clone)]
+pub struct ListEntry {
+    /// List status, e.g. CURRENT or PLANNING
+    pub status: String,
+    pub media: AniShow,
 }
 
 #[derive(Deserialize, Debug)]
-struct Response {
-    data: Data,
+struct MediaList {
+    entries: Vec<ListEntry>,
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+#[derive(Deserialize, Debug)]
+struct MediaListCollection {
+    lists: Vec<MediaList>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ListData {
+    #[serde(rename = "MediaListCollection")]
+    collection: Option<MediaListCollection>,
+}
+
+#[derive(Deserialize, Debug)]
+struct GraphQlError {
+    message: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct ListResponse {
+    data: Option<ListData>,
+    #[serde(default)]
+    errors: Vec<GraphQlError>,
+}
+
+/// Entries of a watchlist response, one per show
+fn parse_user_list(body: &str) -> anyhow::Result<Vec<ListEntry>> {
+    let response: ListResponse = serde_json::from_str(body)?;
+    let Some(collection) = response.data.and_then(|d| d.collection) else {
+        let messages: Vec<String> = response.errors.into_iter().map(|e| e.message).collect();
+        anyhow::bail!("AniList returned no list: {}", messages.join(", "));
+    };
+
+    let mut seen = std::collections::HashSet::new();
+    Ok(collection
+        .lists
+        .into_iter()
+        .flat_map(|list| list.entries)
+        .filter(|entry| entry.media.id.is_some_and(|id| seen.insert(id)))
+        .collect())
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Season {
     #[serde(rename = "SPRING")]
     SPRING,
@@ -305,10 +717,145 @@ pub enum Season {
     SUMMER,
 }
 
+impl Season {
+    /// Position of the season within its year
+    fn index(&self) -> u8 {
+        match self {
+            Season::WINTER => 0,
+            Season::SPRING => 1,
+            Season::SUMMER => 2,
+            Season::FALL => 3,
+        }
+    }
+
+    /// Season a month (1-12) falls in
+    pub fn from_month(month: u8) -> Self {
+        match month {
+            1..=3 => Season::WINTER,
+            4..=6 => Season::SPRING,
+            7..=9 => Season::SUMMER,
+            _ => Season::FALL,
+        }
+    }
+}
+
+/// Whether a season is over, so its AniList data won't change much anymore
+fn season_has_ended(season: Season, year: u16) -> bool {
+    let now = Utc::now();
+    let current = (now.year() as u16, ((now.month() - 1) / 3) as u8);
+    (year, season.index()) < current
+}
+
+/// Shows from AniList, possibly served from the cache
+#[derive(Debug)]
+pub struct AniListResult {
+    pub shows: Vec<AniShow>,
+    /// When the shows were fetched, if AniList couldn't be reached and the
+    /// cached copy is past its TTL
+    pub stale_since: Option<String>,
+}
+
 pub async fn get_anilist_data(season: Season, year: u16) -> anyhow::Result<Vec<AniShow>> {
+    let media = fetch_all_pages(SEASONAL, json!({"season": season, "seasonYear": year})).await?;
+    parse_media(&serde_json::to_string(&media)?)
+}
+
+/// Fetch several shows by their AniList IDs
+pub async fn get_anilist_shows(ids: &[u32]) -> anyhow::Result<Vec<AniShow>> {
+    let media = fetch_all_pages(MEDIA_BY_ID, json!({"ids": ids})).await?;
+    parse_media(&serde_json::to_string(&media)?)
+}
+
+/// How long a season's shows stay cached; `None` once the season has ended
+pub fn seasonal_ttl(season: Season, year: u16) -> Option<i64> {
+    (!season_has_ended(season, year)).then_some(SEASONAL_TTL_SECS)
+}
+
+/// Seasonal shows, from the cache while it's fresh
+pub async fn get_anilist_data_cached(season: Season, year: u16) -> anyhow::Result<AniListResult> {
+    cached_query(
+        format!("seasonal:{:?}:{}", season, year),
+        seasonal_ttl(season, year),
+        SEASONAL,
+        json!({"season": season, "seasonYear": year}),
+    )
+    .await
+}
+
+/// Currently airing shows, from the cache while it's fresh
+pub async fn get_anilist_all_airing_cached() -> anyhow::Result<AniListResult> {
+    cached_query("airing".to_string(), Some(AIRING_TTL_SECS), CURRENTLY_AIRING, json!({})).await
+}
+
+/// Search shows by title, best matches first
+pub async fn search_anilist(title: &str) -> anyhow::Result<Vec<AniShow>> {
+    let (media, _) = fetch_page(SEARCH, json!({"search": title}), 1).await?;
+    parse_media(&serde_json::to_string(&media)?)
+}
+
+/// Fetch several shows by their MyAnimeList IDs
+pub async fn get_anilist_shows_by_mal(mal_ids: &[u32]) -> anyhow::Result<Vec<AniShow>> {
+    let media = fetch_all_pages(MEDIA_BY_MAL_ID, json!({"idMal": mal_ids})).await?;
+    parse_media(&serde_json::to_string(&media)?)
+}
+
+/// Serve a query from the cache while it's fresh, otherwise fetch and cache it
+///
+/// If AniList can't be reached, an expired cache entry is served instead.
+async fn cached_query(
+    key: String,
+    ttl_secs: Option<i64>,
+    query: &str,
+    variables: serde_json::Value,
+) -> anyhow::Result<AniListResult> {
+    let fetch = async { Ok(serde_json::to_string(&fetch_all_pages(query, variables).await?)?) };
+    let cached = query_cache::cached(key, ttl_secs, fetch, parse_media).await?;
+    Ok(AniListResult {
+        shows: cached.value,
+        stale_since: cached.stale_since,
+    })
+}
+
+/// Parse shows from the raw JSON of a media list
+fn parse_media(body: &str) -> anyhow::Result<Vec<AniShow>> {
+    Ok(serde_json::from_str(body)?)
+}
+
+/// Run a `Page` query page by page until AniList reports no next page
+///
+/// Returns the raw JSON of every show, for caching.
+async fn fetch_all_pages(
+    query: &str,
+    variables: serde_json::Value,
+) -> anyhow::Result<Vec<serde_json::Value>> {
+    let mut shows: Vec<serde_json::Value> = Vec::new();
+
+    for page in 1..=MAX_PAGES {
+        let (media, has_next_page) = fetch_page(query, variables.clone(), page).await?;
+        shows.extend(media);
+
+        if !has_next_page {
+            return Ok(shows);
+        }
+    }
+
+    tracing::warn!("AniList query stopped after {} pages", MAX_PAGES);
+    Ok(shows)
+}
+
+/// Fetch one page of a `Page` query, returning its raw shows and whether more follow
+async fn fetch_page(
+    query: &str,
+    mut variables: serde_json::Value,
+    page: u32,
+) -> anyhow::Result<(Vec<serde_json::Value>, bool)> {
     let client = super::http_client();
+    let query = format!("{}{}", query, MEDIA_FIELDS);
+    variables["page"] = json!(page);
+    variables["perPage"] = json!(PER_PAGE);
+
     // Define query and variables
-    let json = json!({"query": SEASONAL, "variables": {"season": season, "seasonYear": year}});
+    let json = json!({"query": query, "variables": variables});
     let resp = client
         .post("https://graphql.anilist.co/")
         .header("Content-Type", "application/json")
@@ -317,16 +864,67 @@ pub async fn get_anilist_data(season: Season, year: u16) -> anyhow::Result<Vec<A
         .send()
         .await?;
     let text_resp = resp.text().await?;
-    //println!("{}", text_resp);
-    let result: Response = serde_json::from_str(&text_resp)?;
+    let result: Response<serde_json::Value> = serde_json::from_str(&text_resp)?;
 
-    Ok(result.data.page.media)
+    let has_next_page = result
+        .data
+        .page
+        .page_info
+        .and_then(|info| info.has_next_page)
+        .unwrap_or(false);
+    Ok((result.data.page.media, has_next_page))
 }
 
-pub async fn get_anilist_all_airing() -> anyhow::Result<Vec<AniShow>> {
+/// Latest episode of each show to have aired in the last month, by AniList ID
+///
+/// Shows that haven't aired an episode in that time are left out.
+pub async fn get_last_aired_episodes(ids: &[u32]) -> anyhow::Result<HashMap<u32, AiredEpisode>> {
+    let now = Utc::now().timestamp();
+    let variables = json!({
+        "ids": ids,
+        "after": now - LAST_AIRED_WINDOW_SECS,
+        "before": now + 1,
+        "perPage": PER_PAGE,
+    });
+
+    let mut latest: HashMap<u32, AiredEpisode> = HashMap::new();
+    for page in 1..=MAX_PAGES {
+        let mut variables = variables.clone();
+        variables["page"] = json!(page);
+        let json = json!({"query": LAST_AIRED, "variables": variables});
+        let resp = super::http_client()
+            .post("https://graphql.anilist.co/")
+            .header("Content-Type", "application/json")
+            .header("Accept", "application/json")
+            .body(json.to_string())
+            .send()
+            .await?;
+        let text_resp = resp.text().await?;
+        let result: AiringResponse = serde_json::from_str(&text_resp)?;
+
+        // Latest first, so the first episode seen of a show is its last aired
+        for episode in result.data.page.airing_schedules {
+            latest.entry(episode.media_id).or_insert(episode);
+        }
+
+        let has_next_page = result
+            .data
+            .page
+            .page_info
+            .and_then(|info| info.has_next_page)
+            .unwrap_or(false);
+        if !has_next_page || latest.len() == ids.len() {
+            return Ok(latest);
+        }
+    }
+
+    Ok(latest)
+}
+
+/// Fetch the titles, synonyms and cover of a single show by its AniList ID
+pub async fn get_anilist_titles(id: u32) -> anyhow::Result<Option<AniShow>> {
     let client = super::http_client();
-    // Define query and variables
-    let json = json!({"query": CURRENTLY_AIRING});
+    let json = json!({"query": MEDIA_TITLES, "variables": {"id": id}});
     let resp = client
         .post("https://graphql.anilist.co/")
         .header("Content-Type", "application/json")
@@ -335,10 +933,26 @@ pub async fn get_anilist_all_airing() -> anyhow::Result<Vec<AniShow>> {
         .send()
         .await?;
     let text_resp = resp.text().await?;
-    //println!("{}", text_resp);
-    let result: Response = serde_json::from_str(&text_resp)?;
+    let result: MediaResponse = serde_json::from_str(&text_resp)?;
 
-    Ok(result.data.page.media)
+    Ok(result.data.media)
+}
+
+/// Fetch the shows a user is watching or planning to watch
+pub async fn get_user_watchlist(username: &str) -> anyhow::Result<Vec<ListEntry>> {
+    let client = super::http_client();
+    let query = format!("{}{}", USER_LIST, MEDIA_FIELDS);
+    let json = json!({"query": query, "variables": {"userName": username}});
+    let resp = client
+        .post("https://graphql.anilist.co/")
+        .header("Content-Type", "application/json")
+        .header("Accept", "application/json")
+        .body(json.to_string())
+        .send()
+        .await?;
+    let text_resp = resp.text().await?;
+
+    parse_user_list(&text_resp)
 }
 
 #[cfg(test)]
@@ -357,4 +971,145 @@ mod test {
             }
         };
     }
+
+    #[test]
+    fn test_parse_last_aired_page() {
+        let response: AiringResponse = serde_json::from_str(
+            r#"{"data": {"Page": {
+                "pageInfo": {"hasNextPage": false},
+                "airingSchedules": [
+                    {"mediaId": 154587, "episode": 12, "airingAt": 1700000000},
+                    {"mediaId": 21, "episode": 1085, "airingAt": 1699900000}
+                ]
+            }}}"#,
+        )
+        .unwrap();
+
+        let episodes = response.data.page.airing_schedules;
+        assert_eq!(episodes[0].media_id, 154587);
+        assert_eq!(episodes[1].episode, Some(1085));
+        assert_eq!(episodes[1].airing_at, Some(1699900000));
+    }
+
+    #[test]
+    fn test_parse_page_with_media_fields() {
+        let response: Response<AniShow> = serde_json::from_str(
+            r#"{"data": {"Page": {
+                "pageInfo": {"hasNextPage": true},
+                "media": [{
+                    "id": 154587,
+                    "format": "TV",
+                    "status": "RELEASING",
+                    "season": "FALL",
+                    "seasonYear": 2023,
+                    "isAdult": false,
+                    "airingSchedule": {"nodes": [
+                        {"episode": 2, "airingAt": 1696000000},
+                        {"episode": 1, "airingAt": 1695400000}
+                    ]},
+                    "relations": {"edges": [
+                        {"relationType": "SEQUEL", "node": {"id": 182255, "type": "ANIME", "format": "TV", "status": "NOT_YET_RELEASED", "season": "WINTER", "seasonYear": 2025}},
+                        {"relationType": "SOURCE", "node": {"id": 118586, "type": "MANGA", "format": "MANGA"}}
+                    ]},
+                    "externalLinks": [{"site": "Crunchyroll", "url": "https://www.crunchyroll.com/frieren", "type": "STREAMING"}]
+                }]
+            }}}"#,
+        )
+        .unwrap();
+
+        let page = response.data.page;
+        assert_eq!(page.page_info.unwrap().has_next_page, Some(true));
+
+        let show = &page.media[0];
+        assert_eq!(show.format, Some(MediaFormat::Tv));
+        assert_eq!(show.status, Some(MediaStatus::Releasing));
+        assert_eq!(show.season_label().as_deref(), Some("Fall 2023"));
+        assert_eq!(show.is_adult, Some(false));
+        assert_eq!(
+            show.airing_episodes().iter().map(|e| e.episode).collect::<Vec<_>>(),
+            vec![Some(1), Some(2)]
+        );
+
+        let sequels = show.related("SEQUEL");
+        assert_eq!(sequels.len(), 1);
+        assert_eq!(sequels[0].id, Some(182255));
+        assert_eq!(sequels[0].status, Some(MediaStatus::NotYetReleased));
+        assert_eq!(sequels[0].season_year, Some(2025));
+        // Unknown formats (here a manga's) don't fail the whole page
+        assert!(show.related("SOURCE").is_empty());
+        assert_eq!(show.external_links.as_ref().unwrap()[0].site.as_deref(), Some("Crunchyroll"));
+    }
+
+    #[test]
+    fn test_parse_user_list() {
+        let entries = parse_user_list(
+            r#"{"data": {"MediaListCollection": {"lists": [
+                {"entries": [
+                    {"status": "CURRENT", "media": {"id": 1, "status": "RELEASING"}},
+                    {"status": "CURRENT", "media": {"id": 2, "status": "FINISHED"}}
+                ]},
+                {"entries": [
+                    {"status": "PLANNING", "media": {"id": 3, "status": "NOT_YET_RELEASED"}},
+                    {"status": "PLANNING", "media": {"id": 1, "status": "RELEASING"}}
+                ]}
+            ]}}}"#,
+        )
+        .unwrap();
+
+        let ids: Vec<(Option<u32>, &str)> = entries
+            .iter()
+            .map(|e| (e.media.id, e.status.as_str()))
+            .collect();
+        assert_eq!(
+            ids,
+            vec![(Some(1), "CURRENT"), (Some(2), "CURRENT"), (Some(3), "PLANNING")]
+        );
+        assert!(entries[0].media.is_airing());
+        assert!(!entries[1].media.is_airing());
+        assert!(entries[2].media.is_airing());
+    }
+
+    #[test]
+    fn test_parse_user_list_error() {
+        let err = parse_user_list(
+            r#"{"errors": [{"message": "User not found", "status": 404}],
+                "data": {"MediaListCollection": null}}"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("User not found"));
+    }
+
+    #[test]
+    fn test_season_has_ended() {
+        assert!(season_has_ended(Season::FALL, 2000));
+        assert!(!season_has_ended(Season::WINTER, 3000));
+
+        let (now_year, now_index) = (Utc::now().year() as u16, (Utc::now().month0() / 3) as u8);
+        let current = [Season::WINTER, Season::SPRING, Season::SUMMER, Season::FALL]
+            .into_iter()
+            .find(|s| s.index() == now_index)
+            .unwrap();
+        assert!(!season_has_ended(current, now_year));
+    }
+
+    #[test]
+    fn test_search_titles() {
+        let show: AniShow = serde_json::from_str(
+            r#"{
+                "id": 154587,
+                "title": {"romaji": "Sousou no Frieren", "english": "Frieren: Beyond Journey's End", "native": "葬送のフリーレン"},
+                "synonyms": ["Frieren at the Funeral", "장송의 프리렌", "sousou no frieren"]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            show.search_titles(),
+            vec![
+                "Sousou no Frieren",
+                "Frieren: Beyond Journey's End",
+                "Frieren at the Funeral",
+            ]
+        );
+    }
 }
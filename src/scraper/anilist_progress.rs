@@ -0,0 +1,285 @@
+//! Pushing download progress to an AniList account
+//!
+//! When an episode downloads, the show's entry on the authorized user's list is set
+//! to CURRENT with its progress raised to that episode. Entries that are already
+//! further along, or completed, are left alone.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::db;
+
+/// AniList GraphQL endpoint
+pub const ANILIST_API: &str = "https://graphql.anilist.co/";
+
+const LIST_ENTRY: &str = "
+query ($mediaId: Int) {
+  Media (id: $mediaId, type: ANIME) {
+    mediaListEntry {
+      status
+      progress
+    }
+  }
+}
+";
+
+const SAVE_LIST_ENTRY: &str = "
+mutation ($mediaId: Int, $status: MediaListStatus, $progress: Int) {
+  SaveMediaListEntry (mediaId: $mediaId, status: $status, progress: $progress) {
+    status
+    progress
+  }
+}
+";
+
+/// A show's entry on the user's list
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ListProgress {
+    pub status: String,
+    pub progress: u16,
+}
+
+/// What pushing progress did to a list entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProgressUpdate {
+    Updated(ListProgress),
+    /// The entry was already at or past the episode
+    AlreadyAhead,
+    /// The entry is completed and left as is
+    Completed,
+}
+
+#[derive(Deserialize, Debug)]
+struct GraphQlError {
+    message: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct GraphQlResponse<T> {
+    data: Option<T>,
+    #[serde(default)]
+    errors: Vec<GraphQlError>,
+}
+
+#[derive(Deserialize, Debug)]
+struct EntryMedia {
+    #[serde(rename = "mediaListEntry")]
+    media_list_entry: Option<ListProgress>,
+}
+
+#[derive(Deserialize, Debug)]
+struct EntryData {
+    #[serde(rename = "Media")]
+    media: Option<EntryMedia>,
+}
+
+#[derive(Deserialize, Debug)]
+struct SaveData {
+    #[serde(rename = "SaveMediaListEntry")]
+    entry: ListProgress,
+}
+
+/// Client for the authorized user's AniList list
+pub struct ProgressClient {
+    endpoint: String,
+    token: String,
+}
+
+impl ProgressClient {
+    pub fn new(token: &str) -> Self {
+        Self::with_endpoint(ANILIST_API, token)
+    }
+
+    /// Client talking to another GraphQL endpoint, e.g. a stub server
+    pub fn with_endpoint(endpoint: &str, token: &str) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            token: token.to_string(),
+        }
+    }
+
+    async fn request<T: for<'de> Deserialize<'de>>(
+        &self,
+        query: &str,
+        variables: serde_json::Value,
+    ) -> Result<T> {
+        let json = json!({"query": query, "variables": variables});
+        let resp = super::http_client()
+            .post(&self.endpoint)
+            .header("Content-Type", "application/json")
+            .header("Accept", "application/json")
+            .bearer_auth(&self.token)
+            .body(json.to_string())
+            .send()
+            .await?;
+        let text_resp = resp.text().await?;
+        let result: GraphQlResponse<T> =
+            serde_json::from_str(&text_resp).context("Unexpected AniList response")?;
+
+        match result.data {
+            Some(data) if result.errors.is_empty() => Ok(data),
+            _ => {
+                let messages: Vec<String> = result.errors.into_iter().map(|e| e.message).collect();
+                anyhow::bail!("AniList request failed: {}", messages.join(", "))
+            }
+        }
+    }
+
+    /// The user's list entry for a show, if it's on their list
+    pub async fn list_entry(&self, media_id: u32) -> Result<Option<ListProgress>> {
+        let data: EntryData = self.request(LIST_ENTRY, json!({"mediaId": media_id})).await?;
+        Ok(data.media.and_then(|m| m.media_list_entry))
+    }
+
+    /// Mark a show as watching and raise its progress to `episode`
+    pub async fn push_progress(&self, media_id: u32, episode: u16) -> Result<ProgressUpdate> {
+        let entry = self.list_entry(media_id).await?;
+        let status = match &entry {
+            Some(entry) if entry.status == "COMPLETED" => return Ok(ProgressUpdate::Completed),
+            Some(entry) if entry.progress >= episode => return Ok(ProgressUpdate::AlreadyAhead),
+            // A rewatch stays a rewatch
+            Some(entry) if entry.status == "REPEATING" => "REPEATING",
+            _ => "CURRENT",
+        };
+
+        let data: SaveData = self
+            .request(
+                SAVE_LIST_ENTRY,
+                json!({"mediaId": media_id, "status": status, "progress": episode}),
+            )
+            .await?;
+        Ok(ProgressUpdate::Updated(data.entry))
+    }
+}
+
+/// Push a downloaded episode to AniList, if enabled and the show hasn't opted out
+///
+/// Failures are only logged; the download counts either way.
+pub async fn push_download_progress(show_id: u32, episode: u16) {
+    let settings = db::with_db(move |conn| {
+        let config = db::get_rss_config(conn)?;
+        let show = db::get_show(conn, show_id)?;
+        Ok((config, show))
+    })
+    .await;
+
+    let token = match settings {
+        Ok((config, Some(show)))
+            if config.anilist_push_progress && !show.skip_anilist_progress && !show.is_manual() =>
+        {
+            match config.anilist_token {
+                Some(token) => token,
+                None => {
+                    tracing::warn!("AniList progress push is on but no access token is saved");
+                    return;
+                }
+            }
+        }
+        Ok(_) => return,
+        Err(e) => {
+            tracing::error!("Failed to load AniList progress settings: {:?}", e);
+            return;
+        }
+    };
+
+    match ProgressClient::new(&token).push_progress(show_id, episode).await {
+        Ok(update) => tracing::info!("AniList progress for {} episode {}: {:?}", show_id, episode, update),
+        Err(e) => tracing::error!("Failed to push AniList progress for {}: {:?}", show_id, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::HeaderMap, routing::post, Json, Router};
+    use std::sync::{Arc, Mutex};
+
+    /// Serve a stub GraphQL endpoint holding one list entry, recording the mutations it gets
+    async fn stub_server(entry: Option<ListProgress>) -> (String, Arc<Mutex<Vec<serde_json::Value>>>) {
+        let saved = Arc::new(Mutex::new(Vec::new()));
+        let recorded = saved.clone();
+
+        let app = Router::new().route(
+            "/",
+            post(move |headers: HeaderMap, Json(body): Json<serde_json::Value>| {
+                let entry = entry.clone();
+                let recorded = recorded.clone();
+                async move {
+                    if headers.get("authorization").and_then(|v| v.to_str().ok()) != Some("Bearer secret") {
+                        return Json(json!({"data": null, "errors": [{"message": "Invalid token"}]}));
+                    }
+
+                    let query = body["query"].as_str().unwrap_or_default();
+                    if query.contains("SaveMediaListEntry") {
+                        let variables = body["variables"].clone();
+                        recorded.lock().unwrap().push(variables.clone());
+                        Json(json!({"data": {"SaveMediaListEntry": {
+                            "status": variables["status"],
+                            "progress": variables["progress"],
+                        }}}))
+                    } else {
+                        Json(json!({"data": {"Media": {"mediaListEntry": entry}}}))
+                    }
+                }
+            }),
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        (format!("http://{}/", addr), saved)
+    }
+
+    fn entry(status: &str, progress: u16) -> ListProgress {
+        ListProgress {
+            status: status.to_string(),
+            progress,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_push_progress_adds_show_to_list() {
+        let (endpoint, saved) = stub_server(None).await;
+        let client = ProgressClient::with_endpoint(&endpoint, "secret");
+
+        let update = client.push_progress(42, 3).await.unwrap();
+        assert_eq!(update, ProgressUpdate::Updated(entry("CURRENT", 3)));
+        assert_eq!(
+            saved.lock().unwrap().as_slice(),
+            &[json!({"mediaId": 42, "status": "CURRENT", "progress": 3})]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_push_progress_keeps_entries_ahead() {
+        let (endpoint, saved) = stub_server(Some(entry("CURRENT", 5))).await;
+        let client = ProgressClient::with_endpoint(&endpoint, "secret");
+        assert_eq!(client.push_progress(42, 5).await.unwrap(), ProgressUpdate::AlreadyAhead);
+
+        let (endpoint, _) = stub_server(Some(entry("COMPLETED", 12))).await;
+        let client = ProgressClient::with_endpoint(&endpoint, "secret");
+        assert_eq!(client.push_progress(42, 13).await.unwrap(), ProgressUpdate::Completed);
+
+        assert!(saved.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_push_progress_keeps_rewatch_status() {
+        let (endpoint, _) = stub_server(Some(entry("REPEATING", 2))).await;
+        let client = ProgressClient::with_endpoint(&endpoint, "secret");
+
+        let update = client.push_progress(42, 4).await.unwrap();
+        assert_eq!(update, ProgressUpdate::Updated(entry("REPEATING", 4)));
+    }
+
+    #[tokio::test]
+    async fn test_push_progress_reports_errors() {
+        let (endpoint, _) = stub_server(None).await;
+        let client = ProgressClient::with_endpoint(&endpoint, "wrong");
+
+        let err = client.push_progress(42, 1).await.unwrap_err();
+        assert!(err.to_string().contains("Invalid token"));
+    }
+}
@@ -0,0 +1,75 @@
+//! Local archive of downloaded .torrent files
+//!
+//! Releases get removed from Nyaa and bare magnets without trackers can take a long
+//! time to resolve, so every .torrent the tracker grabs is kept on disk keyed by its
+//! info hash. The archived file can later be re-uploaded to Transmission as-is.
+
+use anyhow::{anyhow, Context, Result};
+use std::path::PathBuf;
+
+/// Get the archive directory from environment or use default
+fn archive_dir() -> PathBuf {
+    PathBuf::from(std::env::var("TORRENT_ARCHIVE_DIR").unwrap_or_else(|_| "torrents".to_string()))
+}
+
+/// Save a .torrent file into the archive, returning the path it was written to
+///
+/// Existing files are overwritten, since the same info hash always means the same torrent.
+pub fn save_torrent(info_hash: &str, data: &[u8]) -> Result<PathBuf> {
+    save_torrent_in(&archive_dir(), info_hash, data)
+}
+
+fn save_torrent_in(dir: &std::path::Path, info_hash: &str, data: &[u8]) -> Result<PathBuf> {
+    // The hash becomes part of a file name, so only accept plain hex
+    if info_hash.is_empty() || !info_hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(anyhow!("Invalid info hash '{}'", info_hash));
+    }
+
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create torrent archive at {}", dir.display()))?;
+
+    let path = dir.join(format!("{}.torrent", info_hash.to_lowercase()));
+    std::fs::write(&path, data)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    Ok(path)
+}
+
+/// Read an archived .torrent file back
+pub fn load_torrent(path: &str) -> Result<Vec<u8>> {
+    std::fs::read(path)
+        .with_context(|| format!("Failed to read archived torrent {}", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_archive(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("torrent_archive_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_save_and_load_torrent() {
+        let dir = temp_archive("roundtrip");
+
+        let path = save_torrent_in(&dir, "ABCDEF0123", b"d4:infodee").unwrap();
+        assert_eq!(path, dir.join("abcdef0123.torrent"));
+
+        let data = load_torrent(path.to_str().unwrap()).unwrap();
+        assert_eq!(data, b"d4:infodee");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_save_rejects_bad_hash() {
+        let dir = temp_archive("bad_hash");
+
+        assert!(save_torrent_in(&dir, "../escape", b"data").is_err());
+        assert!(save_torrent_in(&dir, "", b"data").is_err());
+        assert!(!dir.exists());
+    }
+}
@@ -0,0 +1,396 @@
+//! Bencode decoder and .torrent metainfo parsing
+//!
+//! SubsPlease's direct feed doesn't include info hashes, so the tracker downloads
+//! the `.torrent` file instead and computes the hash itself. The info hash is the
+//! SHA-1 of the raw bencoded `info` dictionary, so the decoder keeps track of byte
+//! offsets rather than re-encoding the parsed value.
+
+use anyhow::{anyhow, Context, Result};
+
+use super::rss::TorrentFile;
+
+/// Refuse to download anything larger than this; episode torrents are a few KiB
+const MAX_TORRENT_SIZE: usize = 10 * 1024 * 1024;
+
+/// Guards against stack exhaustion on maliciously nested input
+const MAX_DEPTH: usize = 64;
+
+/// A decoded bencode value borrowing from the input buffer
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value<'a> {
+    Int(i64),
+    Bytes(&'a [u8]),
+    List(Vec<Value<'a>>),
+    /// Dictionary entries in the order they appear (bencode requires sorted keys)
+    Dict(Vec<(&'a [u8], Value<'a>)>),
+}
+
+impl<'a> Value<'a> {
+    /// Look up a key in a dictionary value
+    pub fn get(&self, key: &str) -> Option<&Value<'a>> {
+        match self {
+            Value::Dict(entries) => entries
+                .iter()
+                .find(|(k, _)| *k == key.as_bytes())
+                .map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            Value::Int(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<String> {
+        match self {
+            Value::Bytes(b) => Some(String::from_utf8_lossy(b).into_owned()),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[Value<'a>]> {
+        match self {
+            Value::List(items) => Some(items),
+            _ => None,
+        }
+    }
+}
+
+/// Parsed contents of a .torrent file
+#[derive(Debug, Clone)]
+pub struct TorrentMetainfo {
+    /// Lowercase hex SHA-1 of the bencoded info dictionary
+    pub info_hash: String,
+    pub name: String,
+    pub files: Vec<TorrentFile>,
+    pub total_size: u64,
+}
+
+struct Decoder<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn peek(&self) -> Result<u8> {
+        self.data
+            .get(self.pos)
+            .copied()
+            .ok_or_else(|| anyhow!("Unexpected end of input at byte {}", self.pos))
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<()> {
+        let found = self.peek()?;
+        if found != byte {
+            return Err(anyhow!(
+                "Expected '{}' at byte {}, found '{}'",
+                byte as char,
+                self.pos,
+                found as char
+            ));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn parse_value(&mut self, depth: usize) -> Result<Value<'a>> {
+        if depth > MAX_DEPTH {
+            return Err(anyhow!("Bencode nesting too deep"));
+        }
+
+        match self.peek()? {
+            b'i' => self.parse_int().map(Value::Int),
+            b'l' => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                while self.peek()? != b'e' {
+                    items.push(self.parse_value(depth + 1)?);
+                }
+                self.pos += 1;
+                Ok(Value::List(items))
+            }
+            b'd' => {
+                self.pos += 1;
+                let mut entries = Vec::new();
+                while self.peek()? != b'e' {
+                    let key = self.parse_bytes()?;
+                    let value = self.parse_value(depth + 1)?;
+                    entries.push((key, value));
+                }
+                self.pos += 1;
+                Ok(Value::Dict(entries))
+            }
+            b'0'..=b'9' => self.parse_bytes().map(Value::Bytes),
+            other => Err(anyhow!(
+                "Unexpected byte '{}' at position {}",
+                other as char,
+                self.pos
+            )),
+        }
+    }
+
+    fn parse_int(&mut self) -> Result<i64> {
+        self.expect(b'i')?;
+        let end = self.find(b'e')?;
+        let text = std::str::from_utf8(&self.data[self.pos..end])
+            .context("Integer is not valid UTF-8")?;
+        let value = text
+            .parse()
+            .with_context(|| format!("Invalid integer '{}'", text))?;
+        self.pos = end + 1;
+        Ok(value)
+    }
+
+    fn parse_bytes(&mut self) -> Result<&'a [u8]> {
+        let colon = self.find(b':')?;
+        let text = std::str::from_utf8(&self.data[self.pos..colon])
+            .context("String length is not valid UTF-8")?;
+        let len: usize = text
+            .parse()
+            .with_context(|| format!("Invalid string length '{}'", text))?;
+        let start = colon + 1;
+        let end = start
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| anyhow!("String of length {} runs past end of input", len))?;
+        self.pos = end;
+        Ok(&self.data[start..end])
+    }
+
+    fn find(&self, byte: u8) -> Result<usize> {
+        self.data[self.pos..]
+            .iter()
+            .position(|b| *b == byte)
+            .map(|offset| self.pos + offset)
+            .ok_or_else(|| anyhow!("Missing '{}' after byte {}", byte as char, self.pos))
+    }
+}
+
+/// Decodes a complete bencoded value
+pub fn decode(data: &[u8]) -> Result<Value<'_>> {
+    let mut decoder = Decoder::new(data);
+    let value = decoder.parse_value(0)?;
+    if decoder.pos != data.len() {
+        return Err(anyhow!("Trailing data after byte {}", decoder.pos));
+    }
+    Ok(value)
+}
+
+/// Parses a .torrent file, computing its info hash and listing its files
+pub fn parse_torrent(data: &[u8]) -> Result<TorrentMetainfo> {
+    // Walk the top-level dictionary by hand so we can hash the raw info bytes
+    let mut decoder = Decoder::new(data);
+    decoder.expect(b'd')?;
+
+    let mut info_span = None;
+    while decoder.peek()? != b'e' {
+        let key = decoder.parse_bytes()?;
+        let start = decoder.pos;
+        decoder.parse_value(1)?;
+        if key == b"info" {
+            info_span = Some(start..decoder.pos);
+        }
+    }
+
+    let info_span = info_span.ok_or_else(|| anyhow!("Torrent has no info dictionary"))?;
+    let info_bytes = &data[info_span];
+    let info_hash = sha1_smol::Sha1::from(info_bytes).digest().to_string();
+    let info = decode(info_bytes)?;
+
+    let name = info
+        .get("name.utf-8")
+        .or_else(|| info.get("name"))
+        .and_then(Value::as_str)
+        .ok_or_else(|| anyhow!("Torrent info has no name"))?;
+
+    let files = match info.get("files").and_then(Value::as_list) {
+        // Multi-file torrent: paths are lists of components under the root folder
+        Some(entries) => entries
+            .iter()
+            .map(|entry| {
+                let size = entry
+                    .get("length")
+                    .and_then(Value::as_int)
+                    .ok_or_else(|| anyhow!("File entry has no length"))?;
+                let components = entry
+                    .get("path.utf-8")
+                    .or_else(|| entry.get("path"))
+                    .and_then(Value::as_list)
+                    .ok_or_else(|| anyhow!("File entry has no path"))?;
+                let path = std::iter::once(name.clone())
+                    .chain(components.iter().filter_map(Value::as_str))
+                    .collect::<Vec<_>>()
+                    .join("/");
+                Ok(TorrentFile {
+                    path,
+                    size: size.max(0) as u64,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?,
+        // Single-file torrent: the name is the file name
+        None => {
+            let size = info
+                .get("length")
+                .and_then(Value::as_int)
+                .ok_or_else(|| anyhow!("Single-file torrent has no length"))?;
+            vec![TorrentFile {
+                path: name.clone(),
+                size: size.max(0) as u64,
+            }]
+        }
+    };
+
+    let total_size = files.iter().map(|f| f.size).sum();
+
+    Ok(TorrentMetainfo {
+        info_hash,
+        name,
+        files,
+        total_size,
+    })
+}
+
+/// Whether a .torrent file is the one an info hash names
+pub fn has_info_hash(data: &[u8], info_hash: &str) -> bool {
+    parse_torrent(data).is_ok_and(|meta| meta.info_hash.eq_ignore_ascii_case(info_hash))
+}
+
+/// Downloads a .torrent file
+pub async fn fetch_torrent(url: &str) -> Result<Vec<u8>> {
+    let mut response = super::http_client()
+        .get(url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to fetch torrent from {}", url))?
+        .error_for_status()
+        .with_context(|| format!("Torrent download failed for {}", url))?;
+
+    if let Some(length) = response.content_length()
+        && length > MAX_TORRENT_SIZE as u64
+    {
+        return Err(anyhow!("Torrent at {} is too large ({} bytes)", url, length));
+    }
+
+    // The length header may be missing or wrong, so count while reading too
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .with_context(|| "Failed to read torrent body")?
+    {
+        if bytes.len() + chunk.len() > MAX_TORRENT_SIZE {
+            return Err(anyhow!(
+                "Torrent at {} is too large (over {} bytes)",
+                url,
+                MAX_TORRENT_SIZE
+            ));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn single_file_torrent() -> Vec<u8> {
+        let mut data = b"d8:announce31:http://nyaa.tracker.wf/announce4:info".to_vec();
+        data.extend_from_slice(b"d6:lengthi1024e4:name8:test.mkv12:piece lengthi16384e6:pieces20:");
+        data.extend_from_slice(&[b'a'; 20]);
+        data.extend_from_slice(b"ee");
+        data
+    }
+
+    fn multi_file_torrent() -> Vec<u8> {
+        let mut data = b"d4:info".to_vec();
+        data.extend_from_slice(
+            b"d5:filesld6:lengthi100e4:pathl6:Extras9:setup.exeeed6:lengthi2048e4:pathl7:ep1.mkveee",
+        );
+        data.extend_from_slice(b"4:name4:Show12:piece lengthi16384e6:pieces20:");
+        data.extend_from_slice(&[b'b'; 20]);
+        data.extend_from_slice(b"ee");
+        data
+    }
+
+    #[test]
+    fn test_decode_primitives() {
+        assert_eq!(decode(b"i42e").unwrap(), Value::Int(42));
+        assert_eq!(decode(b"i-7e").unwrap(), Value::Int(-7));
+        assert_eq!(decode(b"4:spam").unwrap(), Value::Bytes(b"spam"));
+        assert_eq!(decode(b"0:").unwrap(), Value::Bytes(b""));
+        assert_eq!(
+            decode(b"l4:spami1ee").unwrap(),
+            Value::List(vec![Value::Bytes(b"spam"), Value::Int(1)])
+        );
+    }
+
+    #[test]
+    fn test_decode_dict() {
+        let value = decode(b"d3:cow3:moo4:spaml1:a1:bee").unwrap();
+        assert_eq!(value.get("cow").and_then(Value::as_str).as_deref(), Some("moo"));
+        assert_eq!(value.get("spam").and_then(Value::as_list).map(|l| l.len()), Some(2));
+        assert!(value.get("missing").is_none());
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert!(decode(b"i42").is_err());
+        assert!(decode(b"10:short").is_err());
+        assert!(decode(b"l4:spam").is_err());
+        assert!(decode(b"x").is_err());
+        assert!(decode(b"i1ei2e").is_err());
+    }
+
+    #[test]
+    fn test_decode_depth_limit() {
+        let mut data = vec![b'l'; MAX_DEPTH + 2];
+        data.extend(vec![b'e'; MAX_DEPTH + 2]);
+        assert!(decode(&data).is_err());
+    }
+
+    #[test]
+    fn test_parse_single_file_torrent() {
+        let meta = parse_torrent(&single_file_torrent()).unwrap();
+
+        assert_eq!(meta.info_hash, "41163af0f9c05162b6f7a9e7769fd943d3860008");
+        assert_eq!(meta.name, "test.mkv");
+        assert_eq!(meta.total_size, 1024);
+        assert_eq!(meta.files.len(), 1);
+        assert_eq!(meta.files[0].path, "test.mkv");
+    }
+
+    #[test]
+    fn test_parse_multi_file_torrent() {
+        let meta = parse_torrent(&multi_file_torrent()).unwrap();
+
+        assert_eq!(meta.info_hash, "e8b59772acb242dc113278d72f1aa6e27c353f6e");
+        assert_eq!(meta.name, "Show");
+        assert_eq!(meta.total_size, 2148);
+        assert_eq!(
+            meta.files.iter().map(|f| f.path.as_str()).collect::<Vec<_>>(),
+            vec!["Show/Extras/setup.exe", "Show/ep1.mkv"]
+        );
+    }
+
+    #[test]
+    fn test_parse_torrent_without_info() {
+        assert!(parse_torrent(b"d8:announce3:urle").is_err());
+    }
+
+    #[test]
+    fn test_has_info_hash() {
+        let data = single_file_torrent();
+        assert!(has_info_hash(&data, "41163AF0F9C05162B6F7A9E7769FD943D3860008"));
+        assert!(!has_info_hash(&data, "e8b59772acb242dc113278d72f1aa6e27c353f6e"));
+        assert!(!has_info_hash(b"not a torrent", "41163af0f9c05162b6f7a9e7769fd943d3860008"));
+    }
+}
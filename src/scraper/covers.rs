@@ -0,0 +1,204 @@
+//! Local cover image cache
+//!
+//! Cards point at `/assets/covers/:id` instead of the AniList CDN. The first
+//! request for a cover downloads it, shrinks it to card size and stores it as a
+//! JPEG under [`COVER_CACHE_DIR`]; later requests are served from disk. The
+//! directory is pruned oldest-first once it grows past [`COVER_CACHE_MAX_BYTES`].
+
+use anyhow::{Context, Result};
+use image::imageops::FilterType;
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+use std::time::SystemTime;
+
+use super::anilist::{get_anilist_titles, AniShow, CoverImage};
+use crate::db;
+
+/// Directory cover thumbnails are stored in, relative to the working directory
+pub const COVER_CACHE_DIR: &str = "cache/covers";
+
+/// Total size the cover cache may grow to before old covers are removed
+pub const COVER_CACHE_MAX_BYTES: u64 = 200 * 1024 * 1024;
+
+/// Image shown for shows without a cover, served from the assets directory
+pub const PLACEHOLDER_PATH: &str = "/assets/no-cover.svg";
+
+/// Thumbnail bounds; cards are 256x300 CSS pixels, doubled for high-DPI screens
+const THUMB_WIDTH: u32 = 512;
+const THUMB_HEIGHT: u32 = 600;
+
+const THUMB_QUALITY: u8 = 85;
+
+/// Cover URLs of shows seen in AniList results, so a cover can be fetched
+/// without asking AniList for it again
+static COVER_URLS: OnceLock<RwLock<HashMap<u32, String>>> = OnceLock::new();
+
+fn cover_urls() -> &'static RwLock<HashMap<u32, String>> {
+    COVER_URLS.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Best available URL for a cover
+fn best_url(cover: &CoverImage) -> Option<&String> {
+    cover.extra_large.as_ref().or(cover.large.as_ref())
+}
+
+/// Remember the cover URLs of shows about to be rendered
+pub fn remember_covers(shows: &[AniShow]) {
+    let mut urls = cover_urls().write().unwrap_or_else(|e| e.into_inner());
+    for show in shows {
+        if let (Some(id), Some(url)) = (show.id, show.cover_image.as_ref().and_then(best_url)) {
+            urls.insert(id, url.clone());
+        }
+    }
+}
+
+/// Path of the cached thumbnail for a show
+fn thumbnail_path(dir: &Path, id: u32) -> PathBuf {
+    dir.join(format!("{}.jpg", id))
+}
+
+/// JPEG thumbnail of a show's cover, from disk if cached
+///
+/// Returns `None` when the show has no cover on AniList, or was added by hand.
+pub async fn get_cover(id: u32) -> Result<Option<Vec<u8>>> {
+    if db::is_manual_show_id(id) {
+        return Ok(None);
+    }
+
+    let dir = Path::new(COVER_CACHE_DIR);
+    let path = thumbnail_path(dir, id);
+    if let Ok(bytes) = tokio::fs::read(&path).await {
+        return Ok(Some(bytes));
+    }
+
+    let known = cover_urls()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&id)
+        .cloned();
+    let url = match known {
+        Some(url) => url,
+        None => match get_anilist_titles(id)
+            .await?
+            .and_then(|show| show.cover_image)
+            .as_ref()
+            .and_then(best_url)
+        {
+            Some(url) => url.clone(),
+            None => return Ok(None),
+        },
+    };
+
+    let original = super::http_client()
+        .get(&url)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+    let thumbnail = tokio::task::spawn_blocking(move || make_thumbnail(&original))
+        .await
+        .context("Thumbnail task panicked")??;
+
+    tokio::fs::create_dir_all(dir)
+        .await
+        .context("Failed to create cover cache directory")?;
+    tokio::fs::write(&path, &thumbnail)
+        .await
+        .context("Failed to write cover thumbnail")?;
+    if let Err(err) = prune_cache(dir, COVER_CACHE_MAX_BYTES) {
+        tracing::warn!("Failed to prune cover cache: {:?}", err);
+    }
+
+    Ok(Some(thumbnail))
+}
+
+/// Shrink an image to fit the thumbnail bounds and encode it as JPEG
+fn make_thumbnail(original: &[u8]) -> Result<Vec<u8>> {
+    let image = image::load_from_memory(original).context("Failed to decode cover image")?;
+    let image = if image.width() > THUMB_WIDTH || image.height() > THUMB_HEIGHT {
+        image.resize(THUMB_WIDTH, THUMB_HEIGHT, FilterType::Triangle)
+    } else {
+        image
+    };
+
+    let mut out = Cursor::new(Vec::new());
+    let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut out, THUMB_QUALITY);
+    image
+        .to_rgb8()
+        .write_with_encoder(encoder)
+        .context("Failed to encode cover thumbnail")?;
+    Ok(out.into_inner())
+}
+
+/// Remove the least recently written files until the directory fits in `max_bytes`
+fn prune_cache(dir: &Path, max_bytes: u64) -> Result<()> {
+    let mut files: Vec<(SystemTime, u64, PathBuf)> = std::fs::read_dir(dir)
+        .context("Failed to read cover cache directory")?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let meta = entry.metadata().ok()?;
+            meta.is_file()
+                .then(|| (meta.modified().unwrap_or(SystemTime::UNIX_EPOCH), meta.len(), entry.path()))
+        })
+        .collect();
+
+    let mut total: u64 = files.iter().map(|(_, len, _)| len).sum();
+    files.sort_by_key(|(modified, _, _)| *modified);
+
+    for (_, len, path) in files {
+        if total <= max_bytes {
+            break;
+        }
+        std::fs::remove_file(&path)
+            .with_context(|| format!("Failed to remove {}", path.display()))?;
+        total -= len;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{DynamicImage, ImageFormat, RgbImage};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("covers-test-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_make_thumbnail_shrinks_large_covers() {
+        let mut png = Cursor::new(Vec::new());
+        DynamicImage::ImageRgb8(RgbImage::new(1000, 1500))
+            .write_to(&mut png, ImageFormat::Png)
+            .unwrap();
+
+        let thumbnail = make_thumbnail(png.get_ref()).unwrap();
+        let decoded = image::load_from_memory(&thumbnail).unwrap();
+        assert_eq!(image::guess_format(&thumbnail).unwrap(), ImageFormat::Jpeg);
+        assert_eq!(decoded.height(), THUMB_HEIGHT);
+        assert_eq!(decoded.width(), 400);
+    }
+
+    #[test]
+    fn test_prune_cache_removes_oldest() {
+        let dir = temp_dir("prune");
+        for id in 1..=3u32 {
+            std::fs::write(thumbnail_path(&dir, id), vec![0u8; 100]).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+
+        prune_cache(&dir, 250).unwrap();
+        assert!(!thumbnail_path(&dir, 1).exists());
+        assert!(thumbnail_path(&dir, 2).exists());
+        assert!(thumbnail_path(&dir, 3).exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,94 @@
+//! In-memory fansub group registry
+//!
+//! Group detection runs synchronously while parsing feeds, so the `fansub_groups`
+//! table is mirrored here. The registry starts out with the built-in groups and is
+//! replaced from the database at startup and whenever a group is edited.
+
+use anyhow::Result;
+use std::sync::{OnceLock, RwLock};
+
+use crate::db::{self, fansub_groups::builtin_groups, FansubGroup};
+
+static REGISTRY: OnceLock<RwLock<Vec<FansubGroup>>> = OnceLock::new();
+
+fn registry() -> &'static RwLock<Vec<FansubGroup>> {
+    REGISTRY.get_or_init(|| RwLock::new(builtin_groups()))
+}
+
+/// Replace the registry contents
+pub fn set_groups(groups: Vec<FansubGroup>) {
+    let mut guard = registry().write().unwrap_or_else(|e| e.into_inner());
+    *guard = groups;
+}
+
+/// Reload the registry from the database
+pub async fn reload_groups() -> Result<()> {
+    let groups = db::with_db(db::get_all_fansub_groups).await?;
+    tracing::debug!("Loaded {} fansub groups", groups.len());
+    set_groups(groups);
+    Ok(())
+}
+
+/// Snapshot of all known groups
+pub fn all_groups() -> Vec<FansubGroup> {
+    registry().read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Look up a group by canonical name or alias
+pub fn find_group(name: &str) -> Option<FansubGroup> {
+    registry()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .find(|g| g.matches(name))
+        .cloned()
+}
+
+/// Canonical name for a group, or the trimmed input if the group is unknown
+pub fn canonical_group(name: &str) -> String {
+    find_group(name)
+        .map(|g| g.name)
+        .unwrap_or_else(|| name.trim().to_string())
+}
+
+/// Whether two names refer to the same group, taking aliases into account
+pub fn same_group(a: &str, b: &str) -> bool {
+    canonical_group(a).eq_ignore_ascii_case(&canonical_group(b))
+}
+
+/// Name to search Nyaa with for a group's releases
+///
+/// Uses the group's configured feed if it has one, otherwise the name as given.
+pub fn feed_name(name: &str) -> String {
+    find_group(name)
+        .and_then(|g| g.default_feed)
+        .unwrap_or_else(|| name.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_group() {
+        assert_eq!(canonical_group("subsplease"), "SubsPlease");
+        assert_eq!(canonical_group("SP"), "SubsPlease");
+        assert_eq!(canonical_group("ERAI-RAWS"), "Erai-raws");
+        assert_eq!(canonical_group(" SomeNewGroup "), "SomeNewGroup");
+    }
+
+    #[test]
+    fn test_same_group() {
+        assert!(same_group("SubsPlease", "sp"));
+        assert!(same_group("subsplease_direct", "SubsPlease"));
+        assert!(same_group("unknown", "UNKNOWN"));
+        assert!(!same_group("SubsPlease", "Erai-raws"));
+    }
+
+    #[test]
+    fn test_feed_name() {
+        assert_eq!(feed_name("SP"), "subsplease");
+        assert_eq!(feed_name("Judas"), "Judas");
+        assert_eq!(feed_name("SomeNewGroup"), "SomeNewGroup");
+    }
+}
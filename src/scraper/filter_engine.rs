@@ -4,7 +4,9 @@
 //! based on configurable filter rules.
 
 use crate::db::{FilterAction, FilterRule, FilterType, ShowFilterOverride};
-use crate::scraper::rss::RssItem;
+use crate::scraper::fansub::same_group;
+use crate::scraper::language::title_has_language;
+use crate::scraper::rss::{detect_fansub_source, RssItem};
 
 /// Result of applying filters to an RSS item
 #[derive(Debug, Clone)]
@@ -38,6 +40,21 @@ impl FilterEngine {
         Self::new(global_rules, Vec::new())
     }
 
+    /// Returns true if any enabled rule inspects torrent contents
+    ///
+    /// Callers should fill in `RssItem.files` before calling `apply` when this is set,
+    /// otherwise content rules can't match (and require rules will fail).
+    pub fn needs_contents(&self) -> bool {
+        let global = self
+            .rules
+            .iter()
+            .any(|r| r.enabled && r.filter_type.is_content_rule());
+        let show = self.show_overrides.iter().any(|o| {
+            o.enabled && o.filter_type.map(|t| t.is_content_rule()).unwrap_or(false)
+        });
+        global || show
+    }
+
     /// Apply filters to a list of RSS items
     ///
     /// Returns a list of items that pass all filters, sorted by score (highest first).
@@ -216,15 +233,48 @@ impl FilterEngine {
             }
             FilterType::Group => {
                 // Match fansub group names (usually in brackets at the start)
-                // Pattern: [GroupName] at the start of the title
+                // Pattern: [GroupName] at the start of the title, or any alias of it
                 let group_pattern = format!("[{}]", pattern_lower);
                 title_lower.contains(&group_pattern)
-                    || title_lower.starts_with(&format!("[{}]", pattern_lower))
+                    || (item.title.starts_with('[')
+                        && same_group(&detect_fansub_source(&item.title), pattern))
             }
             FilterType::TitleExclude | FilterType::TitleInclude => {
                 // Case-insensitive substring match
                 title_lower.contains(&pattern_lower)
             }
+            FilterType::FileExtension => {
+                // Pattern is a comma-separated list like ".rar, .exe"
+                let Some(files) = &item.files else {
+                    return false;
+                };
+                let extensions: Vec<String> = pattern_lower
+                    .split(',')
+                    .map(|e| e.trim().trim_start_matches('.'))
+                    .filter(|e| !e.is_empty())
+                    .map(|e| format!(".{}", e))
+                    .collect();
+                files.iter().any(|f| {
+                    let path = f.path.to_lowercase();
+                    extensions.iter().any(|ext| path.ends_with(ext))
+                })
+            }
+            FilterType::FileCount => {
+                // Matches when the torrent has more than N files
+                match (&item.files, pattern.trim().parse::<usize>()) {
+                    (Some(files), Ok(max)) => files.len() > max,
+                    _ => false,
+                }
+            }
+            FilterType::Language => title_has_language(&item.title, pattern),
+            FilterType::Category => {
+                // "1_0" is Nyaa's "all subcategories" code
+                let pattern = pattern.trim();
+                match pattern.strip_suffix("_0") {
+                    Some(category) => item.category_id.split('_').next() == Some(category),
+                    None => item.category_id == pattern,
+                }
+            }
         }
     }
 }
@@ -240,6 +290,7 @@ enum MatchResult {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::scraper::rss::TorrentFile;
 
     fn make_rss_item(title: &str) -> RssItem {
         RssItem {
@@ -252,6 +303,7 @@ mod tests {
             size: "1 GiB".to_string(),
             seeders: 10,
             leechers: 5,
+            files: None,
         }
     }
 
@@ -351,6 +403,31 @@ mod tests {
         assert!(results[0].item.title.contains("SubsPlease"));
     }
 
+    #[test]
+    fn test_group_filter_matches_alias() {
+        let rules = vec![make_filter(
+            1,
+            "Require SubsPlease",
+            FilterType::Group,
+            "SubsPlease",
+            FilterAction::Require,
+            50,
+        )];
+
+        let engine = FilterEngine::with_global_rules(rules);
+
+        let items = vec![
+            make_rss_item("[SP] One Piece - 1060 (1080p).mkv"),
+            make_rss_item("One Piece - 1060 (1080p).mkv"),
+        ];
+
+        let results = engine.apply(items);
+
+        // The [SP] tag is an alias; an untagged title is not a group match
+        assert_eq!(results.len(), 1);
+        assert!(results[0].item.title.starts_with("[SP]"));
+    }
+
     #[test]
     fn test_combined_filters() {
         let rules = vec![
@@ -400,6 +477,138 @@ mod tests {
         assert!(results[0].score == 15); // 10 + 5
     }
 
+    fn with_files(mut item: RssItem, paths: &[&str]) -> RssItem {
+        item.files = Some(
+            paths
+                .iter()
+                .map(|p| TorrentFile {
+                    path: p.to_string(),
+                    size: 1024,
+                })
+                .collect(),
+        );
+        item
+    }
+
+    #[test]
+    fn test_file_extension_filter() {
+        let rules = vec![make_filter(
+            1,
+            "Reject archives",
+            FilterType::FileExtension,
+            ".rar, exe",
+            FilterAction::Exclude,
+            100,
+        )];
+
+        let engine = FilterEngine::with_global_rules(rules);
+        assert!(engine.needs_contents());
+
+        let items = vec![
+            with_files(
+                make_rss_item("[Sketchy] One Piece - 1060 (1080p)"),
+                &["One Piece - 1060.mkv", "Codec/Setup.EXE"],
+            ),
+            with_files(
+                make_rss_item("[SubsPlease] One Piece - 1060 (1080p)"),
+                &["One Piece - 1060.mkv"],
+            ),
+            // Unknown contents can't match an exclude rule
+            make_rss_item("[Other] One Piece - 1060 (1080p)"),
+        ];
+
+        let results = engine.apply(items);
+
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| !r.item.title.contains("Sketchy")));
+    }
+
+    #[test]
+    fn test_file_count_filter() {
+        let rules = vec![make_filter(
+            1,
+            "Reject multi-file",
+            FilterType::FileCount,
+            "2",
+            FilterAction::Exclude,
+            100,
+        )];
+
+        let engine = FilterEngine::with_global_rules(rules);
+
+        let items = vec![
+            with_files(make_rss_item("[A] Show - 01 (1080p)"), &["a.mkv", "b.mkv"]),
+            with_files(
+                make_rss_item("[B] Show - 01 (1080p)"),
+                &["a.mkv", "b.mkv", "c.mkv"],
+            ),
+        ];
+
+        let results = engine.apply(items);
+
+        assert_eq!(results.len(), 1);
+        assert!(results[0].item.title.starts_with("[A]"));
+    }
+
+    #[test]
+    fn test_language_and_category_filters() {
+        let rules = vec![
+            make_filter(1, "Raws only", FilterType::Category, "1_4", FilterAction::Exclude, 100),
+            make_filter(2, "Prefer Spanish", FilterType::Language, "es", FilterAction::Prefer, 10),
+        ];
+
+        let engine = FilterEngine::with_global_rules(rules);
+
+        let mut raw = make_rss_item("[Ohys-Raws] Show - 01 (1080p)");
+        raw.category_id = "1_4".to_string();
+        let mut spanish = make_rss_item("[Group] Show - 01 [Spanish] (1080p)");
+        spanish.category_id = "1_3".to_string();
+        let english = make_rss_item("[Erai-raws] Show - 01 [1080p][ENG]");
+
+        let results = engine.apply(vec![raw, english, spanish]);
+
+        assert_eq!(results.len(), 2);
+        assert!(results[0].item.title.contains("Spanish"));
+        assert_eq!(results[0].score, 10);
+    }
+
+    #[test]
+    fn test_category_filter_matches_all_subcategories() {
+        let rules = vec![make_filter(
+            1,
+            "Anime only",
+            FilterType::Category,
+            "1_0",
+            FilterAction::Require,
+            50,
+        )];
+
+        let engine = FilterEngine::with_global_rules(rules);
+
+        let mut live_action = make_rss_item("[Group] Drama - 01 (1080p)");
+        live_action.category_id = "4_1".to_string();
+        let items = vec![make_rss_item("[SubsPlease] Show - 01 (1080p)"), live_action];
+
+        let results = engine.apply(items);
+        assert_eq!(results.len(), 1);
+        assert!(results[0].item.title.contains("SubsPlease"));
+    }
+
+    #[test]
+    fn test_needs_contents_ignores_title_rules() {
+        let rules = vec![make_filter(
+            1,
+            "Exclude batches",
+            FilterType::TitleExclude,
+            "batch",
+            FilterAction::Exclude,
+            100,
+        )];
+
+        let engine = FilterEngine::with_global_rules(rules);
+        assert!(!engine.needs_contents());
+    }
+
     #[test]
     fn test_disabled_filter() {
         let mut rules = vec![make_filter(
@@ -0,0 +1,396 @@
+//! MyAnimeList metadata through a Jikan API
+//!
+//! Jikan (v4) serves MyAnimeList's data without an API key. Its shows are converted
+//! to AniList's shape. MAL doesn't know AniList IDs, so those are mapped through
+//! AniList's `idMal`.
+//!
+//! Jikan allows about three requests a second, so listings are fetched with a pause
+//! between pages and cached like AniList's.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::{header::RETRY_AFTER, StatusCode};
+use serde::{de::DeserializeOwned, Deserialize};
+use std::time::Duration;
+
+use super::{
+    anilist::{
+        get_anilist_shows, month_name, sanitize_html, seasonal_ttl, AniShow, CoverImage,
+        FuzzyDate, MediaFormat, MediaStatus, Node, Season, Studio, Title, AIRING_TTL_SECS,
+    },
+    metadata::{cached_listing, MetadataProvider, ProviderShow},
+};
+use crate::db::MetadataSource;
+
+/// Public Jikan API
+pub const JIKAN_API: &str = "https://api.jikan.moe/v4";
+
+/// Upper bound on pages fetched for one listing; Jikan pages hold 25 shows
+const MAX_PAGES: u32 = 10;
+
+/// Pause between the pages of a listing
+const PAGE_DELAY: Duration = Duration::from_millis(400);
+
+/// Longest wait for a rate limit before giving up; Jikan's `Retry-After` can be minutes
+const MAX_RETRY_WAIT: Duration = Duration::from_secs(5);
+
+#[derive(Deserialize, Debug)]
+struct JikanNamed {
+    name: String,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct JikanImage {
+    small_image_url: Option<String>,
+    image_url: Option<String>,
+    large_image_url: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct JikanImages {
+    #[serde(default)]
+    jpg: JikanImage,
+}
+
+#[derive(Deserialize, Debug)]
+struct JikanDate {
+    year: Option<u16>,
+    month: Option<u8>,
+    day: Option<u8>,
+}
+
+#[derive(Deserialize, Debug)]
+struct JikanDateProp {
+    from: Option<JikanDate>,
+}
+
+#[derive(Deserialize, Debug)]
+struct JikanAired {
+    prop: Option<JikanDateProp>,
+}
+
+/// A show as Jikan returns it
+#[derive(Deserialize, Debug)]
+struct JikanAnime {
+    mal_id: u32,
+    title: Option<String>,
+    title_english: Option<String>,
+    title_japanese: Option<String>,
+    #[serde(default)]
+    title_synonyms: Vec<String>,
+    #[serde(rename = "type")]
+    format: Option<String>,
+    status: Option<String>,
+    episodes: Option<u16>,
+    /// e.g. "24 min per ep" or "1 hr 45 min"
+    duration: Option<String>,
+    /// e.g. "R+ - Mild Nudity" or "Rx - Hentai"
+    rating: Option<String>,
+    /// 0-10
+    score: Option<f64>,
+    members: Option<u64>,
+    synopsis: Option<String>,
+    season: Option<String>,
+    year: Option<u16>,
+    aired: Option<JikanAired>,
+    #[serde(default)]
+    images: JikanImages,
+    #[serde(default)]
+    genres: Vec<JikanNamed>,
+    #[serde(default)]
+    studios: Vec<JikanNamed>,
+}
+
+#[derive(Deserialize, Debug)]
+struct JikanPagination {
+    #[serde(default)]
+    has_next_page: bool,
+}
+
+#[derive(Deserialize, Debug)]
+struct JikanList<T> {
+    data: Vec<T>,
+    pagination: Option<JikanPagination>,
+}
+
+#[derive(Deserialize, Debug)]
+struct JikanSingle {
+    data: JikanAnime,
+}
+
+/// Shows from the raw JSON of a listing's shows
+fn parse_listing(body: &str) -> Result<Vec<ProviderShow>> {
+    let shows: Vec<JikanAnime> = serde_json::from_str(body).context("Unexpected Jikan response")?;
+    Ok(shows.into_iter().map(JikanAnime::into_provider_show).collect())
+}
+
+/// Minutes in a Jikan duration such as "24 min per ep" or "1 hr 45 min"
+fn parse_duration(duration: &str) -> Option<u16> {
+    let words: Vec<&str> = duration.split_whitespace().collect();
+    let minutes: u16 = words
+        .windows(2)
+        .filter_map(|pair| {
+            let value: u16 = pair[0].parse().ok()?;
+            match pair[1] {
+                "hr" | "hrs" => Some(value * 60),
+                "min" | "mins" => Some(value),
+                _ => None,
+            }
+        })
+        .sum();
+    (minutes > 0).then_some(minutes)
+}
+
+impl JikanAnime {
+    fn into_provider_show(self) -> ProviderShow {
+        let start_date = self.aired.and_then(|a| a.prop).and_then(|p| p.from);
+        let season = match self.season.as_deref() {
+            Some("winter") => Some(Season::WINTER),
+            Some("spring") => Some(Season::SPRING),
+            Some("summer") => Some(Season::SUMMER),
+            Some("fall") => Some(Season::FALL),
+            _ => None,
+        };
+
+        let show = AniShow {
+            id: Some(self.mal_id),
+            id_mal: Some(self.mal_id),
+            title: Some(Title {
+                romaji: self.title,
+                english: self.title_english,
+                native: self.title_japanese,
+            }),
+            synonyms: Some(self.title_synonyms),
+            average_score: self.score.map(|s| (s * 10.0).round() as u8),
+            popularity: self.members,
+            genres: Some(self.genres.into_iter().map(|g| g.name).collect()),
+            cover_image: Some(CoverImage {
+                medium: self.images.jpg.small_image_url,
+                large: self.images.jpg.image_url,
+                extra_large: self.images.jpg.large_image_url,
+            }),
+            description: self.synopsis.as_deref().map(sanitize_html),
+            season_year: self.year.or(start_date.as_ref().and_then(|d| d.year)),
+            start_date: start_date.map(|d| FuzzyDate {
+                year: d.year,
+                month: d.month.and_then(month_name).map(str::to_string),
+                day: d.day,
+            }),
+            episodes: self.episodes,
+            duration: self.duration.as_deref().and_then(parse_duration),
+            studios: Some(Studio {
+                nodes: Some(
+                    self.studios
+                        .into_iter()
+                        .map(|s| Node { name: Some(s.name) })
+                        .collect(),
+                ),
+            }),
+            format: self.format.as_deref().map(|format| match format {
+                "TV" => MediaFormat::Tv,
+                "Movie" => MediaFormat::Movie,
+                "OVA" => MediaFormat::Ova,
+                "ONA" => MediaFormat::Ona,
+                "Special" | "TV Special" => MediaFormat::Special,
+                "Music" => MediaFormat::Music,
+                _ => MediaFormat::Other,
+            }),
+            status: self.status.as_deref().map(|status| match status {
+                "Currently Airing" => MediaStatus::Releasing,
+                "Finished Airing" => MediaStatus::Finished,
+                "Not yet aired" => MediaStatus::NotYetReleased,
+                _ => MediaStatus::Other,
+            }),
+            season,
+            is_adult: self.rating.map(|r| r.starts_with("Rx")),
+            ..Default::default()
+        };
+
+        ProviderShow {
+            show,
+            ids: vec![(MetadataSource::MyAnimeList, self.mal_id)],
+        }
+    }
+}
+
+/// MyAnimeList through a Jikan-compatible API
+pub struct JikanProvider {
+    base_url: String,
+}
+
+impl JikanProvider {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    async fn send(&self, url: &str) -> Result<reqwest::Response> {
+        Ok(super::http_client()
+            .get(url)
+            .header("Accept", "application/json")
+            .send()
+            .await?)
+    }
+
+    /// Fetch a path, retrying once if Jikan's rate limit is hit
+    ///
+    /// Returns `None` if Jikan has nothing at the path.
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<Option<T>> {
+        let url = format!("{}{}", self.base_url, path);
+        let mut resp = self.send(&url).await?;
+        if resp.status() == StatusCode::TOO_MANY_REQUESTS {
+            let wait = resp
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok()?.parse().ok())
+                .map_or(Duration::from_secs(1), Duration::from_secs);
+            if wait > MAX_RETRY_WAIT {
+                anyhow::bail!("Jikan is rate limiting requests for {}s", wait.as_secs());
+            }
+            tracing::debug!("Jikan rate limited {}, retrying in {:?}", path, wait);
+            tokio::time::sleep(wait).await;
+            resp = self.send(&url).await?;
+        }
+
+        if resp.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let resp = resp.error_for_status().context("Jikan request failed")?;
+        Ok(Some(serde_json::from_str(&resp.text().await?).context("Unexpected Jikan response")?))
+    }
+
+    /// Fetch a path that should exist
+    async fn get_existing<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        self.get(path)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Jikan has nothing at {}", path))
+    }
+
+    /// Fetch a paginated listing page by page, as the raw JSON of its shows
+    async fn list_all(&self, path: &str) -> Result<String> {
+        let separator = if path.contains('?') { '&' } else { '?' };
+        let mut shows = Vec::new();
+
+        for page in 1..=MAX_PAGES {
+            if page > 1 {
+                tokio::time::sleep(PAGE_DELAY).await;
+            }
+            let list: JikanList<serde_json::Value> =
+                self.get_existing(&format!("{}{}page={}", path, separator, page)).await?;
+            shows.extend(list.data);
+
+            if !list.pagination.is_some_and(|p| p.has_next_page) {
+                return Ok(serde_json::to_string(&shows)?);
+            }
+        }
+
+        tracing::warn!("Jikan listing {} stopped after {} pages", path, MAX_PAGES);
+        Ok(serde_json::to_string(&shows)?)
+    }
+}
+
+#[async_trait]
+impl MetadataProvider for JikanProvider {
+    async fn seasonal(&self, season: Season, year: u16) -> Result<Vec<ProviderShow>> {
+        let name = format!("{:?}", season).to_lowercase();
+        let path = format!("/seasons/{}/{}", year, name);
+        cached_listing(
+            format!("myanimelist:seasonal:{:?}:{}", season, year),
+            seasonal_ttl(season, year),
+            self.list_all(&path),
+            parse_listing,
+        )
+        .await
+    }
+
+    async fn airing(&self) -> Result<Vec<ProviderShow>> {
+        cached_listing(
+            "myanimelist:airing".to_string(),
+            Some(AIRING_TTL_SECS),
+            self.list_all("/anime?status=airing&order_by=popularity"),
+            parse_listing,
+        )
+        .await
+    }
+
+    async fn search(&self, title: &str) -> Result<Vec<ProviderShow>> {
+        let list: JikanList<JikanAnime> =
+            self.get_existing(&format!("/anime?q={}", urlencoding::encode(title))).await?;
+        Ok(list.data.into_iter().map(JikanAnime::into_provider_show).collect())
+    }
+
+    async fn details(&self, id: u32) -> Result<Option<ProviderShow>> {
+        let single: Option<JikanSingle> = self.get(&format!("/anime/{}/full", id)).await?;
+        Ok(single.map(|single| single.data.into_provider_show()))
+    }
+
+    async fn find_by_anilist_id(&self, anilist_id: u32) -> Result<Option<u32>> {
+        let shows = get_anilist_shows(&[anilist_id]).await?;
+        Ok(shows.into_iter().next().and_then(|show| show.id_mal))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_jikan_anime() {
+        let shows = parse_listing(
+            r#"[{
+                "mal_id": 52991,
+                "title": "Sousou no Frieren",
+                "title_english": "Frieren: Beyond Journey's End",
+                "title_japanese": "葬送のフリーレン",
+                "title_synonyms": ["Frieren at the Funeral"],
+                "type": "TV",
+                "status": "Finished Airing",
+                "episodes": 28,
+                "duration": "24 min per ep",
+                "rating": "PG-13 - Teens 13 or older",
+                "score": 9.31,
+                "members": 1000000,
+                "season": "fall",
+                "year": 2023,
+                "aired": {"prop": {"from": {"day": 29, "month": 9, "year": 2023}}},
+                "images": {"jpg": {"image_url": "https://cdn.myanimelist.net/images/anime/1015/138006.jpg"}},
+                "genres": [{"mal_id": 2, "name": "Adventure"}],
+                "studios": [{"mal_id": 11, "name": "Madhouse"}]
+            }]"#,
+        )
+        .unwrap();
+
+        let result = shows.into_iter().next().unwrap();
+        assert_eq!(result.id_on(MetadataSource::MyAnimeList), Some(52991));
+        assert_eq!(result.id_on(MetadataSource::AniList), None);
+
+        let show = result.show;
+        assert_eq!(show.id, Some(52991));
+        assert_eq!(
+            show.search_titles(),
+            vec![
+                "Sousou no Frieren",
+                "Frieren: Beyond Journey's End",
+                "Frieren at the Funeral",
+            ]
+        );
+        assert_eq!(show.format, Some(MediaFormat::Tv));
+        assert_eq!(show.status, Some(MediaStatus::Finished));
+        assert_eq!(show.season, Some(Season::FALL));
+        assert_eq!(show.season_year, Some(2023));
+        assert_eq!(show.average_score, Some(93));
+        assert_eq!(show.duration, Some(24));
+        assert_eq!(show.is_adult, Some(false));
+        assert_eq!(show.start_date.unwrap().to_string(), "Sep 29, 2023");
+        assert_eq!(show.studios.unwrap().nodes.unwrap()[0].name.as_deref(), Some("Madhouse"));
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("24 min per ep"), Some(24));
+        assert_eq!(parse_duration("1 hr 45 min"), Some(105));
+        assert_eq!(parse_duration("2 hr"), Some(120));
+        assert_eq!(parse_duration("Unknown"), None);
+    }
+}
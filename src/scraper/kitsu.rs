@@ -0,0 +1,449 @@
+//! Kitsu metadata
+//!
+//! Kitsu's JSON:API responses are converted to AniList's shape. Shows are fetched
+//! with their `mappings`, which carry their MyAnimeList and AniList IDs. Seasonal and
+//! airing listings are cached like AniList's.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Deserialize};
+use std::collections::HashMap;
+
+use super::{
+    anilist::{
+        month_name, sanitize_html, seasonal_ttl, AniShow, CoverImage, FuzzyDate, MediaFormat,
+        MediaStatus, Season, Title, AIRING_TTL_SECS,
+    },
+    metadata::{cached_listing, MetadataProvider, ProviderShow},
+};
+use crate::db::MetadataSource;
+
+/// Public Kitsu API
+pub const KITSU_API: &str = "https://kitsu.io/api/edge";
+
+/// Results per page; Kitsu's maximum
+const PER_PAGE: u32 = 20;
+
+/// Upper bound on pages fetched for one listing
+const MAX_PAGES: u32 = 20;
+
+/// Kitsu's names for providers in show mappings
+const ANILIST_SITE: &str = "anilist/anime";
+const MAL_SITE: &str = "myanimelist/anime";
+
+/// Season a show starting on a date airs in
+///
+/// Kitsu has no season field. Shows starting in the last days of a season's final
+/// month count towards the next season, as on AniList.
+fn airing_season(year: u16, month: u8, day: Option<u8>) -> (Season, u16) {
+    match (month, day) {
+        (12, Some(day)) if day >= 20 => (Season::WINTER, year + 1),
+        (3 | 6 | 9, Some(day)) if day >= 20 => (Season::from_month(month + 1), year),
+        _ => (Season::from_month(month), year),
+    }
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct KitsuTitles {
+    en: Option<String>,
+    en_jp: Option<String>,
+    ja_jp: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct KitsuImage {
+    small: Option<String>,
+    medium: Option<String>,
+    large: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct KitsuAttributes {
+    // Shows
+    canonical_title: Option<String>,
+    #[serde(default)]
+    titles: KitsuTitles,
+    abbreviated_titles: Option<Vec<String>>,
+    synopsis: Option<String>,
+    /// Percentage as a string, e.g. "82.95"
+    average_rating: Option<String>,
+    user_count: Option<u64>,
+    /// e.g. "2023-09-29"
+    start_date: Option<String>,
+    status: Option<String>,
+    subtype: Option<String>,
+    episode_count: Option<u16>,
+    episode_length: Option<u16>,
+    poster_image: Option<KitsuImage>,
+    nsfw: Option<bool>,
+
+    // Mappings
+    external_site: Option<String>,
+    external_id: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct KitsuRef {
+    #[serde(rename = "type")]
+    kind: String,
+    id: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct KitsuRelationship {
+    data: Option<serde_json::Value>,
+}
+
+impl KitsuRelationship {
+    /// Resources the relationship points to; JSON:API uses an object for to-one
+    /// relationships and an array for to-many
+    fn refs(&self) -> Vec<KitsuRef> {
+        match &self.data {
+            Some(serde_json::Value::Array(items)) => items
+                .iter()
+                .filter_map(|item| serde_json::from_value(item.clone()).ok())
+                .collect(),
+            Some(item) => serde_json::from_value(item.clone()).into_iter().collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// A JSON:API resource: a show, or one of its mappings
+#[derive(Deserialize, Debug)]
+struct KitsuResource {
+    id: String,
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    attributes: KitsuAttributes,
+    #[serde(default)]
+    relationships: HashMap<String, KitsuRelationship>,
+}
+
+#[derive(Deserialize, Debug)]
+struct KitsuDocument<D> {
+    data: D,
+    #[serde(default)]
+    included: Vec<KitsuResource>,
+}
+
+impl<D> KitsuDocument<D> {
+    /// IDs on other providers from a show's included mappings
+    fn mapped_ids(&self, anime: &KitsuResource) -> Vec<(MetadataSource, u32)> {
+        let Some(mappings) = anime.relationships.get("mappings") else {
+            return Vec::new();
+        };
+
+        mappings
+            .refs()
+            .iter()
+            .filter_map(|r| {
+                self.included
+                    .iter()
+                    .find(|inc| inc.kind == r.kind && inc.id == r.id)
+            })
+            .filter_map(|mapping| {
+                let source = match mapping.attributes.external_site.as_deref()? {
+                    ANILIST_SITE => MetadataSource::AniList,
+                    MAL_SITE => MetadataSource::MyAnimeList,
+                    _ => return None,
+                };
+                Some((source, mapping.attributes.external_id.as_deref()?.parse().ok()?))
+            })
+            .collect()
+    }
+
+    fn provider_show(&self, anime: &KitsuResource) -> Option<ProviderShow> {
+        let id: u32 = anime.id.parse().ok()?;
+        let attributes = &anime.attributes;
+
+        // "2023-09-29"
+        let date: Vec<u16> = attributes
+            .start_date
+            .as_deref()
+            .unwrap_or_default()
+            .split('-')
+            .filter_map(|part| part.parse().ok())
+            .collect();
+        let (year, month, day) = (
+            date.first().copied(),
+            date.get(1).map(|&m| m as u8),
+            date.get(2).map(|&d| d as u8),
+        );
+        let season = year.zip(month).map(|(year, month)| airing_season(year, month, day));
+
+        let show = AniShow {
+            id: Some(id),
+            title: Some(Title {
+                romaji: attributes
+                    .titles
+                    .en_jp
+                    .clone()
+                    .or_else(|| attributes.canonical_title.clone()),
+                english: attributes.titles.en.clone(),
+                native: attributes.titles.ja_jp.clone(),
+            }),
+            synonyms: attributes.abbreviated_titles.clone(),
+            average_score: attributes
+                .average_rating
+                .as_deref()
+                .and_then(|r| r.parse::<f64>().ok())
+                .map(|r| r.round() as u8),
+            popularity: attributes.user_count,
+            cover_image: attributes.poster_image.as_ref().map(|poster| CoverImage {
+                medium: poster.small.clone(),
+                large: poster.medium.clone(),
+                extra_large: poster.large.clone(),
+            }),
+            description: attributes.synopsis.as_deref().map(sanitize_html),
+            start_date: year.map(|year| FuzzyDate {
+                year: Some(year),
+                month: month.and_then(month_name).map(str::to_string),
+                day,
+            }),
+            episodes: attributes.episode_count,
+            duration: attributes.episode_length,
+            format: attributes.subtype.as_deref().map(|subtype| match subtype {
+                "TV" => MediaFormat::Tv,
+                "movie" => MediaFormat::Movie,
+                "OVA" => MediaFormat::Ova,
+                "ONA" => MediaFormat::Ona,
+                "special" => MediaFormat::Special,
+                "music" => MediaFormat::Music,
+                _ => MediaFormat::Other,
+            }),
+            status: attributes.status.as_deref().map(|status| match status {
+                "current" => MediaStatus::Releasing,
+                "finished" => MediaStatus::Finished,
+                "upcoming" | "unreleased" | "tba" => MediaStatus::NotYetReleased,
+                _ => MediaStatus::Other,
+            }),
+            season: season.map(|(season, _)| season),
+            season_year: season.map(|(_, year)| year),
+            is_adult: attributes.nsfw,
+            ..Default::default()
+        };
+
+        let mut ids = vec![(MetadataSource::Kitsu, id)];
+        ids.extend(self.mapped_ids(anime));
+        Some(ProviderShow { show, ids })
+    }
+}
+
+impl KitsuDocument<Vec<KitsuResource>> {
+    fn provider_shows(&self) -> Vec<ProviderShow> {
+        self.data
+            .iter()
+            .filter(|resource| resource.kind == "anime")
+            .filter_map(|anime| self.provider_show(anime))
+            .collect()
+    }
+}
+
+/// Shows from the raw JSON of a listing's pages
+fn parse_listing(body: &str) -> Result<Vec<ProviderShow>> {
+    let pages: Vec<KitsuDocument<Vec<KitsuResource>>> =
+        serde_json::from_str(body).context("Unexpected Kitsu response")?;
+    Ok(pages.iter().flat_map(|page| page.provider_shows()).collect())
+}
+
+/// Kitsu's public API
+pub struct KitsuProvider {
+    base_url: String,
+}
+
+impl KitsuProvider {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(&self, url: &str) -> Result<Option<T>> {
+        let resp = super::http_client()
+            .get(url)
+            .header("Accept", "application/vnd.api+json")
+            .send()
+            .await?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let text = resp.error_for_status().context("Kitsu request failed")?.text().await?;
+        Ok(Some(serde_json::from_str(&text).context("Unexpected Kitsu response")?))
+    }
+
+    /// Fetch a listing of shows, following `next` links for up to `max_pages`, as the
+    /// raw JSON of its pages
+    async fn list(&self, filters: &str, max_pages: u32) -> Result<String> {
+        let mut url = Some(format!(
+            "{}/anime?{}&include=mappings&page[limit]={}",
+            self.base_url, filters, PER_PAGE
+        ));
+        let mut pages: Vec<serde_json::Value> = Vec::new();
+
+        for _ in 0..max_pages {
+            let Some(page_url) = url.take() else {
+                break;
+            };
+            let Some(page) = self.get::<serde_json::Value>(&page_url).await? else {
+                break;
+            };
+            url = page["links"]["next"].as_str().map(str::to_string);
+            pages.push(page);
+        }
+
+        if url.is_some() {
+            tracing::warn!("Kitsu listing {} stopped after {} pages", filters, max_pages);
+        }
+        Ok(serde_json::to_string(&pages)?)
+    }
+}
+
+#[async_trait]
+impl MetadataProvider for KitsuProvider {
+    async fn seasonal(&self, season: Season, year: u16) -> Result<Vec<ProviderShow>> {
+        let name = format!("{:?}", season).to_lowercase();
+        let filters = format!("filter[season]={}&filter[seasonYear]={}", name, year);
+        cached_listing(
+            format!("kitsu:seasonal:{:?}:{}", season, year),
+            seasonal_ttl(season, year),
+            self.list(&filters, MAX_PAGES),
+            parse_listing,
+        )
+        .await
+    }
+
+    async fn airing(&self) -> Result<Vec<ProviderShow>> {
+        cached_listing(
+            "kitsu:airing".to_string(),
+            Some(AIRING_TTL_SECS),
+            self.list("filter[status]=current&sort=-userCount", MAX_PAGES),
+            parse_listing,
+        )
+        .await
+    }
+
+    async fn search(&self, title: &str) -> Result<Vec<ProviderShow>> {
+        parse_listing(
+            &self
+                .list(&format!("filter[text]={}", urlencoding::encode(title)), 1)
+                .await?,
+        )
+    }
+
+    async fn details(&self, id: u32) -> Result<Option<ProviderShow>> {
+        let url = format!("{}/anime/{}?include=mappings", self.base_url, id);
+        let Some(document) = self.get::<KitsuDocument<KitsuResource>>(&url).await? else {
+            return Ok(None);
+        };
+        Ok(document.provider_show(&document.data))
+    }
+
+    async fn find_by_anilist_id(&self, anilist_id: u32) -> Result<Option<u32>> {
+        let url = format!(
+            "{}/mappings?filter[externalSite]={}&filter[externalId]={}&include=item",
+            self.base_url, ANILIST_SITE, anilist_id
+        );
+        let Some(document) = self.get::<KitsuDocument<Vec<KitsuResource>>>(&url).await? else {
+            return Ok(None);
+        };
+
+        Ok(document
+            .data
+            .iter()
+            .filter_map(|mapping| mapping.relationships.get("item"))
+            .flat_map(|item| item.refs())
+            .find(|r| r.kind == "anime")
+            .and_then(|r| r.id.parse().ok()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_kitsu_page() {
+        let document: KitsuDocument<Vec<KitsuResource>> = serde_json::from_str(
+            r#"{
+                "data": [{
+                    "id": "46474",
+                    "type": "anime",
+                    "attributes": {
+                        "canonicalTitle": "Sousou no Frieren",
+                        "titles": {"en": "Frieren: Beyond Journey's End", "en_jp": "Sousou no Frieren", "ja_jp": "葬送のフリーレン"},
+                        "abbreviatedTitles": ["Frieren at the Funeral"],
+                        "averageRating": "90.6",
+                        "startDate": "2023-09-29",
+                        "status": "finished",
+                        "subtype": "TV",
+                        "episodeCount": 28,
+                        "episodeLength": 24,
+                        "nsfw": false
+                    },
+                    "relationships": {"mappings": {"data": [
+                        {"type": "mappings", "id": "1"},
+                        {"type": "mappings", "id": "2"},
+                        {"type": "mappings", "id": "3"}
+                    ]}}
+                }],
+                "included": [
+                    {"id": "1", "type": "mappings", "attributes": {"externalSite": "myanimelist/anime", "externalId": "52991"}},
+                    {"id": "2", "type": "mappings", "attributes": {"externalSite": "anilist/anime", "externalId": "154587"}},
+                    {"id": "3", "type": "mappings", "attributes": {"externalSite": "thetvdb", "externalId": "424536/1"}}
+                ],
+                "links": {"next": "https://kitsu.io/api/edge/anime?page%5Boffset%5D=20"}
+            }"#,
+        )
+        .unwrap();
+
+        let shows = document.provider_shows();
+        assert_eq!(shows.len(), 1);
+        assert_eq!(
+            shows[0].ids,
+            vec![
+                (MetadataSource::Kitsu, 46474),
+                (MetadataSource::MyAnimeList, 52991),
+                (MetadataSource::AniList, 154587),
+            ]
+        );
+
+        let show = &shows[0].show;
+        assert_eq!(show.id, Some(46474));
+        assert_eq!(show.title.as_ref().unwrap().romaji.as_deref(), Some("Sousou no Frieren"));
+        assert_eq!(show.format, Some(MediaFormat::Tv));
+        assert_eq!(show.status, Some(MediaStatus::Finished));
+        assert_eq!(show.season, Some(Season::FALL));
+        assert_eq!(show.season_year, Some(2023));
+        assert_eq!(show.average_score, Some(91));
+        assert_eq!(show.start_date.as_ref().unwrap().to_string(), "Sep 29, 2023");
+    }
+
+    #[test]
+    fn test_airing_season() {
+        assert_eq!(airing_season(2024, 1, Some(6)), (Season::WINTER, 2024));
+        assert_eq!(airing_season(2023, 9, Some(29)), (Season::FALL, 2023));
+        assert_eq!(airing_season(2023, 12, Some(28)), (Season::WINTER, 2024));
+        assert_eq!(airing_season(2023, 9, None), (Season::SUMMER, 2023));
+    }
+
+    #[test]
+    fn test_parse_kitsu_mapping_lookup() {
+        let document: KitsuDocument<Vec<KitsuResource>> = serde_json::from_str(
+            r#"{"data": [{
+                "id": "9",
+                "type": "mappings",
+                "attributes": {"externalSite": "anilist/anime", "externalId": "154587"},
+                "relationships": {"item": {"data": {"type": "anime", "id": "46474"}}}
+            }]}"#,
+        )
+        .unwrap();
+
+        // Mapping lookups aren't shows
+        assert!(document.provider_shows().is_empty());
+        let item = document.data[0].relationships["item"].refs();
+        assert_eq!(item[0].id, "46474");
+    }
+}
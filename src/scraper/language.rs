@@ -0,0 +1,91 @@
+//! Subtitle language detection from release titles
+//!
+//! Groups mark subtitle languages in many ways: `[ENG][POR-BR]` tags from Erai-raws,
+//! spelled-out names like `(Spanish)`, or French `VOSTFR`. Each language has a short
+//! code used in show preferences and filter patterns, plus the tags that mark it.
+
+/// A subtitle language and the title tags that indicate it
+pub struct Language {
+    pub code: &'static str,
+    pub name: &'static str,
+    pub tags: &'static [&'static str],
+}
+
+/// Languages offered in the UI, English first
+pub const LANGUAGES: &[Language] = &[
+    Language { code: "en", name: "English", tags: &["eng", "english"] },
+    Language { code: "es", name: "Spanish", tags: &["spa", "esp", "spanish", "español", "espanol", "castellano", "latino"] },
+    Language { code: "pt-br", name: "Portuguese (Brazil)", tags: &["por-br", "pt-br", "ptbr", "portuguese", "português", "portugues"] },
+    Language { code: "fr", name: "French", tags: &["fre", "fra", "french", "français", "francais", "vostfr"] },
+    Language { code: "de", name: "German", tags: &["ger", "deu", "german", "deutsch"] },
+    Language { code: "it", name: "Italian", tags: &["ita", "italian", "italiano"] },
+    Language { code: "ru", name: "Russian", tags: &["rus", "russian"] },
+    Language { code: "ar", name: "Arabic", tags: &["ara", "arabic"] },
+    Language { code: "id", name: "Indonesian", tags: &["ind", "indonesian"] },
+    Language { code: "vi", name: "Vietnamese", tags: &["vie", "vietnamese"] },
+    Language { code: "zh", name: "Chinese", tags: &["chs", "cht", "chi", "chinese", "big5"] },
+];
+
+/// Looks up a language by its code, ignoring case
+pub fn find_language(code: &str) -> Option<&'static Language> {
+    LANGUAGES.iter().find(|l| l.code.eq_ignore_ascii_case(code.trim()))
+}
+
+/// Returns true if the title is tagged with any of the comma-separated languages
+///
+/// Each entry is a language code from [`LANGUAGES`] or, failing that, a literal tag.
+/// Tags are matched against whole words of the title, so "ita" doesn't match
+/// "Itadaki".
+pub fn title_has_language(title: &str, languages: &str) -> bool {
+    let lower = title.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_alphanumeric() && c != '-')
+        .flat_map(|word| {
+            // "Erai-raws" should still offer "erai" and "raws", and "POR-BR" itself
+            std::iter::once(word).chain(word.split('-'))
+        })
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    languages
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .any(|entry| match find_language(entry) {
+            Some(language) => language.tags.iter().any(|tag| words.contains(tag)),
+            None => words.contains(&entry.to_lowercase().as_str()),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_erai_raws_tags() {
+        let title = "[Erai-raws] Goblin Slayer II - 03 [1080p][Multiple Subtitle] [ENG][POR-BR][SPA-LA]";
+        assert!(title_has_language(title, "en"));
+        assert!(title_has_language(title, "pt-br"));
+        assert!(title_has_language(title, "es"));
+        assert!(!title_has_language(title, "fr"));
+    }
+
+    #[test]
+    fn test_spelled_out_and_literal_tags() {
+        assert!(title_has_language("[Group] Show - 01 VOSTFR [1080p]", "de, fr"));
+        assert!(title_has_language("[Group] Show - 01 (Castellano) [720p]", "es"));
+        assert!(title_has_language("[Group] Show - 01 [Dual Audio]", "dual"));
+    }
+
+    #[test]
+    fn test_tags_match_whole_words() {
+        assert!(!title_has_language("[SubsPlease] Itadaki Seieki - 01 (1080p)", "it"));
+        assert!(!title_has_language("[SubsPlease] Frieren - 01 (1080p)", "fr"));
+    }
+
+    #[test]
+    fn test_find_language() {
+        assert_eq!(find_language(" PT-BR").map(|l| l.name), Some("Portuguese (Brazil)"));
+        assert!(find_language("xx").is_none());
+    }
+}
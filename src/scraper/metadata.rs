@@ -0,0 +1,254 @@
+//! Show metadata from AniList, MyAnimeList (through a Jikan API) or Kitsu
+//!
+//! Every provider returns shows in AniList's shape, with `id` being the provider's own
+//! ID. `Show.id` stays the AniList ID; the IDs a provider reports for other providers
+//! are recorded in `provider_ids`, so a show can be looked up on any of them.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::{collections::HashMap, future::Future};
+
+use super::{
+    anilist::{
+        get_anilist_all_airing_cached, get_anilist_data_cached, get_anilist_shows,
+        get_anilist_shows_by_mal, search_anilist, AniShow, Season,
+    },
+    jikan::{JikanProvider, JIKAN_API},
+    kitsu::{KitsuProvider, KITSU_API},
+    query_cache,
+};
+use crate::db::{self, MetadataSource};
+
+/// A show from a metadata provider
+#[derive(Debug, Clone)]
+pub struct ProviderShow {
+    /// The show, with `id` being the provider's own ID
+    pub show: AniShow,
+    /// IDs of the show on each provider, as far as this provider knows them
+    pub ids: Vec<(MetadataSource, u32)>,
+}
+
+impl ProviderShow {
+    /// ID of the show on a provider, if known
+    pub fn id_on(&self, source: MetadataSource) -> Option<u32> {
+        self.ids.iter().find(|(s, _)| *s == source).map(|(_, id)| *id)
+    }
+}
+
+/// A source of show metadata
+#[async_trait]
+pub trait MetadataProvider: Send + Sync {
+    /// Shows starting in a season
+    async fn seasonal(&self, season: Season, year: u16) -> Result<Vec<ProviderShow>>;
+
+    /// Shows airing right now
+    async fn airing(&self) -> Result<Vec<ProviderShow>>;
+
+    /// Shows matching a title, best matches first
+    async fn search(&self, title: &str) -> Result<Vec<ProviderShow>>;
+
+    /// A single show by this provider's ID
+    async fn details(&self, id: u32) -> Result<Option<ProviderShow>>;
+
+    /// This provider's ID for a show given its AniList ID
+    async fn find_by_anilist_id(&self, anilist_id: u32) -> Result<Option<u32>>;
+}
+
+/// The provider for a metadata source
+///
+/// MyAnimeList goes through the public Jikan API unless `JIKAN_API_URL` points at
+/// another Jikan-compatible instance.
+pub fn provider(source: MetadataSource) -> Box<dyn MetadataProvider> {
+    match source {
+        MetadataSource::AniList => Box::new(AniListProvider),
+        MetadataSource::MyAnimeList => Box::new(JikanProvider::new(
+            &std::env::var("JIKAN_API_URL").unwrap_or_else(|_| JIKAN_API.to_string()),
+        )),
+        MetadataSource::Kitsu => Box::new(KitsuProvider::new(KITSU_API)),
+    }
+}
+
+/// AniList, served from the AniList cache where possible
+pub struct AniListProvider;
+
+impl AniListProvider {
+    fn wrap(shows: Vec<AniShow>) -> Vec<ProviderShow> {
+        shows
+            .into_iter()
+            .map(|show| {
+                let ids = [
+                    show.id.map(|id| (MetadataSource::AniList, id)),
+                    show.id_mal.map(|id| (MetadataSource::MyAnimeList, id)),
+                ]
+                .into_iter()
+                .flatten()
+                .collect();
+                ProviderShow { show, ids }
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl MetadataProvider for AniListProvider {
+    async fn seasonal(&self, season: Season, year: u16) -> Result<Vec<ProviderShow>> {
+        Ok(Self::wrap(get_anilist_data_cached(season, year).await?.shows))
+    }
+
+    async fn airing(&self) -> Result<Vec<ProviderShow>> {
+        Ok(Self::wrap(get_anilist_all_airing_cached().await?.shows))
+    }
+
+    async fn search(&self, title: &str) -> Result<Vec<ProviderShow>> {
+        Ok(Self::wrap(search_anilist(title).await?))
+    }
+
+    async fn details(&self, id: u32) -> Result<Option<ProviderShow>> {
+        Ok(Self::wrap(get_anilist_shows(&[id]).await?).into_iter().next())
+    }
+
+    async fn find_by_anilist_id(&self, anilist_id: u32) -> Result<Option<u32>> {
+        Ok(Some(anilist_id))
+    }
+}
+
+/// Serve a provider listing from the cache while it's fresh, otherwise fetch and
+/// cache it; see [`query_cache::cached`]
+pub async fn cached_listing(
+    key: String,
+    ttl_secs: Option<i64>,
+    fetch: impl Future<Output = Result<String>>,
+    parse: fn(&str) -> Result<Vec<ProviderShow>>,
+) -> Result<Vec<ProviderShow>> {
+    Ok(query_cache::cached(key, ttl_secs, fetch, parse).await?.value)
+}
+
+/// Store the ID mappings reported with a list of shows
+pub async fn remember_ids(shows: &[ProviderShow]) {
+    let mappings: Vec<(u32, MetadataSource, u32)> = shows
+        .iter()
+        .filter_map(|show| Some((show.id_on(MetadataSource::AniList)?, &show.ids)))
+        .flat_map(|(anilist_id, ids)| ids.iter().map(move |(source, id)| (anilist_id, *source, *id)))
+        .filter(|(_, source, _)| *source != MetadataSource::AniList)
+        .collect();
+    if mappings.is_empty() {
+        return;
+    }
+
+    let result = db::with_db(move |conn| {
+        for (anilist_id, source, id) in mappings {
+            db::record_provider_id(conn, anilist_id, source, id)?;
+        }
+        Ok(())
+    })
+    .await;
+    if let Err(err) = result {
+        tracing::warn!("Failed to store provider id mappings: {:?}", err);
+    }
+}
+
+/// Give shows from any provider their AniList IDs, so they work like AniList's own
+///
+/// Known mappings come from the database. MyAnimeList IDs that aren't mapped yet are
+/// looked up on AniList in one go. Shows that still have no AniList ID are left out.
+pub async fn with_anilist_ids(shows: Vec<ProviderShow>) -> Vec<AniShow> {
+    remember_ids(&shows).await;
+
+    let pairs: Vec<(MetadataSource, u32)> = shows.iter().flat_map(|s| s.ids.clone()).collect();
+    let mut known: HashMap<(MetadataSource, u32), u32> = db::with_db(move |conn| {
+        let mut known = HashMap::new();
+        for (source, id) in pairs {
+            if let Some(anilist_id) = db::get_anilist_id(conn, source, id)? {
+                known.insert((source, id), anilist_id);
+            }
+        }
+        Ok(known)
+    })
+    .await
+    .unwrap_or_else(|err| {
+        tracing::warn!("Failed to load provider id mappings: {:?}", err);
+        HashMap::new()
+    });
+    let anilist_id =
+        |show: &ProviderShow, known: &HashMap<_, u32>| show.ids.iter().find_map(|pair| known.get(pair).copied());
+
+    let unmapped_mal: Vec<u32> = shows
+        .iter()
+        .filter(|show| anilist_id(show, &known).is_none())
+        .filter_map(|show| show.id_on(MetadataSource::MyAnimeList))
+        .collect();
+    if !unmapped_mal.is_empty() {
+        match get_anilist_shows_by_mal(&unmapped_mal).await {
+            Ok(found) => {
+                let found = AniListProvider::wrap(found);
+                remember_ids(&found).await;
+                for show in &found {
+                    if let (Some(id), Some(mal_id)) = (
+                        show.id_on(MetadataSource::AniList),
+                        show.id_on(MetadataSource::MyAnimeList),
+                    ) {
+                        known.insert((MetadataSource::MyAnimeList, mal_id), id);
+                    }
+                }
+            }
+            Err(err) => tracing::warn!("Failed to map MyAnimeList ids to AniList: {:?}", err),
+        }
+    }
+
+    let total = shows.len();
+    let mapped: Vec<AniShow> = shows
+        .into_iter()
+        .filter_map(|show| {
+            let id = anilist_id(&show, &known)?;
+            Some(AniShow {
+                id: Some(id),
+                id_mal: show.id_on(MetadataSource::MyAnimeList),
+                ..show.show
+            })
+        })
+        .collect();
+    if mapped.len() < total {
+        tracing::info!("{} of {} shows have no AniList id and were left out", total - mapped.len(), total);
+    }
+    mapped
+}
+
+/// ID of a show on a provider given its AniList ID, asking the provider if the
+/// mapping isn't known yet
+pub async fn resolve_provider_id(anilist_id: u32, source: MetadataSource) -> Result<Option<u32>> {
+    if let Some(id) =
+        db::with_db(move |conn| db::get_provider_id(conn, anilist_id, source)).await?
+    {
+        return Ok(Some(id));
+    }
+
+    let Some(id) = provider(source).find_by_anilist_id(anilist_id).await? else {
+        return Ok(None);
+    };
+    db::with_db(move |conn| db::record_provider_id(conn, anilist_id, source, id)).await?;
+    Ok(Some(id))
+}
+
+/// Page of a show on a provider's website
+pub fn show_url(source: MetadataSource, id: u32) -> String {
+    match source {
+        MetadataSource::AniList => format!("https://anilist.co/anime/{}", id),
+        MetadataSource::MyAnimeList => format!("https://myanimelist.net/anime/{}", id),
+        MetadataSource::Kitsu => format!("https://kitsu.io/anime/{}", id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anilist_ids() {
+        let show: AniShow = serde_json::from_str(r#"{"id": 154587, "idMal": 52991}"#).unwrap();
+        let wrapped = AniListProvider::wrap(vec![show]);
+
+        assert_eq!(wrapped[0].id_on(MetadataSource::AniList), Some(154587));
+        assert_eq!(wrapped[0].id_on(MetadataSource::MyAnimeList), Some(52991));
+        assert_eq!(wrapped[0].id_on(MetadataSource::Kitsu), None);
+    }
+}
@@ -1,10 +1,24 @@
 pub mod transmission;
 pub mod nyaasi;
 pub mod anilist;
+pub mod anilist_progress;
 pub mod tracker;
 pub mod rss;
 pub mod season_parser;
 pub mod filter_engine;
+pub mod bencode;
+pub mod archive;
+pub mod fansub;
+pub mod title_matcher;
+pub mod language;
+pub mod covers;
+pub mod metadata;
+pub mod query_cache;
+pub mod jikan;
+pub mod kitsu;
+pub mod schedule;
+pub mod sync_jobs;
+pub mod activity;
 mod raii_process_driver;
 
 use reqwest::Client;
@@ -1,9 +1,32 @@
-use anyhow::{self, Ok};
+use anyhow::{self, Context, Ok};
 use reqwest;
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html, Selector};
 use serde::Deserialize;
 
-use super::rss::detect_fansub_source;
+use super::rss::{detect_fansub_source, parse_size, TorrentFile};
+
+/// Nyaa's anime categories as (code, name), used for the `c=` search parameter
+pub const NYAA_CATEGORIES: &[(&str, &str)] = &[
+    ("1_0", "Anime - All"),
+    ("1_1", "Anime - Music Video"),
+    ("1_2", "Anime - English-translated"),
+    ("1_3", "Anime - Non-English-translated"),
+    ("1_4", "Anime - Raw"),
+];
+
+/// Category searched when neither the show nor the global config picks one
+pub const DEFAULT_CATEGORY: &str = "1_2";
+
+/// Splits a category code like "1_3" into (category, subcategory)
+///
+/// Returns None for codes that aren't in [`NYAA_CATEGORIES`].
+pub fn split_category(code: &str) -> Option<(u8, u8)> {
+    if !NYAA_CATEGORIES.iter().any(|(c, _)| *c == code) {
+        return None;
+    }
+    let (category, subcategory) = code.split_once('_')?;
+    Some((category.parse().ok()?, subcategory.parse().ok()?))
+}
 
 #[derive(Debug)]
 pub struct Torrent {
@@ -95,6 +118,7 @@ pub struct Link {
     pub episode: String,
     pub magnet_link: Option<String>,
     pub torrent_link: Option<String>,
+    pub view_link: Option<String>, // https://nyaa.si/view/ID
     pub source: String, // Detected fansub source from title
 }
 
@@ -110,10 +134,16 @@ fn extract_show_info(title: &str) -> (&str, &str) {
     ("N/A", "N/A")
 }
 
-pub async fn fetch_sources(keyword: &str, user: &str) -> anyhow::Result<Vec<Link>> {
+/// Searches Nyaa's HTML listing for 1080p releases
+///
+/// `category` is a code from [`NYAA_CATEGORIES`]; unknown codes fall back to the
+/// default English-translated category.
+pub async fn fetch_sources(keyword: &str, user: &str, category: &str) -> anyhow::Result<Vec<Link>> {
     let local_user = if user != "default" {Some(user)} else {None};
+    let (category, subcategory) = split_category(category).unzip();
     let request_text =
-        get_torrents_from_nyaa(keyword, local_user, None, None, None, None, None, None).await?;
+        get_torrents_from_nyaa(keyword, local_user, None, category, subcategory, None, None, None)
+            .await?;
     let parsed = parse_nyaa(request_text);
 
     let links = parsed
@@ -127,6 +157,7 @@ pub async fn fetch_sources(keyword: &str, user: &str) -> anyhow::Result<Vec<Link
                     episode: episode.to_string(),
                     torrent_link: p.torrent.clone(),
                     magnet_link: p.magnet.clone(),
+                    view_link: p.view.as_ref().map(|v| format!("https://nyaa.si{}", v)),
                     source,
                 })
             })
@@ -136,6 +167,167 @@ pub async fn fetch_sources(keyword: &str, user: &str) -> anyhow::Result<Vec<Link
     Ok(links)
 }
 
+/// Details scraped from a Nyaa torrent page (`https://nyaa.si/view/ID`)
+#[derive(Debug, Clone, Default)]
+pub struct TorrentDetails {
+    pub title: String,
+    pub submitter: Option<String>,
+    pub total_size: Option<String>,
+    pub info_hash: Option<String>,
+    pub description: String,
+    pub comment_count: u32,
+    pub files: Vec<TorrentFile>,
+}
+
+/// Returns true if the URL points to a Nyaa torrent page
+pub fn is_nyaa_view_url(url: &str) -> bool {
+    url.starts_with("https://nyaa.si/view/") || url.starts_with("http://nyaa.si/view/")
+}
+
+/// Fetches and parses a Nyaa torrent page
+///
+/// # Arguments
+/// * `view_url` - The torrent page URL (`RssItem.view_url`)
+pub async fn fetch_torrent_details(view_url: &str) -> anyhow::Result<TorrentDetails> {
+    if !is_nyaa_view_url(view_url) {
+        return Err(anyhow::anyhow!("Not a Nyaa torrent page: {}", view_url));
+    }
+
+    let html = super::http_client()
+        .get(view_url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to fetch torrent page {}", view_url))?
+        .text()
+        .await
+        .with_context(|| "Failed to read torrent page body")?;
+
+    Ok(parse_torrent_details(&html))
+}
+
+/// Parses the HTML of a Nyaa torrent page
+///
+/// The page consists of a panel with label/value rows ("Submitter:", "File size:",
+/// "Info hash:"), a markdown description, a nested file list and a comments panel.
+pub fn parse_torrent_details(html: &str) -> TorrentDetails {
+    let fragment = Html::parse_document(html);
+
+    let selector_title = Selector::parse("h3.panel-title").unwrap();
+    let selector_label = Selector::parse("div.panel-body div.row > div.col-md-1").unwrap();
+    let selector_description = Selector::parse("#torrent-description").unwrap();
+    let selector_file_list = Selector::parse("div.torrent-file-list > ul").unwrap();
+    let selector_comments = Selector::parse("#comments h3.panel-title").unwrap();
+
+    let mut details = TorrentDetails {
+        title: fragment
+            .select(&selector_title)
+            .next()
+            .map(|e| element_text(&e))
+            .unwrap_or_default(),
+        ..Default::default()
+    };
+
+    // Each label column is followed by its value column
+    for label in fragment.select(&selector_label) {
+        let value = match label.next_siblings().find_map(ElementRef::wrap) {
+            Some(v) => element_text(&v),
+            None => continue,
+        };
+
+        match element_text(&label).as_str() {
+            "Submitter:" => details.submitter = Some(value),
+            "File size:" => details.total_size = Some(value),
+            "Info hash:" => details.info_hash = Some(value.to_lowercase()),
+            _ => {}
+        }
+    }
+
+    details.description = fragment
+        .select(&selector_description)
+        .next()
+        .map(|e| e.text().collect::<String>().trim().to_string())
+        .unwrap_or_default();
+
+    details.comment_count = fragment
+        .select(&selector_comments)
+        .next()
+        .and_then(|e| {
+            element_text(&e)
+                .rsplit(' ')
+                .next()
+                .and_then(|n| n.parse().ok())
+        })
+        .unwrap_or(0);
+
+    if let Some(list) = fragment.select(&selector_file_list).next() {
+        collect_files(list, "", &mut details.files);
+    }
+
+    details
+}
+
+/// Recursively walks the nested `<ul>` file tree, joining folder names into paths
+fn collect_files(list: ElementRef, prefix: &str, files: &mut Vec<TorrentFile>) {
+    for li in list
+        .children()
+        .filter_map(ElementRef::wrap)
+        .filter(|e| e.value().name() == "li")
+    {
+        let folder = li
+            .children()
+            .filter_map(ElementRef::wrap)
+            .find(|e| e.value().name() == "a" && e.value().classes().any(|c| c == "folder"));
+
+        if let Some(folder) = folder {
+            let path = join_path(prefix, &element_text(&folder));
+            for sub_list in li
+                .children()
+                .filter_map(ElementRef::wrap)
+                .filter(|e| e.value().name() == "ul")
+            {
+                collect_files(sub_list, &path, files);
+            }
+            continue;
+        }
+
+        // File entries are a bare text node followed by <span class="file-size">(1.2 GiB)</span>
+        let name: String = li
+            .children()
+            .filter_map(|n| n.value().as_text().map(|t| t.to_string()))
+            .collect::<String>()
+            .trim()
+            .to_string();
+
+        if name.is_empty() {
+            continue;
+        }
+
+        let size = li
+            .children()
+            .filter_map(ElementRef::wrap)
+            .find(|e| e.value().classes().any(|c| c == "file-size"))
+            .and_then(|e| parse_size(element_text(&e).trim_matches(|c| c == '(' || c == ')')))
+            .unwrap_or(0);
+
+        files.push(TorrentFile {
+            path: join_path(prefix, &name),
+            size,
+        });
+    }
+}
+
+fn join_path(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", prefix, name)
+    }
+}
+
+fn element_text(element: &ElementRef) -> String {
+    element.text().collect::<String>().trim().to_string()
+}
+
 #[cfg(test)]
 mod test {
     use std::collections::HashMap;
@@ -193,6 +385,102 @@ mod test {
         assert!(parsed.len() > 0);
     }
 
+    const SAMPLE_VIEW_PAGE: &str = r##"<html><body><div class="container">
+<div class="panel panel-success">
+    <div class="panel-heading">
+        <h3 class="panel-title">[SubsPlease] Sousou no Frieren - 01 (1080p) [F02B9CEE].mkv</h3>
+    </div>
+    <div class="panel-body">
+        <div class="row">
+            <div class="col-md-1">Category:</div>
+            <div class="col-md-5"><a href="/?c=1_0">Anime</a> - <a href="/?c=1_2">English-translated</a></div>
+            <div class="col-md-1">Date:</div>
+            <div class="col-md-5" data-timestamp="1695999600">2023-09-29 15:00 UTC</div>
+        </div>
+        <div class="row">
+            <div class="col-md-1">Submitter:</div>
+            <div class="col-md-5"><a class="text-success" href="/user/subsplease" title="Trusted">subsplease</a></div>
+            <div class="col-md-1">Seeders:</div>
+            <div class="col-md-5"><span style="color: green;">1234</span></div>
+        </div>
+        <div class="row">
+            <div class="col-md-1">File size:</div>
+            <div class="col-md-5">1.4 GiB</div>
+            <div class="col-md-1">Completed:</div>
+            <div class="col-md-5">56789</div>
+        </div>
+        <div class="row">
+            <div class="col-md-offset-6 col-md-1">Info hash:</div>
+            <div class="col-md-5"><kbd>E30690D4A8D1F5E45F5DED430BDAEDC710DA0245</kbd></div>
+        </div>
+    </div>
+</div>
+<div class="panel panel-default">
+    <div markdown-text class="panel-body" id="torrent-description">Join our IRC &amp; enjoy!</div>
+</div>
+<div class="panel panel-default">
+    <div class="panel-heading"><h3 class="panel-title">File list</h3></div>
+    <div class="torrent-file-list panel-body">
+        <ul>
+            <li><a href="" class="folder"><i class="fa fa-folder-open"></i>Extras</a>
+                <ul>
+                    <li><i class="fa fa-file"></i>setup.exe <span class="file-size">(12 KiB)</span></li>
+                </ul>
+            </li>
+            <li><i class="fa fa-file"></i>[SubsPlease] Sousou no Frieren - 01 (1080p) [F02B9CEE].mkv <span class="file-size">(1.4 GiB)</span></li>
+        </ul>
+    </div>
+</div>
+<div id="comments" class="panel panel-default">
+    <div class="panel-heading">
+        <a class="collapsed" data-toggle="collapse" href="#collapse-comments" role="button">
+            <h3 class="panel-title">Comments - 7</h3>
+        </a>
+    </div>
+</div>
+</div></body></html>"##;
+
+    #[test]
+    fn test_parse_torrent_details() {
+        let details = parse_torrent_details(SAMPLE_VIEW_PAGE);
+
+        assert_eq!(
+            details.title,
+            "[SubsPlease] Sousou no Frieren - 01 (1080p) [F02B9CEE].mkv"
+        );
+        assert_eq!(details.submitter.as_deref(), Some("subsplease"));
+        assert_eq!(details.total_size.as_deref(), Some("1.4 GiB"));
+        assert_eq!(
+            details.info_hash.as_deref(),
+            Some("e30690d4a8d1f5e45f5ded430bdaedc710da0245")
+        );
+        assert_eq!(details.description, "Join our IRC & enjoy!");
+        assert_eq!(details.comment_count, 7);
+
+        assert_eq!(details.files.len(), 2);
+        assert_eq!(details.files[0].path, "Extras/setup.exe");
+        assert_eq!(details.files[0].size, 12 * 1024);
+        assert_eq!(
+            details.files[1].path,
+            "[SubsPlease] Sousou no Frieren - 01 (1080p) [F02B9CEE].mkv"
+        );
+    }
+
+    #[test]
+    fn test_split_category() {
+        assert_eq!(split_category("1_3"), Some((1, 3)));
+        assert_eq!(split_category(DEFAULT_CATEGORY), Some((1, 2)));
+        assert_eq!(split_category("3_1"), None);
+        assert_eq!(split_category("junk"), None);
+    }
+
+    #[test]
+    fn test_is_nyaa_view_url() {
+        assert!(is_nyaa_view_url("https://nyaa.si/view/2059096"));
+        assert!(!is_nyaa_view_url("https://example.com/view/2059096"));
+        assert!(!is_nyaa_view_url("https://nyaa.si/download/2059096.torrent"));
+    }
+
     #[test]
     fn test_parse_title_episode() {
         let title1 = "[SubsPlease] One Piece - 1060 (1080p) [37A98D45].mkv";
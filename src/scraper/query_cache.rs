@@ -0,0 +1,76 @@
+//! Cached metadata listings
+//!
+//! Seasonal and airing listings from AniList and the other metadata providers are
+//! kept in the `anilist_cache` table as the raw JSON they were parsed from, keyed by
+//! the listing. Entries are served while they're within their TTL; once they expire,
+//! they're still served when the provider can't be reached.
+
+use anyhow::Result;
+use std::future::Future;
+
+use crate::db;
+
+/// A cached result
+pub struct Cached<T> {
+    pub value: T,
+    /// When the served result was fetched, if it's an expired entry served because
+    /// the provider couldn't be reached
+    pub stale_since: Option<String>,
+}
+
+/// Serve a listing from the cache while it's fresh, otherwise fetch and cache it
+///
+/// `fetch` gives the listing's raw JSON, which `parse` turns into its value. If the
+/// fetch fails, an expired cache entry is served instead.
+pub async fn cached<T>(
+    key: String,
+    ttl_secs: Option<i64>,
+    fetch: impl Future<Output = Result<String>>,
+    parse: impl Fn(&str) -> Result<T>,
+) -> Result<Cached<T>> {
+    let cache_key = key.clone();
+    let cached = match db::with_db(move |conn| db::get_cached_query(conn, &cache_key)).await {
+        Ok(cached) => cached,
+        Err(err) => {
+            tracing::warn!("Could not read cache for '{}': {:?}", key, err);
+            None
+        }
+    };
+
+    if let Some(cached) = cached.as_ref().filter(|c| c.fresh) {
+        match parse(&cached.body) {
+            Ok(value) => return Ok(Cached { value, stale_since: None }),
+            Err(err) => tracing::warn!("Discarding unreadable cache for '{}': {:?}", key, err),
+        }
+    }
+
+    match fetch.await {
+        Ok(body) => {
+            let value = parse(&body)?;
+            let cache_key = key.clone();
+            if let Err(err) = db::with_db(move |conn| {
+                db::store_cached_query(conn, &cache_key, &body, ttl_secs)
+            })
+            .await
+            {
+                tracing::warn!("Could not cache results for '{}': {:?}", key, err);
+            }
+            Ok(Cached { value, stale_since: None })
+        }
+        Err(err) => match cached {
+            Some(cached) => {
+                tracing::warn!(
+                    "Query '{}' failed, serving cache from {}: {:?}",
+                    key,
+                    cached.fetched_at,
+                    err
+                );
+                Ok(Cached {
+                    value: parse(&cached.body)?,
+                    stale_since: Some(cached.fetched_at),
+                })
+            }
+            None => Err(err),
+        },
+    }
+}
@@ -10,6 +10,10 @@ use quick_xml::Reader;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 
+use super::fansub::canonical_group;
+use super::nyaasi::DEFAULT_CATEGORY;
+use super::title_matcher::similarity;
+
 /// RSS source type for fetching torrents
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -38,7 +42,7 @@ impl RssSource {
 /// - "Sousou no Frieren 2nd Season" -> "Sousou no Frieren"
 /// - "My Hero Academia Season 7" -> "My Hero Academia"
 /// - "One Piece" -> "One Piece" (unchanged)
-fn normalize_title_for_search(title: &str) -> String {
+pub(crate) fn normalize_title_for_search(title: &str) -> String {
     let patterns = [
         r"\s+(?:2nd|3rd|[4-9]th)\s+Season\s*$",           // "2nd Season", "3rd Season", etc.
         r"\s+Season\s+\d+\s*$",                           // "Season 2", "Season 10"
@@ -69,6 +73,58 @@ pub struct RssItem {
     pub size: String,
     pub seeders: u32,
     pub leechers: u32,
+    /// File list of the torrent, if its contents have been inspected
+    pub files: Option<Vec<TorrentFile>>,
+}
+
+/// A single file inside a torrent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TorrentFile {
+    /// Path of the file relative to the torrent root
+    pub path: String,
+    /// Size in bytes
+    pub size: u64,
+}
+
+/// Parses a human-readable size as shown by Nyaa (e.g. "1.2 GiB", "500 MiB") into bytes
+///
+/// Both binary (KiB) and decimal-looking (KB) units are treated as powers of 1024,
+/// which is what Nyaa actually uses.
+pub fn parse_size(size: &str) -> Option<u64> {
+    let re = Regex::new(r"(?i)^\s*([\d.]+)\s*([KMGT]i?B|B|Bytes?)?\s*$").ok()?;
+    let caps = re.captures(size)?;
+    let value: f64 = caps.get(1)?.as_str().parse().ok()?;
+
+    let multiplier = match caps.get(2).map(|m| m.as_str().to_uppercase()) {
+        Some(unit) if unit.starts_with('K') => 1024f64,
+        Some(unit) if unit.starts_with('M') => 1024f64.powi(2),
+        Some(unit) if unit.starts_with('G') => 1024f64.powi(3),
+        Some(unit) if unit.starts_with('T') => 1024f64.powi(4),
+        _ => 1.0,
+    };
+
+    Some((value * multiplier).round() as u64)
+}
+
+/// Formats a byte count the way Nyaa displays sizes (e.g. "1.2 GiB")
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{} Bytes", bytes);
+    }
+
+    let mut value = bytes as f64;
+    let mut unit = "Bytes";
+    for u in UNITS {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = u;
+    }
+
+    format!("{:.1} {}", value, unit)
 }
 
 /// Represents a parsed episode with extracted metadata
@@ -82,36 +138,98 @@ pub struct ParsedEpisode {
     pub magnet_url: String,     // Constructed from info_hash
 }
 
+/// Query used for shows without a template of their own
+pub const DEFAULT_QUERY_TEMPLATE: &str = "{group} {alternate}";
+
+/// How to search Nyaa for a show
+///
+/// The query template is passed to Nyaa as-is apart from its placeholders, so
+/// Nyaa's own syntax works: `-batch` excludes a term and `(HEVC|x265)` matches either.
+#[derive(Debug, Clone)]
+pub struct NyaaSearch {
+    /// Category code (e.g., "1_2")
+    pub category: String,
+    /// Query with `{group}`, `{alternate}` and `{quality}` placeholders; None uses
+    /// [`DEFAULT_QUERY_TEMPLATE`]
+    pub query_template: Option<String>,
+    /// Search the group's user feed (`u=`) instead of putting the group in the query
+    pub user_feed: bool,
+}
+
+impl NyaaSearch {
+    /// The default search in a category
+    pub fn new(category: &str) -> Self {
+        Self {
+            category: category.to_string(),
+            query_template: None,
+            user_feed: false,
+        }
+    }
+
+    /// Search query for one of the show's titles
+    ///
+    /// With the user feed the group is already the uploader, so `{group}` is left empty.
+    pub fn query(&self, group: &str, alternate: &str, quality: &str) -> String {
+        let template = self
+            .query_template
+            .as_deref()
+            .filter(|t| !t.trim().is_empty())
+            .unwrap_or(DEFAULT_QUERY_TEMPLATE);
+        let group = if self.user_feed { "" } else { group };
+        // Normalize title to remove season suffixes that don't match Nyaa naming
+        expand_query_template(template, group, &normalize_title_for_search(alternate), quality)
+    }
+
+    /// Nyaa RSS URL searching for one of the show's titles
+    pub fn feed_url(&self, group: &str, alternate: &str, quality: &str) -> String {
+        let mut url = format!(
+            "https://nyaa.si/?page=rss&q={}&c={}&f=0",
+            urlencoding::encode(&self.query(group, alternate, quality)),
+            urlencoding::encode(&self.category)
+        );
+        if self.user_feed && !group.is_empty() {
+            url.push_str(&format!("&u={}", urlencoding::encode(group)));
+        }
+        url
+    }
+}
+
+/// Fills in the placeholders of a query template and collapses the whitespace left
+/// by empty ones
+pub fn expand_query_template(template: &str, group: &str, alternate: &str, quality: &str) -> String {
+    template
+        .replace("{group}", group)
+        .replace("{alternate}", alternate)
+        .replace("{quality}", quality)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 /// Fetches and parses an RSS feed from nyaa.si
 ///
 /// # Arguments
 /// * `source` - The uploader name (e.g., "subsplease", "Erai-raws")
 /// * `alternate` - The search term / show name
+/// * `category` - Nyaa category code (e.g., "1_2" for English-translated anime)
 ///
 /// # Returns
 /// A vector of `RssItem` parsed from the feed
 ///
 /// # Example
 /// ```ignore
-/// let items = fetch_rss_feed("subsplease", "One Piece").await?;
+/// let items = fetch_rss_feed("subsplease", "One Piece", "1_2").await?;
 /// ```
-pub async fn fetch_rss_feed(source: &str, alternate: &str) -> Result<Vec<RssItem>> {
-    // Normalize title to remove season suffixes that don't match Nyaa naming
-    let normalized_title = normalize_title_for_search(alternate);
-    let query = format!("{} {}", source, normalized_title);
-    let encoded_query = urlencoding::encode(&query);
-    let url = format!(
-        "https://nyaa.si/?page=rss&q={}&c=1_2&f=0",
-        encoded_query
-    );
+pub async fn fetch_rss_feed(source: &str, alternate: &str, category: &str) -> Result<Vec<RssItem>> {
+    fetch_nyaa_rss(&NyaaSearch::new(category).feed_url(source, alternate, "")).await
+}
 
+/// Fetches and parses a Nyaa RSS feed URL, e.g. one built by [`NyaaSearch::feed_url`]
+pub async fn fetch_nyaa_rss(url: &str) -> Result<Vec<RssItem>> {
     tracing::debug!("Fetching Nyaa RSS: {}", url);
-    if normalized_title != alternate {
-        tracing::debug!("Title normalized: '{}' -> '{}'", alternate, normalized_title);
-    }
 
     let response = super::http_client()
-        .get(&url)
+        .get(url)
         .send()
         .await
         .with_context(|| format!("Failed to fetch RSS feed from {}", url))?;
@@ -163,42 +281,99 @@ pub async fn fetch_subsplease_rss(quality: &str) -> Result<Vec<RssItem>> {
 /// # Arguments
 /// * `source` - The RSS source type (Nyaa or SubsPleaseDirect)
 /// * `source_name` - For Nyaa: the uploader name; for SubsPlease: ignored
-/// * `show_name` - The show name to search/filter for
+/// * `show_names` - The show's titles to search/filter for (alternate and aliases)
 /// * `quality` - Quality preference (e.g., "1080p")
+/// * `search` - For Nyaa: the category, query template and user feed option;
+///   for SubsPlease: ignored
 ///
 /// # Returns
 /// A vector of `RssItem` matching the criteria
 pub async fn fetch_rss_by_source(
     source: RssSource,
     source_name: &str,
-    show_name: &str,
+    show_names: &[&str],
     quality: &str,
+    search: &NyaaSearch,
 ) -> Result<Vec<RssItem>> {
     match source {
         RssSource::Nyaa => {
-            // Nyaa: search with source + show name
-            fetch_rss_feed(source_name, show_name).await
+            // Nyaa: search with source + each show name, merging the results
+            let mut items: Vec<RssItem> = Vec::new();
+            let mut searched: Vec<String> = Vec::new();
+            let mut last_error = None;
+            for show_name in show_names {
+                // A template without {alternate} gives the same URL for every title
+                let url = search.feed_url(source_name, show_name, quality);
+                if searched.contains(&url) {
+                    continue;
+                }
+                searched.push(url.clone());
+
+                match fetch_nyaa_rss(&url).await {
+                    Ok(found) => {
+                        for item in found {
+                            if !items.iter().any(|known| same_release(known, &item)) {
+                                items.push(item);
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!("Nyaa search for '{}' failed: {:?}", show_name, e);
+                        last_error = Some(e);
+                    }
+                }
+            }
+
+            // Only fail if every search failed
+            match last_error {
+                Some(e) if items.is_empty() => Err(e),
+                _ => Ok(items),
+            }
         }
         RssSource::SubsPleaseDirect => {
             // SubsPlease: fetch all at quality, then filter by show name
             let all_items = fetch_subsplease_rss(quality).await?;
-
-            // Filter items that match the show name (case-insensitive)
-            let show_lower = show_name.to_lowercase();
-            let filtered: Vec<RssItem> = all_items
-                .into_iter()
-                .filter(|item| {
-                    let title_lower = item.title.to_lowercase();
-                    // Check if the show name appears in the title
-                    title_lower.contains(&show_lower)
-                })
-                .collect();
-
-            Ok(filtered)
+            Ok(filter_items_by_show(all_items, show_names))
         }
     }
 }
 
+/// Whether two feed items are the same release, by hash or else by torrent link
+fn same_release(a: &RssItem, b: &RssItem) -> bool {
+    if !a.info_hash.is_empty() && !b.info_hash.is_empty() {
+        a.info_hash.eq_ignore_ascii_case(&b.info_hash)
+    } else {
+        a.torrent_link == b.torrent_link && a.title == b.title
+    }
+}
+
+/// Minimum similarity between a release's show title and the tracked show name
+/// for a SubsPlease direct item to count as that show
+const DIRECT_MATCH_THRESHOLD: f64 = 0.9;
+
+/// Keeps the items whose parsed show title fuzzily matches any of the show names
+///
+/// Season suffixes are ignored on both sides, since season filtering happens later.
+/// Titles that can't be parsed fall back to comparing the whole release title.
+pub fn filter_items_by_show(items: Vec<RssItem>, show_names: &[&str]) -> Vec<RssItem> {
+    let wanted: Vec<String> = show_names
+        .iter()
+        .map(|name| normalize_title_for_search(name))
+        .collect();
+    items
+        .into_iter()
+        .filter(|item| {
+            let release_show = parse_episode_info_full(&item.title)
+                .map(|info| info.show_title)
+                .unwrap_or_else(|| item.title.clone());
+            let release_show = normalize_title_for_search(&release_show);
+            wanted
+                .iter()
+                .any(|name| similarity(&release_show, name) >= DIRECT_MATCH_THRESHOLD)
+        })
+        .collect()
+}
+
 /// Parses SubsPlease RSS XML content into a vector of RssItem
 ///
 /// SubsPlease RSS has a simpler format than Nyaa - it doesn't include
@@ -297,8 +472,9 @@ impl SubsPleaseItemBuilder {
             let torrent_url = format!("https://nyaa.si/download/{}.torrent", id);
             (torrent_url, link.clone(), String::new())
         } else {
-            // Fallback: use the link as-is
-            (link.clone(), link, String::new())
+            // Fallback: use the link as-is, keeping the hash if it's a magnet
+            let info_hash = info_hash_from_magnet(&link).unwrap_or_default();
+            (link.clone(), link, info_hash)
         };
 
         Some(RssItem {
@@ -311,6 +487,7 @@ impl SubsPleaseItemBuilder {
             size: String::new(),
             seeders: 0,
             leechers: 0,
+            files: None,
         })
     }
 }
@@ -426,6 +603,7 @@ impl RssItemBuilder {
             size: self.size.unwrap_or_default(),
             seeders: self.seeders.unwrap_or(0),
             leechers: self.leechers.unwrap_or(0),
+            files: None,
         })
     }
 }
@@ -542,45 +720,34 @@ pub fn parse_episode_info_full(title: &str) -> Option<EpisodeInfo> {
 
 /// Detects the fansub source/group from a torrent title
 ///
-/// Parses common fansub group brackets from anime release titles.
+/// Parses the group tag in brackets at the start of anime release titles and
+/// resolves it through the fansub registry, so aliases map to one canonical name.
 ///
 /// # Arguments
 /// * `title` - The torrent title string (e.g., "[SubsPlease] One Piece - 1060 (1080p)")
 ///
 /// # Returns
-/// The detected source name (lowercase), or "subsplease" as default
+/// The canonical group name, or "SubsPlease" as default
 ///
 /// # Examples
 /// ```ignore
-/// assert_eq!(detect_fansub_source("[SubsPlease] One Piece - 01 (1080p)"), "subsplease");
+/// assert_eq!(detect_fansub_source("[SubsPlease] One Piece - 01 (1080p)"), "SubsPlease");
+/// assert_eq!(detect_fansub_source("[SP] One Piece - 01 (1080p)"), "SubsPlease");
 /// assert_eq!(detect_fansub_source("[Erai-raws] Frieren - 01 [1080p]"), "Erai-raws");
-/// assert_eq!(detect_fansub_source("[Judas] Attack on Titan - 01.mkv"), "judas");
 /// ```
 pub fn detect_fansub_source(title: &str) -> String {
     // Pattern to extract group name from brackets at the start
     let re = Regex::new(r"^\[([^\]]+)\]").ok();
 
-    if let Some(regex) = re {
-        if let Some(captures) = regex.captures(title) {
-            if let Some(group) = captures.get(1) {
-                let source = group.as_str().trim();
-                // Return common groups with their canonical casing
-                return match source.to_lowercase().as_str() {
-                    "subsplease" => "subsplease".to_string(),
-                    "erai-raws" => "Erai-raws".to_string(),
-                    "horriblesubs" => "horriblesubs".to_string(),
-                    "judas" => "judas".to_string(),
-                    "yameii" => "yameii".to_string(),
-                    "ember" => "ember".to_string(),
-                    "asm" => "asm".to_string(),
-                    _ => source.to_string(), // Preserve original casing for unknown groups
-                };
-            }
-        }
+    if let Some(regex) = re
+        && let Some(group) = regex.captures(title).and_then(|c| c.get(1))
+    {
+        // Unknown groups keep their original casing
+        return canonical_group(group.as_str());
     }
 
-    // Default to subsplease if no group found
-    "subsplease".to_string()
+    // Default to SubsPlease if no group found
+    "SubsPlease".to_string()
 }
 
 /// Constructs a magnet URL from an info hash and title
@@ -588,21 +755,44 @@ pub fn detect_fansub_source(title: &str) -> String {
 /// # Arguments
 /// * `info_hash` - The torrent info hash (40 character hex string)
 /// * `title` - The display name for the torrent
+/// * `trackers` - Announce URLs appended as `tr=` parameters, so peers can be found without DHT
 ///
 /// # Returns
 /// A properly formatted magnet URL
 ///
 /// # Example
 /// ```ignore
-/// let magnet = construct_magnet_url("e30690d4a8d1f5e45f5ded430bdaedc710da0245", "Show Name");
+/// let magnet = construct_magnet_url("e30690d4a8d1f5e45f5ded430bdaedc710da0245", "Show Name", &["udp://tracker:80"]);
 /// assert!(magnet.starts_with("magnet:?xt=urn:btih:"));
 /// ```
-pub fn construct_magnet_url(info_hash: &str, title: &str) -> String {
+pub fn construct_magnet_url<S: AsRef<str>>(info_hash: &str, title: &str, trackers: &[S]) -> String {
     let encoded_title = urlencoding::encode(title);
-    format!(
+    let mut magnet = format!(
         "magnet:?xt=urn:btih:{}&dn={}",
         info_hash, encoded_title
-    )
+    );
+
+    for tracker in trackers {
+        let tracker = tracker.as_ref().trim();
+        if !tracker.is_empty() {
+            magnet.push_str("&tr=");
+            magnet.push_str(&urlencoding::encode(tracker));
+        }
+    }
+
+    magnet
+}
+
+/// Extracts the hex info hash from a magnet URL, if it has one
+///
+/// Only 40-character hex hashes are accepted; base32 hashes are ignored.
+pub fn info_hash_from_magnet(magnet: &str) -> Option<String> {
+    let query = magnet.strip_prefix("magnet:?")?;
+    query
+        .split('&')
+        .filter_map(|param| param.strip_prefix("xt=urn:btih:"))
+        .find(|hash| hash.len() == 40 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+        .map(|hash| hash.to_lowercase())
 }
 
 /// Filters RSS items by video quality
@@ -627,12 +817,13 @@ pub fn filter_by_quality<'a>(items: &'a [RssItem], quality: &str) -> Vec<&'a Rss
 ///
 /// # Arguments
 /// * `item` - The RssItem to convert
+/// * `trackers` - Announce URLs for the magnet link
 ///
 /// # Returns
 /// Some(ParsedEpisode) if the title can be parsed, None otherwise
-pub fn rss_item_to_parsed_episode(item: &RssItem) -> Option<ParsedEpisode> {
+pub fn rss_item_to_parsed_episode<S: AsRef<str>>(item: &RssItem, trackers: &[S]) -> Option<ParsedEpisode> {
     let (show_title, episode, quality) = parse_episode_info(&item.title)?;
-    let magnet_url = construct_magnet_url(&item.info_hash, &item.title);
+    let magnet_url = construct_magnet_url(&item.info_hash, &item.title, trackers);
 
     Some(ParsedEpisode {
         show_title,
@@ -653,15 +844,17 @@ pub fn rss_item_to_parsed_episode(item: &RssItem) -> Option<ParsedEpisode> {
 /// * `source` - The uploader name
 /// * `alternate` - The search term / show name
 /// * `quality` - Optional quality filter (e.g., "1080p")
+/// * `trackers` - Announce URLs for the magnet links
 ///
 /// # Returns
 /// A vector of successfully parsed episodes
-pub async fn fetch_episodes(
+pub async fn fetch_episodes<S: AsRef<str>>(
     source: &str,
     alternate: &str,
     quality: Option<&str>,
+    trackers: &[S],
 ) -> Result<Vec<ParsedEpisode>> {
-    let items = fetch_rss_feed(source, alternate).await?;
+    let items = fetch_rss_feed(source, alternate, DEFAULT_CATEGORY).await?;
 
     let filtered_items: Vec<&RssItem> = if let Some(q) = quality {
         filter_by_quality(&items, q)
@@ -671,7 +864,7 @@ pub async fn fetch_episodes(
 
     let episodes: Vec<ParsedEpisode> = filtered_items
         .into_iter()
-        .filter_map(rss_item_to_parsed_episode)
+        .filter_map(|item| rss_item_to_parsed_episode(item, trackers))
         .collect();
 
     Ok(episodes)
@@ -860,11 +1053,60 @@ mod tests {
         let info_hash = "e30690d4a8d1f5e45f5ded430bdaedc710da0245";
         let title = "One Piece - 1060";
 
-        let magnet = construct_magnet_url(info_hash, title);
+        let magnet = construct_magnet_url::<&str>(info_hash, title, &[]);
 
         assert!(magnet.starts_with("magnet:?xt=urn:btih:"));
         assert!(magnet.contains(info_hash));
         assert!(magnet.contains("One%20Piece"));
+        assert!(!magnet.contains("&tr="));
+    }
+
+    #[test]
+    fn test_construct_magnet_url_with_trackers() {
+        let info_hash = "e30690d4a8d1f5e45f5ded430bdaedc710da0245";
+        let trackers = vec![
+            "http://nyaa.tracker.wf:7777/announce".to_string(),
+            "  ".to_string(),
+            "udp://open.stealth.si:80/announce".to_string(),
+        ];
+
+        let magnet = construct_magnet_url(info_hash, "Show - 01", &trackers);
+
+        assert_eq!(
+            magnet,
+            "magnet:?xt=urn:btih:e30690d4a8d1f5e45f5ded430bdaedc710da0245&dn=Show%20-%2001\
+             &tr=http%3A%2F%2Fnyaa.tracker.wf%3A7777%2Fannounce\
+             &tr=udp%3A%2F%2Fopen.stealth.si%3A80%2Fannounce"
+        );
+        assert_eq!(info_hash_from_magnet(&magnet).as_deref(), Some(info_hash));
+    }
+
+    #[test]
+    fn test_info_hash_from_magnet() {
+        let magnet = "magnet:?xt=urn:btih:E30690D4A8D1F5E45F5DED430BDAEDC710DA0245&dn=Show";
+        assert_eq!(
+            info_hash_from_magnet(magnet).as_deref(),
+            Some("e30690d4a8d1f5e45f5ded430bdaedc710da0245")
+        );
+        assert_eq!(info_hash_from_magnet("magnet:?dn=Show&xt=urn:btih:short"), None);
+        assert_eq!(info_hash_from_magnet("https://nyaa.si/view/1"), None);
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("1.2 GiB"), Some(1288490189));
+        assert_eq!(parse_size("500 MiB"), Some(524288000));
+        assert_eq!(parse_size("812 KiB"), Some(831488));
+        assert_eq!(parse_size("12 Bytes"), Some(12));
+        assert_eq!(parse_size("1 GB"), Some(1073741824));
+        assert_eq!(parse_size("not a size"), None);
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(12), "12 Bytes");
+        assert_eq!(format_size(524288000), "500.0 MiB");
+        assert_eq!(format_size(1288490189), "1.2 GiB");
     }
 
     #[test]
@@ -883,7 +1125,7 @@ mod tests {
     #[test]
     fn test_rss_item_to_parsed_episode() {
         let items = parse_rss_xml(SAMPLE_RSS).unwrap();
-        let episode = rss_item_to_parsed_episode(&items[0]);
+        let episode = rss_item_to_parsed_episode(&items[0], &["udp://tracker.opentrackr.org:1337/announce"]);
 
         assert!(episode.is_some());
         let ep = episode.unwrap();
@@ -894,11 +1136,42 @@ mod tests {
         assert!(ep.magnet_url.starts_with("magnet:?xt=urn:btih:"));
     }
 
+    #[test]
+    fn test_expand_query_template() {
+        assert_eq!(
+            expand_query_template("{group} {alternate} {quality} -batch -HEVC", "subsplease", "Frieren", "1080p"),
+            "subsplease Frieren 1080p -batch -HEVC"
+        );
+        assert_eq!(
+            expand_query_template("{group}  {alternate} (1080p|720p)", "", "Frieren", "1080p"),
+            "Frieren (1080p|720p)"
+        );
+    }
+
+    #[test]
+    fn test_nyaa_search_feed_url() {
+        let search = NyaaSearch::new("1_2");
+        assert_eq!(
+            search.feed_url("subsplease", "One Piece", "1080p"),
+            "https://nyaa.si/?page=rss&q=subsplease%20One%20Piece&c=1_2&f=0"
+        );
+
+        let search = NyaaSearch {
+            category: "1_3".to_string(),
+            query_template: Some("{group} {alternate} -batch".to_string()),
+            user_feed: true,
+        };
+        assert_eq!(
+            search.feed_url("Erai-raws", "One Piece", "1080p"),
+            "https://nyaa.si/?page=rss&q=One%20Piece%20-batch&c=1_3&f=0&u=Erai-raws"
+        );
+    }
+
     #[ignore]
     #[tokio::test]
     async fn test_fetch_rss_feed_live() {
         // Integration test - requires network access
-        let items = fetch_rss_feed("subsplease", "One Piece").await;
+        let items = fetch_rss_feed("subsplease", "One Piece", "1_2").await;
         assert!(items.is_ok());
         let items = items.unwrap();
         println!("Fetched {} items", items.len());
@@ -911,7 +1184,7 @@ mod tests {
     #[tokio::test]
     async fn test_fetch_episodes_live() {
         // Integration test - requires network access
-        let episodes = fetch_episodes("subsplease", "One Piece", Some("1080p")).await;
+        let episodes = fetch_episodes("subsplease", "One Piece", Some("1080p"), &["udp://tracker.opentrackr.org:1337/announce"]).await;
         assert!(episodes.is_ok());
         let episodes = episodes.unwrap();
         println!("Fetched {} episodes", episodes.len());
@@ -986,6 +1259,42 @@ mod tests {
         assert_eq!(RssSource::from_source_string(""), RssSource::Nyaa);
     }
 
+    #[test]
+    fn test_filter_items_by_show() {
+        let item = |title: &str| RssItem {
+            title: title.to_string(),
+            torrent_link: String::new(),
+            view_url: String::new(),
+            pub_date: String::new(),
+            info_hash: String::new(),
+            category_id: String::new(),
+            size: String::new(),
+            seeders: 0,
+            leechers: 0,
+            files: None,
+        };
+
+        let items = vec![
+            item("[SubsPlease] One Piece - 1122 (1080p) [ABCD1234].mkv"),
+            item("[SubsPlease] One Piece Fan Letter - 01 (1080p) [ABCD1234].mkv"),
+            item("[SubsPlease] Sousou no Frieren S2 - 03 (1080p) [ABCD1234].mkv"),
+            item("[SubsPlease] Dandadan - 05 (1080p) [ABCD1234].mkv"),
+        ];
+
+        let one_piece = filter_items_by_show(items.clone(), &["One Piece"]);
+        assert_eq!(one_piece.len(), 1);
+        assert!(one_piece[0].title.contains("1122"));
+
+        let frieren = filter_items_by_show(items.clone(), &["Sousou no Frieren – 2nd Season"]);
+        assert_eq!(frieren.len(), 1);
+        assert!(frieren[0].title.contains("Frieren"));
+
+        // Any alias may match
+        let aliased = filter_items_by_show(items, &["Frieren: Beyond Journey's End", "Dandadan"]);
+        assert_eq!(aliased.len(), 1);
+        assert!(aliased[0].title.contains("Dandadan"));
+    }
+
     #[ignore]
     #[tokio::test]
     async fn test_fetch_subsplease_rss_live() {
@@ -1003,8 +1312,14 @@ mod tests {
     #[tokio::test]
     async fn test_fetch_rss_by_source_subsplease_live() {
         // Integration test - requires network access
-        let items =
-            fetch_rss_by_source(RssSource::SubsPleaseDirect, "", "One Piece", "1080p").await;
+        let items = fetch_rss_by_source(
+            RssSource::SubsPleaseDirect,
+            "",
+            &["One Piece"],
+            "1080p",
+            &NyaaSearch::new(DEFAULT_CATEGORY),
+        )
+        .await;
         assert!(items.is_ok());
         let items = items.unwrap();
         println!("Fetched {} One Piece items from SubsPlease", items.len());
@@ -1017,7 +1332,20 @@ mod tests {
     fn test_detect_fansub_source_subsplease() {
         assert_eq!(
             detect_fansub_source("[SubsPlease] One Piece - 1060 (1080p) [37A98D45].mkv"),
-            "subsplease"
+            "SubsPlease"
+        );
+    }
+
+    #[test]
+    fn test_detect_fansub_source_alias() {
+        // Aliases resolve to the canonical group name
+        assert_eq!(
+            detect_fansub_source("[SP] One Piece - 1060 (1080p).mkv"),
+            "SubsPlease"
+        );
+        assert_eq!(
+            detect_fansub_source("[subsplease] One Piece - 1060 (1080p).mkv"),
+            "SubsPlease"
         );
     }
 
@@ -1033,7 +1361,7 @@ mod tests {
     fn test_detect_fansub_source_judas() {
         assert_eq!(
             detect_fansub_source("[Judas] Attack on Titan - The Final Season - 01.mkv"),
-            "judas"
+            "Judas"
         );
     }
 
@@ -1048,10 +1376,10 @@ mod tests {
 
     #[test]
     fn test_detect_fansub_source_no_brackets() {
-        // No brackets should default to subsplease
+        // No brackets should default to SubsPlease
         assert_eq!(
             detect_fansub_source("One Piece - 1060 (1080p).mkv"),
-            "subsplease"
+            "SubsPlease"
         );
     }
 }
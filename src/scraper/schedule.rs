@@ -0,0 +1,597 @@
+//! Tracker polling schedules: airing-aware polling, cron schedules and quiet hours
+//!
+//! Rather than searching every show on a fixed interval, each show is searched
+//! often in the hours after an episode airs, until the episode is downloaded, and
+//! rarely otherwise. Airing times come from AniList's `airingSchedule` and
+//! `nextAiringEpisode`. Shows without airing times, such as shows added by hand,
+//! keep the fixed `poll_times_per_day` interval.
+//!
+//! Once a show's group has released a few episodes, the first search after an
+//! episode airs waits for the group's median release delay, and searches keep
+//! going often until its 90th percentile.
+//!
+//! Two global caps keep the request rate down: the tracker never wakes more often
+//! than [`MIN_POLL_INTERVAL_SECS`], and one poll searches at most
+//! `max_shows_per_poll` shows, most overdue first.
+//!
+//! Cron schedules instead sync every show at fixed times. Expressions take the
+//! usual five fields (minute hour day month weekday), or six with seconds first.
+//! No schedule runs during quiet hours; runs that would are moved to their end.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveTime, TimeZone};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::anilist::{get_anilist_shows, get_last_aired_episodes, AiredEpisode, AniShow};
+use super::fansub::same_group;
+use crate::db::{
+    self,
+    models::{RssConfig, Show},
+    PollSchedule, ReleaseDelayStats,
+};
+
+/// Shortest time between two polls
+pub const MIN_POLL_INTERVAL_SECS: i64 = 5 * 60;
+
+/// How long after an episode airs a show is searched every `airing_poll_minutes`
+const HOT_WINDOW_SECS: i64 = 6 * 60 * 60;
+
+/// How long after an episode airs a show is still searched every [`WARM_INTERVAL_SECS`],
+/// for late releases
+const WARM_WINDOW_SECS: i64 = 48 * 60 * 60;
+
+const WARM_INTERVAL_SECS: i64 = 2 * 60 * 60;
+
+/// Interval for shows with airing times whose latest episode is downloaded;
+/// catches batches and fixed re-releases
+const IDLE_INTERVAL_SECS: i64 = 12 * 60 * 60;
+
+/// How often airing times are fetched from AniList again
+const AIRING_REFRESH: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Shortest time between two airing time refreshes, even when an episode just aired
+const MIN_AIRING_REFRESH: Duration = Duration::from_secs(30 * 60);
+
+static LAST_AIRING_REFRESH: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Twice a day at 5AM and 5PM, used when RSS polling is off
+pub const FALLBACK_SCHEDULE: &str = "0 5,17 * * *";
+
+/// Ready-made sync schedules, as (label, cron expression)
+pub const SCHEDULE_PRESETS: &[(&str, &str)] = &[
+    ("Twice a day (5AM/5PM)", FALLBACK_SCHEDULE),
+    ("Every 6 hours", "0 */6 * * *"),
+    ("Every 2 hours", "0 */2 * * *"),
+    ("Hourly", "0 * * * *"),
+];
+
+/// Runs looked at per cron schedule when listing upcoming runs, enough to get past
+/// runs that quiet hours fold together
+const CRON_LOOKAHEAD: usize = 64;
+
+/// Fixed interval from `poll_times_per_day`, used for shows without airing times
+fn fixed_interval(config: &RssConfig) -> i64 {
+    24 * 60 * 60 / config.poll_times_per_day.max(1) as i64
+}
+
+/// Interval right after an episode airs
+fn hot_interval(config: &RssConfig) -> i64 {
+    (config.airing_poll_minutes as i64 * 60).max(MIN_POLL_INTERVAL_SECS)
+}
+
+/// Release delay statistics of each show from the group it's tracked from
+pub fn expected_delays(
+    shows: &[Show],
+    stats: Vec<ReleaseDelayStats>,
+) -> HashMap<u32, ReleaseDelayStats> {
+    stats
+        .into_iter()
+        .filter(|s| {
+            shows
+                .iter()
+                .any(|show| show.id == s.show_id && same_group(&s.group, &show.source))
+        })
+        .map(|s| (s.show_id, s))
+        .collect()
+}
+
+/// How long to wait between searches for a show at `now`
+fn poll_interval(
+    show: &Show,
+    schedule: Option<&PollSchedule>,
+    delay: Option<&ReleaseDelayStats>,
+    now: i64,
+    config: &RssConfig,
+) -> i64 {
+    let Some(schedule) = schedule.filter(|s| s.last_aired_at.is_some() || s.next_airing_at.is_some())
+    else {
+        return fixed_interval(config);
+    };
+
+    let pending = schedule
+        .last_aired_episode
+        .is_none_or(|episode| show.last_downloaded_episode < episode);
+    // Late groups stay in the hot window until they've usually released
+    let hot_window = delay.map_or(HOT_WINDOW_SECS, |d| {
+        HOT_WINDOW_SECS.max(d.p90_secs + hot_interval(config))
+    });
+    match schedule.last_aired_at {
+        Some(aired) if pending && now - aired < hot_window => hot_interval(config),
+        Some(aired) if pending && now - aired < WARM_WINDOW_SECS => {
+            WARM_INTERVAL_SECS.min(fixed_interval(config))
+        }
+        _ => IDLE_INTERVAL_SECS.max(fixed_interval(config)),
+    }
+}
+
+/// When a show should next be searched for
+///
+/// Shows never searched are due right away. An upcoming episode moves the next
+/// search forward to when the group usually releases it, or one hot interval
+/// after it airs while that isn't known yet.
+pub fn next_poll_at(
+    show: &Show,
+    schedule: Option<&PollSchedule>,
+    delay: Option<&ReleaseDelayStats>,
+    now: i64,
+    config: &RssConfig,
+) -> i64 {
+    let Some(last_polled) = schedule.and_then(|s| s.last_polled_at) else {
+        return now;
+    };
+
+    let due = last_polled + poll_interval(show, schedule, delay, now, config);
+    let release_delay = delay.map_or(hot_interval(config), |d| d.p50_secs.max(0));
+    match schedule.and_then(|s| s.next_airing_at) {
+        Some(airing) if airing > last_polled => due.min(airing + release_delay),
+        _ => due,
+    }
+}
+
+/// Shows due for a search at `now`, most overdue first, at most `max_shows_per_poll`
+pub fn due_shows<'a>(
+    shows: &'a [Show],
+    schedules: &HashMap<u32, PollSchedule>,
+    delays: &HashMap<u32, ReleaseDelayStats>,
+    now: i64,
+    config: &RssConfig,
+) -> Vec<&'a Show> {
+    let mut due: Vec<(i64, &Show)> = shows
+        .iter()
+        .map(|show| {
+            let at = next_poll_at(show, schedules.get(&show.id), delays.get(&show.id), now, config);
+            (at, show)
+        })
+        .filter(|(at, _)| *at <= now)
+        .collect();
+    due.sort_by_key(|(at, _)| *at);
+    due.into_iter()
+        .take(config.max_shows_per_poll.max(1) as usize)
+        .map(|(_, show)| show)
+        .collect()
+}
+
+/// When the tracker should wake next
+///
+/// The earliest time a show is due, but no sooner than [`MIN_POLL_INTERVAL_SECS`]
+/// and no later than the fixed interval from now.
+pub fn next_wake(
+    shows: &[Show],
+    schedules: &HashMap<u32, PollSchedule>,
+    delays: &HashMap<u32, ReleaseDelayStats>,
+    now: i64,
+    config: &RssConfig,
+) -> i64 {
+    let latest = now + fixed_interval(config);
+    shows
+        .iter()
+        .map(|show| next_poll_at(show, schedules.get(&show.id), delays.get(&show.id), now, config))
+        .min()
+        .unwrap_or(latest)
+        .clamp(now + MIN_POLL_INTERVAL_SECS, latest)
+}
+
+/// Parse a cron expression, giving five-field expressions a zero seconds field
+pub fn parse_cron(expression: &str) -> Result<cron::Schedule> {
+    let expression = expression.trim();
+    let full = if expression.split_whitespace().count() == 5 {
+        format!("0 {}", expression)
+    } else {
+        expression.to_string()
+    };
+    cron::Schedule::from_str(&full)
+        .with_context(|| format!("Invalid cron expression '{}'", expression))
+}
+
+/// Quiet hours as start and end times of day, if both are set and valid
+pub fn quiet_hours(config: &RssConfig) -> Option<(NaiveTime, NaiveTime)> {
+    let parse = |time: &Option<String>| NaiveTime::parse_from_str(time.as_deref()?, "%H:%M").ok();
+    let (start, end) = (parse(&config.quiet_hours_start)?, parse(&config.quiet_hours_end)?);
+    (start != end).then_some((start, end))
+}
+
+/// End of the quiet hours if `at` falls in them; windows may span midnight
+pub fn quiet_hours_end(quiet: (NaiveTime, NaiveTime), at: DateTime<Local>) -> Option<DateTime<Local>> {
+    let (start, end) = quiet;
+    let time = at.time();
+    let inside = if start < end {
+        time >= start && time < end
+    } else {
+        time >= start || time < end
+    };
+    if !inside {
+        return None;
+    }
+
+    let date = if time < end {
+        at.date_naive()
+    } else {
+        at.date_naive().succ_opt()?
+    };
+    Local.from_local_datetime(&date.and_time(end)).earliest()
+}
+
+/// `at`, or the end of the quiet hours if it falls in them
+pub fn after_quiet_hours(quiet: Option<(NaiveTime, NaiveTime)>, at: DateTime<Local>) -> DateTime<Local> {
+    quiet.and_then(|quiet| quiet_hours_end(quiet, at)).unwrap_or(at)
+}
+
+/// The next `count` sync times of a set of cron expressions after `after`
+///
+/// Runs in quiet hours are moved to their end, and runs at the same time merged.
+/// Invalid expressions are skipped.
+pub fn upcoming_runs(
+    expressions: &[String],
+    quiet: Option<(NaiveTime, NaiveTime)>,
+    after: DateTime<Local>,
+    count: usize,
+) -> Vec<DateTime<Local>> {
+    let mut runs: Vec<DateTime<Local>> = expressions
+        .iter()
+        .filter_map(|expression| match parse_cron(expression) {
+            Ok(schedule) => Some(schedule),
+            Err(err) => {
+                tracing::warn!("Skipping sync schedule: {:?}", err);
+                None
+            }
+        })
+        .flat_map(|schedule| {
+            schedule
+                .after(&after)
+                .take(CRON_LOOKAHEAD)
+                .map(|run| after_quiet_hours(quiet, run))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    runs.sort();
+    runs.dedup();
+    runs.truncate(count);
+    runs
+}
+
+/// Latest aired episode and the next airing time of a show at `now`
+///
+/// Returns (last aired at, last aired episode, next airing at).
+fn airing_times(
+    show: &AniShow,
+    last_aired: Option<&AiredEpisode>,
+    now: i64,
+) -> (Option<i64>, Option<u16>, Option<i64>) {
+    let last = last_aired.filter(|e| e.airing_at.is_some_and(|at| at <= now));
+    let next = show
+        .airing_episodes()
+        .iter()
+        .filter_map(|e| e.airing_at)
+        .filter(|at| *at > now)
+        .min()
+        .or_else(|| {
+            show.next_airing_episode
+                .as_ref()
+                .and_then(|e| e.airing_at)
+                .filter(|at| *at > now)
+        });
+
+    (
+        last.and_then(|e| e.airing_at),
+        last.and_then(|e| e.episode),
+        next,
+    )
+}
+
+/// Whether airing times should be fetched again
+///
+/// They go stale after [`AIRING_REFRESH`], or as soon as a show's next episode airs.
+fn airing_refresh_due(schedules: &HashMap<u32, PollSchedule>, now: i64) -> bool {
+    let last = *LAST_AIRING_REFRESH.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(last) = last else {
+        return true;
+    };
+    let episode_aired = schedules
+        .values()
+        .any(|s| s.next_airing_at.is_some_and(|at| at <= now));
+
+    last.elapsed() >= AIRING_REFRESH || (episode_aired && last.elapsed() >= MIN_AIRING_REFRESH)
+}
+
+/// Fetch the airing times of tracked shows from AniList if they're stale
+pub async fn refresh_airing_times(shows: &[Show], schedules: &HashMap<u32, PollSchedule>) -> Result<()> {
+    let now = chrono::Utc::now().timestamp();
+    if !airing_refresh_due(schedules, now) {
+        return Ok(());
+    }
+
+    let ids: Vec<u32> = shows.iter().filter(|s| !s.is_manual()).map(|s| s.id).collect();
+    if !ids.is_empty() {
+        let media = get_anilist_shows(&ids).await?;
+        let last_aired = get_last_aired_episodes(&ids).await?;
+        db::with_db(move |conn| {
+            for show in &media {
+                let Some(id) = show.id else {
+                    continue;
+                };
+                let (last_aired_at, last_aired_episode, next_airing_at) =
+                    airing_times(show, last_aired.get(&id), now);
+                db::update_airing_times(conn, id, last_aired_at, last_aired_episode, next_airing_at)?;
+            }
+            Ok(())
+        })
+        .await?;
+        tracing::debug!("Refreshed airing times of {} show(s)", ids.len());
+    }
+
+    *LAST_AIRING_REFRESH.lock().unwrap_or_else(|e| e.into_inner()) = Some(Instant::now());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: i64 = 60 * 60;
+
+    fn show(last_downloaded_episode: u16) -> Show {
+        Show {
+            id: 1,
+            last_downloaded_episode,
+            ..Default::default()
+        }
+    }
+
+    fn schedule(last_aired_at: i64, episode: u16, next_airing_at: i64, last_polled_at: i64) -> PollSchedule {
+        PollSchedule {
+            show_id: 1,
+            last_aired_at: Some(last_aired_at),
+            last_aired_episode: Some(episode),
+            next_airing_at: Some(next_airing_at),
+            last_polled_at: Some(last_polled_at),
+        }
+    }
+
+    #[test]
+    fn test_poll_interval_follows_airing() {
+        let config = RssConfig::default();
+        let now = 100 * HOUR;
+
+        // Just aired and not downloaded: every airing_poll_minutes
+        let s = schedule(now - HOUR, 5, now + 167 * HOUR, now);
+        assert_eq!(poll_interval(&show(4), Some(&s), None, now, &config), 15 * 60);
+
+        // A day later and still missing: every couple of hours
+        let s = schedule(now - 24 * HOUR, 5, now + 144 * HOUR, now);
+        assert_eq!(poll_interval(&show(4), Some(&s), None, now, &config), 2 * HOUR);
+
+        // Downloaded: rarely
+        let s = schedule(now - HOUR, 5, now + 167 * HOUR, now);
+        assert_eq!(poll_interval(&show(5), Some(&s), None, now, &config), 12 * HOUR);
+
+        // No airing times: the fixed poll_times_per_day interval
+        assert_eq!(poll_interval(&show(0), None, None, now, &config), 6 * HOUR);
+    }
+
+    fn delay(p50_secs: i64, p90_secs: i64) -> ReleaseDelayStats {
+        ReleaseDelayStats {
+            show_id: 1,
+            title: String::new(),
+            group: "SubsPlease".to_string(),
+            samples: 5,
+            average_secs: p50_secs,
+            p50_secs,
+            p90_secs,
+        }
+    }
+
+    #[test]
+    fn test_release_delays_time_polls() {
+        let config = RssConfig::default();
+        let now = 100 * HOUR;
+
+        // The first search after air waits for the group's usual delay
+        let s = schedule(now - 160 * HOUR, 5, now + 2 * HOUR, now);
+        let d = delay(45 * 60, 2 * HOUR);
+        assert_eq!(next_poll_at(&show(5), Some(&s), Some(&d), now, &config), now + 2 * HOUR + 45 * 60);
+
+        // A group that's often late keeps the show in the hot window for longer
+        let s = schedule(now - 8 * HOUR, 6, now + 160 * HOUR, now);
+        let d = delay(5 * HOUR, 10 * HOUR);
+        assert_eq!(poll_interval(&show(5), Some(&s), Some(&d), now, &config), 15 * 60);
+        assert_eq!(poll_interval(&show(5), Some(&s), None, now, &config), 2 * HOUR);
+    }
+
+    #[test]
+    fn test_expected_delays_match_tracked_group() {
+        let shows = vec![Show {
+            id: 1,
+            source: "subsplease".to_string(),
+            ..Default::default()
+        }];
+        let other_group = ReleaseDelayStats {
+            group: "Erai-raws".to_string(),
+            ..delay(HOUR, HOUR)
+        };
+
+        let delays = expected_delays(&shows, vec![other_group, delay(45 * 60, HOUR)]);
+        assert_eq!(delays.len(), 1);
+        assert_eq!(delays[&1].p50_secs, 45 * 60);
+    }
+
+    #[test]
+    fn test_next_poll_at_upcoming_episode() {
+        let config = RssConfig::default();
+        let now = 100 * HOUR;
+
+        // The next episode airs before the idle interval is up
+        let s = schedule(now - 160 * HOUR, 5, now + 2 * HOUR, now);
+        assert_eq!(next_poll_at(&show(5), Some(&s), None, now, &config), now + 2 * HOUR + 15 * 60);
+
+        // Never searched: due right away
+        assert_eq!(next_poll_at(&show(5), None, None, now, &config), now);
+    }
+
+    #[test]
+    fn test_due_shows_capped_most_overdue_first() {
+        let config = RssConfig {
+            max_shows_per_poll: 2,
+            ..Default::default()
+        };
+        let now = 100 * HOUR;
+        let shows: Vec<Show> = (1..=4)
+            .map(|id| Show {
+                id,
+                ..Default::default()
+            })
+            .collect();
+        let schedules: HashMap<u32, PollSchedule> = [(1, 10 * HOUR), (2, 90 * HOUR), (3, 20 * HOUR), (4, now)]
+            .into_iter()
+            .map(|(id, polled)| {
+                (
+                    id,
+                    PollSchedule {
+                        show_id: id,
+                        last_polled_at: Some(polled),
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect();
+
+        let due: Vec<u32> = due_shows(&shows, &schedules, &HashMap::new(), now, &config)
+            .iter()
+            .map(|s| s.id)
+            .collect();
+        assert_eq!(due, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_next_wake_is_capped() {
+        let config = RssConfig::default();
+        let now = 100 * HOUR;
+        let shows = vec![show(0)];
+
+        // A show that's due wakes the tracker no sooner than the minimum interval
+        assert_eq!(
+            next_wake(&shows, &HashMap::new(), &HashMap::new(), now, &config),
+            now + MIN_POLL_INTERVAL_SECS
+        );
+        // Without shows the fixed interval applies
+        assert_eq!(next_wake(&[], &HashMap::new(), &HashMap::new(), now, &config), now + 6 * HOUR);
+    }
+
+    fn local(date: &str) -> DateTime<Local> {
+        let naive = chrono::NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M").unwrap();
+        Local.from_local_datetime(&naive).unwrap()
+    }
+
+    fn time(hm: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(hm, "%H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_parse_cron() {
+        assert!(parse_cron("0 5,17 * * *").is_ok());
+        assert!(parse_cron("30 0 */2 * * *").is_ok());
+        assert!(parse_cron("every day").is_err());
+        for (_, expression) in SCHEDULE_PRESETS {
+            assert!(parse_cron(expression).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_quiet_hours_end() {
+        // A window spanning midnight
+        let quiet = (time("23:00"), time("07:00"));
+        assert_eq!(quiet_hours_end(quiet, local("2024-10-05 23:30")), Some(local("2024-10-06 07:00")));
+        assert_eq!(quiet_hours_end(quiet, local("2024-10-06 03:00")), Some(local("2024-10-06 07:00")));
+        assert_eq!(quiet_hours_end(quiet, local("2024-10-06 07:00")), None);
+        assert_eq!(quiet_hours_end(quiet, local("2024-10-06 12:00")), None);
+
+        // A window within the day
+        let quiet = (time("09:00"), time("17:00"));
+        assert_eq!(quiet_hours_end(quiet, local("2024-10-06 10:00")), Some(local("2024-10-06 17:00")));
+        assert_eq!(quiet_hours_end(quiet, local("2024-10-06 08:59")), None);
+    }
+
+    #[test]
+    fn test_quiet_hours_need_both_ends() {
+        let config = RssConfig {
+            quiet_hours_start: Some("23:00".to_string()),
+            quiet_hours_end: Some("07:00".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(quiet_hours(&config), Some((time("23:00"), time("07:00"))));
+
+        let config = RssConfig {
+            quiet_hours_end: None,
+            ..config
+        };
+        assert_eq!(quiet_hours(&config), None);
+    }
+
+    #[test]
+    fn test_upcoming_runs() {
+        let after = local("2024-10-05 12:00");
+
+        let runs = upcoming_runs(&[FALLBACK_SCHEDULE.to_string()], None, after, 3);
+        assert_eq!(
+            runs,
+            vec![local("2024-10-05 17:00"), local("2024-10-06 05:00"), local("2024-10-06 17:00")]
+        );
+
+        // Runs from several schedules merge, and quiet hours fold night runs into one
+        let schedules = vec!["0 */6 * * *".to_string(), "0 12,18 * * *".to_string()];
+        let quiet = Some((time("23:00"), time("07:00")));
+        let runs = upcoming_runs(&schedules, quiet, after, 4);
+        assert_eq!(
+            runs,
+            vec![
+                local("2024-10-05 18:00"),
+                local("2024-10-06 07:00"),
+                local("2024-10-06 12:00"),
+                local("2024-10-06 18:00"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_airing_times() {
+        let show: AniShow = serde_json::from_str(
+            r#"{
+                "nextAiringEpisode": {"episode": 3, "airingAt": 3000},
+                "airingSchedule": {"nodes": [
+                    {"episode": 4, "airingAt": 4000},
+                    {"episode": 3, "airingAt": 3000}
+                ]}
+            }"#,
+        )
+        .unwrap();
+        let last_aired: AiredEpisode =
+            serde_json::from_str(r#"{"mediaId": 1, "episode": 2, "airingAt": 2000}"#).unwrap();
+
+        assert_eq!(airing_times(&show, Some(&last_aired), 2500), (Some(2000), Some(2), Some(3000)));
+        assert_eq!(airing_times(&show, None, 500), (None, None, Some(3000)));
+        // Once the schedule fetched earlier has gone by, nothing is upcoming
+        assert_eq!(airing_times(&show, Some(&last_aired), 4500), (Some(2000), Some(2), None));
+    }
+}
@@ -4,43 +4,136 @@
 //! - S1, S2, S01, S02 (prefix notation)
 //! - Season 1, Season 2, Saison 2 (spelled out)
 //! - 2nd Season, 3rd Season (ordinal format)
+//! - 第2期, 第二期 (Japanese season markers)
 //! - Part 2, Part II (part notation)
 //! - Cour 2 (anime-specific terminology)
 //! - II, III, IV (Roman numerals)
+//! - Zoku, Kan (sequel and conclusion suffixes)
 //! - Title 2, Title: 2 (trailing numbers)
+//! - Movie, OVA, ONA, Special (not a season; mapped to season 0)
+//! - Final Season, Title (2024) (flagged, but don't give a season number)
+//!
+//! Every pattern that matches becomes a candidate with a confidence, so callers can
+//! suggest the best guess and show the alternatives instead of silently assuming.
 //!
 //! Based on patterns from Sonarr, Anitomy, and common fansub conventions.
 
 use regex::Regex;
+use serde::Serialize;
+
+/// What kind of release a title refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SeasonKind {
+    /// A numbered TV season
+    Regular,
+    Movie,
+    Ova,
+    Ona,
+    Special,
+}
+
+/// A possible season for a title, from a single pattern
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SeasonCandidate {
+    /// Season number; 0 for movies, OVAs, ONAs and specials
+    pub season: u8,
+    pub kind: SeasonKind,
+    /// How sure the pattern is, 0.0-1.0
+    pub confidence: f64,
+    /// The text that matched
+    pub matched_pattern: String,
+    /// Title with the matched text removed
+    pub clean_title: String,
+}
 
 /// Result of season parsing
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct SeasonInfo {
     /// Detected season number (1 if not detected)
     pub season: u8,
+    /// Kind of the best candidate (Regular if not detected)
+    pub kind: SeasonKind,
+    /// Confidence of the best candidate (0.0 if not detected)
+    pub confidence: f64,
     /// The pattern that matched (for debugging)
     pub matched_pattern: Option<String>,
     /// Cleaned title with season indicator removed (optional)
     pub clean_title: Option<String>,
+    /// Year suffix, e.g. 2019 for "Fruits Basket (2019)"
+    pub year: Option<u16>,
+    /// Whether the title says it's the final season
+    pub final_season: bool,
+    /// Every candidate, best first; one per season and kind
+    pub candidates: Vec<SeasonCandidate>,
 }
 
 impl Default for SeasonInfo {
     fn default() -> Self {
         Self {
             season: 1,
+            kind: SeasonKind::Regular,
+            confidence: 0.0,
             matched_pattern: None,
             clean_title: None,
+            year: None,
+            final_season: false,
+            candidates: Vec::new(),
         }
     }
 }
 
+/// Confidence below which a detected season is only a guess
+pub const CONFIDENT_SEASON: f64 = 0.6;
+
+impl SeasonInfo {
+    /// Whether the season came from a reliable pattern rather than a guess or default
+    pub fn is_confident(&self) -> bool {
+        self.confidence >= CONFIDENT_SEASON
+    }
+
+    /// Whether the title has season markers, but none reliable enough to go by
+    ///
+    /// Such titles should get a season suggestion rather than an assumed season.
+    pub fn is_guess(&self) -> bool {
+        (!self.candidates.is_empty() || self.final_season) && !self.is_confident()
+    }
+
+    /// Short description for display, e.g. "Season 2", "Movie" or "Final Season"
+    pub fn label(&self) -> String {
+        match self.kind {
+            SeasonKind::Regular if self.final_season && !self.is_confident() => {
+                "Final Season".to_string()
+            }
+            SeasonKind::Regular => format!("Season {}", self.season),
+            SeasonKind::Movie => "Movie".to_string(),
+            SeasonKind::Ova => "OVA".to_string(),
+            SeasonKind::Ona => "ONA".to_string(),
+            SeasonKind::Special => "Special".to_string(),
+        }
+    }
+
+    /// Every candidate with its confidence, e.g. "S2 95%, Part 2 60%"
+    pub fn summary(&self) -> String {
+        if self.candidates.is_empty() {
+            return "No season marker, assuming season 1".to_string();
+        }
+        self.candidates
+            .iter()
+            .map(|c| format!("{} {}%", c.matched_pattern, (c.confidence * 100.0).round()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
 /// Detects the season number from an anime title
 ///
 /// # Arguments
 /// * `title` - The anime title to parse
 ///
 /// # Returns
-/// A `SeasonInfo` struct containing the detected season and metadata
+/// A `SeasonInfo` struct containing the best candidate, every candidate ranked by
+/// confidence, and metadata
 ///
 /// # Examples
 /// ```ignore
@@ -51,228 +144,313 @@ impl Default for SeasonInfo {
 /// assert_eq!(info.season, 2);
 /// ```
 pub fn detect_season(title: &str) -> SeasonInfo {
-    // Try each pattern in order of specificity
-
-    // Pattern 1: S## format (S1, S2, S01, S02, etc.)
-    // Matches: "Title S2", "Title S02", "[Group] Title S2"
-    if let Some(info) = detect_s_prefix(title) {
-        return info;
-    }
-
-    // Pattern 2: "Season ##" or "Saison ##" (spelled out)
-    // Matches: "Title Season 2", "Title: Season 2"
-    if let Some(info) = detect_spelled_season(title) {
-        return info;
-    }
-
-    // Pattern 3: Ordinal format (2nd Season, 3rd Season, etc.)
-    // Matches: "Title 2nd Season", "Title: 3rd Season"
-    if let Some(info) = detect_ordinal_season(title) {
-        return info;
+    let year = detect_year(title);
+    let final_season = detect_final_season(title);
+
+    // Years ("Title (2024)") would otherwise look like trailing numbers
+    let without_year = match year {
+        Some((_, ref matched)) => title.replace(matched.as_str(), "").trim().to_string(),
+        None => title.to_string(),
+    };
+    let title = without_year.as_str();
+
+    let detectors: [fn(&str) -> Option<SeasonCandidate>; 12] = [
+        // S## format (S1, S2, S01, S02, etc.)
+        detect_s_prefix,
+        // "Season ##" or "Saison ##" (spelled out)
+        detect_spelled_season,
+        // Ordinal format (2nd Season, 3rd Season, etc.)
+        detect_ordinal_season,
+        // Japanese markers (第2期, 第二期)
+        detect_japanese_season,
+        // Movie, OVA/OAD, ONA and Special releases
+        detect_movie,
+        detect_ova,
+        detect_special,
+        // Part notation (Part 2, Part II)
+        detect_part,
+        // Cour notation (Cour 2)
+        detect_cour,
+        // Roman numerals at end (II, III, IV, etc.)
+        detect_roman_numeral,
+        // Zoku / Kan suffixes
+        detect_sequel_suffix,
+        // Trailing number (Title 2, Title: 2); the least specific
+        detect_trailing_number,
+    ];
+
+    let mut candidates: Vec<SeasonCandidate> = Vec::new();
+    for candidate in detectors.iter().filter_map(|detect| detect(title)) {
+        // Keep the most confident candidate per season and kind
+        match candidates
+            .iter_mut()
+            .find(|c| c.season == candidate.season && c.kind == candidate.kind)
+        {
+            Some(existing) if existing.confidence >= candidate.confidence => {}
+            Some(existing) => *existing = candidate,
+            None => candidates.push(candidate),
+        }
     }
 
-    // Pattern 4: Part notation (Part 2, Part II)
-    // Matches: "Title Part 2", "Title: Part II"
-    if let Some(info) = detect_part(title) {
-        return info;
+    // A year suffix alone usually marks a remake, so it's a (weak) first season
+    if let Some((year, _)) = year
+        && candidates.is_empty()
+    {
+        candidates.push(SeasonCandidate {
+            season: 1,
+            kind: SeasonKind::Regular,
+            confidence: 0.3,
+            matched_pattern: format!("year {}", year),
+            clean_title: title.to_string(),
+        });
     }
 
-    // Pattern 5: Cour notation (Cour 2)
-    // Matches: "Title Cour 2", "Title: Cour 2"
-    if let Some(info) = detect_cour(title) {
-        return info;
-    }
+    // Stable sort keeps pattern order among equal confidences
+    candidates.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+
+    let mut info = match candidates.first() {
+        Some(best) => SeasonInfo {
+            season: best.season,
+            kind: best.kind,
+            confidence: best.confidence,
+            matched_pattern: Some(best.matched_pattern.clone()),
+            clean_title: Some(best.clean_title.clone()),
+            ..SeasonInfo::default()
+        },
+        // No season detected, default to 1
+        None => SeasonInfo::default(),
+    };
+    info.year = year.map(|(year, _)| year);
+    info.final_season = final_season;
+    info.candidates = candidates;
+    info
+}
 
-    // Pattern 6: Roman numerals at end (II, III, IV, etc.)
-    // Matches: "Title II", "Title III"
-    if let Some(info) = detect_roman_numeral(title) {
-        return info;
+/// Builds a candidate whose matched text is removed from the title
+fn candidate(
+    title: &str,
+    matched: &str,
+    season: u8,
+    kind: SeasonKind,
+    confidence: f64,
+) -> SeasonCandidate {
+    SeasonCandidate {
+        season,
+        kind,
+        confidence,
+        matched_pattern: matched.trim().to_string(),
+        clean_title: title.replace(matched, "").trim().to_string(),
     }
+}
 
-    // Pattern 7: Trailing number (Title 2, Title: 2)
-    // Matches: "Oregairu 2", "SAO 3"
-    // This is the least specific, so we're more careful
-    if let Some(info) = detect_trailing_number(title) {
-        return info;
-    }
+/// Detects a year suffix, returning the year and the matched text
+fn detect_year(title: &str) -> Option<(u16, String)> {
+    let re = Regex::new(r"\s*[(\[]((?:19|20)\d{2})[)\]]\s*$").ok()?;
+    let caps = re.captures(title)?;
+    Some((caps.get(1)?.as_str().parse().ok()?, caps.get(0)?.as_str().to_string()))
+}
 
-    // No season detected, default to 1
-    SeasonInfo::default()
+/// Detects "Final Season" and the like, which don't say which season they are
+fn detect_final_season(title: &str) -> bool {
+    Regex::new(r"(?i)\b(?:The\s+)?Final\s+(?:Season|Series|Chapters?)\b")
+        .map(|re| re.is_match(title))
+        .unwrap_or(false)
 }
 
 /// Detects S## prefix format
-fn detect_s_prefix(title: &str) -> Option<SeasonInfo> {
+fn detect_s_prefix(title: &str) -> Option<SeasonCandidate> {
     // Case insensitive match for S followed by 1-2 digits
-    // Negative lookbehind to avoid matching in the middle of words
+    // Word boundaries avoid matching in the middle of words
     let re = Regex::new(r"(?i)\bS(\d{1,2})\b").ok()?;
 
-    if let Some(caps) = re.captures(title) {
-        let season_str = caps.get(1)?.as_str();
-        let season: u8 = season_str.parse().ok()?;
-
-        // Clean the title by removing the season marker
-        let clean = re.replace(title, "").trim().to_string();
+    let caps = re.captures(title)?;
+    let season_str = caps.get(1)?.as_str();
+    let season: u8 = season_str.parse().ok()?;
 
-        return Some(SeasonInfo {
-            season,
-            matched_pattern: Some(format!("S{}", season_str)),
-            clean_title: Some(clean),
-        });
-    }
+    // Clean the title by removing the season marker
+    let clean = re.replace(title, "").trim().to_string();
 
-    None
+    Some(SeasonCandidate {
+        season,
+        kind: SeasonKind::Regular,
+        confidence: 0.95,
+        matched_pattern: format!("S{}", season_str),
+        clean_title: clean,
+    })
 }
 
 /// Detects spelled out "Season ##" or "Saison ##"
-fn detect_spelled_season(title: &str) -> Option<SeasonInfo> {
+fn detect_spelled_season(title: &str) -> Option<SeasonCandidate> {
     // Matches Season, Saison, Series, Stagione (Italian)
     let re = Regex::new(r"(?i)\b(Season|Saison|Series|Stagione)[-_.\s]?(\d{1,2})\b").ok()?;
 
-    if let Some(caps) = re.captures(title) {
-        let matched = caps.get(0)?.as_str();
-        let season: u8 = caps.get(2)?.as_str().parse().ok()?;
-
-        let clean = title.replace(matched, "").trim().to_string();
-
-        return Some(SeasonInfo {
-            season,
-            matched_pattern: Some(matched.to_string()),
-            clean_title: Some(clean),
-        });
-    }
-
-    None
+    let caps = re.captures(title)?;
+    let season: u8 = caps.get(2)?.as_str().parse().ok()?;
+    Some(candidate(title, caps.get(0)?.as_str(), season, SeasonKind::Regular, 0.95))
 }
 
 /// Detects ordinal format (2nd Season, 3rd Season)
-fn detect_ordinal_season(title: &str) -> Option<SeasonInfo> {
+fn detect_ordinal_season(title: &str) -> Option<SeasonCandidate> {
     // Matches: 1st, 2nd, 3rd, 4th, etc. followed by Season
     let re = Regex::new(r"(?i)\b(\d{1,2})(st|nd|rd|th)\s*(Season|Cour)?\b").ok()?;
 
-    if let Some(caps) = re.captures(title) {
-        let matched = caps.get(0)?.as_str();
-        let season: u8 = caps.get(1)?.as_str().parse().ok()?;
+    let caps = re.captures(title)?;
+    let season: u8 = caps.get(1)?.as_str().parse().ok()?;
+    // A bare ordinal ("2nd") is less certain than "2nd Season"
+    let confidence = if caps.get(3).is_some() { 0.9 } else { 0.7 };
+    Some(candidate(title, caps.get(0)?.as_str(), season, SeasonKind::Regular, confidence))
+}
 
-        let clean = title.replace(matched, "").trim().to_string();
+/// Detects Japanese season markers (第2期, 第二期, 第2シーズン)
+fn detect_japanese_season(title: &str) -> Option<SeasonCandidate> {
+    let re = Regex::new(r"第\s*([0-9０-９]{1,2}|[一二三四五六七八九十]{1,2})\s*(?:期|季|シーズン)").ok()?;
 
-        return Some(SeasonInfo {
-            season,
-            matched_pattern: Some(matched.to_string()),
-            clean_title: Some(clean),
-        });
-    }
+    let caps = re.captures(title)?;
+    let season = japanese_number(caps.get(1)?.as_str())?;
+    Some(candidate(title, caps.get(0)?.as_str(), season, SeasonKind::Regular, 0.9))
+}
+
+/// Detects movies (Movie, Gekijouban, 劇場版)
+fn detect_movie(title: &str) -> Option<SeasonCandidate> {
+    let re = Regex::new(r"(?i)(?:\b(?:The\s+)?Movie\b|\bGekijou?ban\b|劇場版)").ok()?;
+
+    let matched = re.find(title)?.as_str();
+    Some(candidate(title, matched, 0, SeasonKind::Movie, 0.85))
+}
 
-    None
+/// Detects OVA/OAD and ONA releases
+fn detect_ova(title: &str) -> Option<SeasonCandidate> {
+    let re = Regex::new(r"(?i)\b(OVA|OAD|ONA)s?\b").ok()?;
+
+    let caps = re.captures(title)?;
+    let kind = if caps.get(1)?.as_str().eq_ignore_ascii_case("ONA") {
+        SeasonKind::Ona
+    } else {
+        SeasonKind::Ova
+    };
+    Some(candidate(title, caps.get(0)?.as_str(), 0, kind, 0.85))
+}
+
+/// Detects specials
+fn detect_special(title: &str) -> Option<SeasonCandidate> {
+    // Only at the end, since "Special" is also an ordinary word in titles ("Special A")
+    let re = Regex::new(r"(?i)[\s:\-(\[]+(Specials?|SP)(?:\s*\d{1,2})?[)\]]?\s*$").ok()?;
+
+    let matched = re.find(title)?.as_str();
+    Some(candidate(title, matched, 0, SeasonKind::Special, 0.8))
 }
 
 /// Detects Part notation
-fn detect_part(title: &str) -> Option<SeasonInfo> {
+fn detect_part(title: &str) -> Option<SeasonCandidate> {
+    // Parts often split a single season, so these are weaker than season markers
+
     // First try Part + Arabic numeral
     let re_arabic = Regex::new(r"(?i)\bPart[-_.\s]?(\d{1,2})\b").ok()?;
 
     if let Some(caps) = re_arabic.captures(title) {
-        let matched = caps.get(0)?.as_str();
         let season: u8 = caps.get(1)?.as_str().parse().ok()?;
-
-        let clean = title.replace(matched, "").trim().to_string();
-
-        return Some(SeasonInfo {
-            season,
-            matched_pattern: Some(matched.to_string()),
-            clean_title: Some(clean),
-        });
+        return Some(candidate(title, caps.get(0)?.as_str(), season, SeasonKind::Regular, 0.6));
     }
 
     // Try Part + Roman numeral
     let re_roman = Regex::new(r"(?i)\bPart[-_.\s]?(I{1,3}|IV|VI{0,3}|IX|X)\b").ok()?;
 
-    if let Some(caps) = re_roman.captures(title) {
-        let matched = caps.get(0)?.as_str();
-        let roman = caps.get(1)?.as_str();
-        let season = roman_to_arabic(roman)?;
-
-        let clean = title.replace(matched, "").trim().to_string();
-
-        return Some(SeasonInfo {
-            season,
-            matched_pattern: Some(matched.to_string()),
-            clean_title: Some(clean),
-        });
-    }
-
-    None
+    let caps = re_roman.captures(title)?;
+    let season = roman_to_arabic(caps.get(1)?.as_str())?;
+    Some(candidate(title, caps.get(0)?.as_str(), season, SeasonKind::Regular, 0.6))
 }
 
 /// Detects Cour notation (anime-specific)
-fn detect_cour(title: &str) -> Option<SeasonInfo> {
+fn detect_cour(title: &str) -> Option<SeasonCandidate> {
     let re = Regex::new(r"(?i)\bCour[-_.\s]?(\d{1,2})\b").ok()?;
 
-    if let Some(caps) = re.captures(title) {
-        let matched = caps.get(0)?.as_str();
-        let season: u8 = caps.get(1)?.as_str().parse().ok()?;
-
-        let clean = title.replace(matched, "").trim().to_string();
-
-        return Some(SeasonInfo {
-            season,
-            matched_pattern: Some(matched.to_string()),
-            clean_title: Some(clean),
-        });
-    }
-
-    None
+    let caps = re.captures(title)?;
+    let season: u8 = caps.get(1)?.as_str().parse().ok()?;
+    Some(candidate(title, caps.get(0)?.as_str(), season, SeasonKind::Regular, 0.6))
 }
 
 /// Detects Roman numerals at end of title
-fn detect_roman_numeral(title: &str) -> Option<SeasonInfo> {
+fn detect_roman_numeral(title: &str) -> Option<SeasonCandidate> {
     // Only match at word boundary, typically end of title
     // Matches II, III, IV, V, VI, VII, VIII, IX, X
     let re = Regex::new(r"\b(X{0,1}(?:IX|IV|V?I{1,3}))\s*$").ok()?;
 
-    if let Some(caps) = re.captures(title) {
-        let matched = caps.get(1)?.as_str();
-        let season = roman_to_arabic(matched)?;
+    let caps = re.captures(title)?;
+    let matched = caps.get(1)?.as_str();
+    let season = roman_to_arabic(matched)?;
 
-        // Only consider valid if season > 1 (single "I" is often part of title)
-        if season > 1 {
-            let clean = re.replace(title, "").trim().to_string();
+    // Only consider valid if season > 1 (single "I" is often part of title)
+    if season <= 1 {
+        return None;
+    }
 
-            return Some(SeasonInfo {
-                season,
-                matched_pattern: Some(matched.to_string()),
-                clean_title: Some(clean),
-            });
-        }
+    Some(SeasonCandidate {
+        season,
+        kind: SeasonKind::Regular,
+        confidence: 0.7,
+        matched_pattern: matched.to_string(),
+        clean_title: re.replace(title, "").trim().to_string(),
+    })
+}
+
+/// Detects "Zoku" (continuation, season 2) and "Kan" (conclusion, usually season 3)
+fn detect_sequel_suffix(title: &str) -> Option<SeasonCandidate> {
+    let zoku = Regex::new(r"(?i)(?:^Zoku\b|\bZoku(?:[-\s]?Hen)?\s*$)").ok()?;
+    if let Some(m) = zoku.find(title) {
+        return Some(candidate(title, m.as_str(), 2, SeasonKind::Regular, 0.7));
     }
 
-    None
+    let kan = Regex::new(r"(?i)\bKan\s*$").ok()?;
+    let m = kan.find(title)?;
+    Some(candidate(title, m.as_str(), 3, SeasonKind::Regular, 0.5))
 }
 
 /// Detects trailing number (least specific pattern)
-fn detect_trailing_number(title: &str) -> Option<SeasonInfo> {
+fn detect_trailing_number(title: &str) -> Option<SeasonCandidate> {
     // Match number at end, with optional colon, dash, or space separator
     // Be careful not to match years (4 digits) or episode numbers
     // Patterns: "Title 2", "Title: 2", "Title - 2"
     let re = Regex::new(r"(?:[-:]\s*|\s+)(\d)\s*$").ok()?;
 
-    if let Some(caps) = re.captures(title) {
-        let season_str = caps.get(1)?.as_str();
-        let season: u8 = season_str.parse().ok()?;
+    let caps = re.captures(title)?;
+    let season: u8 = caps.get(1)?.as_str().parse().ok()?;
+
+    // Sanity check: season should be reasonable (1-9, single digit only for trailing)
+    if !(1..=9).contains(&season) {
+        return None;
+    }
 
-        // Sanity check: season should be reasonable (1-9, single digit only for trailing)
-        if season >= 1 && season <= 9 {
-            let matched = caps.get(0)?.as_str();
-            let clean = title.replace(matched, "").trim().to_string();
+    Some(SeasonCandidate {
+        season,
+        kind: SeasonKind::Regular,
+        confidence: 0.4,
+        matched_pattern: format!("trailing {}", season),
+        clean_title: title.replace(caps.get(0)?.as_str(), "").trim().to_string(),
+    })
+}
 
-            return Some(SeasonInfo {
-                season,
-                matched_pattern: Some(format!("trailing {}", season)),
-                clean_title: Some(clean),
-            });
-        }
+/// Converts a number written with (full-width) digits or kanji, up to 99
+fn japanese_number(text: &str) -> Option<u8> {
+    let digits: String = text
+        .chars()
+        .map(|c| match c {
+            '０'..='９' => char::from_u32(c as u32 - '０' as u32 + '0' as u32).unwrap_or(c),
+            _ => c,
+        })
+        .collect();
+    if let Ok(n) = digits.parse() {
+        return Some(n);
     }
 
-    None
+    let kanji = |c: char| "一二三四五六七八九".chars().position(|k| k == c).map(|i| i as u8 + 1);
+    let chars: Vec<char> = text.chars().collect();
+    match chars.as_slice() {
+        ['十'] => Some(10),
+        ['十', one] => Some(10 + kanji(*one)?),
+        [ten, '十'] => Some(kanji(*ten)? * 10),
+        [one] => kanji(*one),
+        _ => None,
+    }
 }
 
 /// Converts Roman numeral to Arabic number
@@ -379,4 +557,83 @@ mod tests {
         // Single digit at end should match
         assert_eq!(detect_season("Title 3").season, 3);
     }
+
+    #[test]
+    fn test_candidates_ranked_by_confidence() {
+        let info = detect_season("Attack on Titan Season 3 Part 2");
+        assert_eq!(info.season, 3);
+        assert!(info.is_confident());
+        assert_eq!(info.candidates.len(), 2);
+        assert_eq!(info.candidates[1].season, 2);
+        assert!(info.candidates[0].confidence > info.candidates[1].confidence);
+
+        // Guesses and defaults aren't confident, but only guesses need a suggestion
+        let info = detect_season("Oregairu 2");
+        assert!(!info.is_confident());
+        assert!(info.is_guess());
+        let info = detect_season("One Punch Man");
+        assert_eq!(info.confidence, 0.0);
+        assert!(info.candidates.is_empty());
+        assert!(!info.is_guess());
+    }
+
+    #[test]
+    fn test_year_suffix() {
+        let info = detect_season("Fruits Basket (2019)");
+        assert_eq!(info.season, 1);
+        assert_eq!(info.year, Some(2019));
+        assert_eq!(info.clean_title, Some("Fruits Basket".to_string()));
+
+        let info = detect_season("Hunter x Hunter (2011) 2nd Season");
+        assert_eq!(info.year, None);
+        assert_eq!(info.season, 2);
+
+        let info = detect_season("Urusei Yatsura (2022) S2 (2024)");
+        assert_eq!(info.year, Some(2024));
+        assert_eq!(info.season, 2);
+    }
+
+    #[test]
+    fn test_final_season() {
+        let info = detect_season("Shingeki no Kyojin: The Final Season");
+        assert!(info.final_season);
+        assert!(!info.is_confident());
+        assert_eq!(info.label(), "Final Season");
+
+        let info = detect_season("Shingeki no Kyojin: The Final Season Part 2");
+        assert!(info.final_season);
+        assert_eq!(info.season, 2);
+    }
+
+    #[test]
+    fn test_special_types() {
+        let info = detect_season("Kimetsu no Yaiba Movie: Mugen Ressha-hen");
+        assert_eq!((info.season, info.kind), (0, SeasonKind::Movie));
+        assert_eq!(info.label(), "Movie");
+
+        assert_eq!(detect_season("Given OVA").kind, SeasonKind::Ova);
+        assert_eq!(detect_season("Spriggan ONA").kind, SeasonKind::Ona);
+        assert_eq!(detect_season("Bocchi the Rock! Special").kind, SeasonKind::Special);
+        assert_eq!(detect_season("劇場版 呪術廻戦 0").kind, SeasonKind::Movie);
+
+        // "Special" inside a title isn't a special
+        assert_eq!(detect_season("Special A").kind, SeasonKind::Regular);
+    }
+
+    #[test]
+    fn test_zoku_kan() {
+        assert_eq!(detect_season("Natsume Yuujinchou Zoku").season, 2);
+        assert_eq!(detect_season("Zoku Owarimonogatari").season, 2);
+        assert_eq!(detect_season("Yahari Ore no Seishun Love Comedy wa Machigatteiru. Kan").season, 3);
+        assert_eq!(detect_season("Kantai Collection").season, 1);
+    }
+
+    #[test]
+    fn test_japanese_season() {
+        assert_eq!(detect_season("葬送のフリーレン 第2期").season, 2);
+        assert_eq!(detect_season("進撃の巨人 第三期").season, 3);
+        assert_eq!(detect_season("ダンまち 第５期").season, 5);
+        assert_eq!(detect_season("銀魂 第十二期").season, 12);
+        assert_eq!(detect_season("ゆるキャン△ 第3シーズン").season, 3);
+    }
 }
@@ -0,0 +1,290 @@
+//! Sync jobs
+//!
+//! Syncs run as background jobs, one at a time. Starting a manual sync while one
+//! is running joins it instead of starting another, and a scheduled sync that
+//! comes up meanwhile is skipped, so no release is grabbed twice. Jobs report
+//! their progress show by show and can be cancelled between shows.
+
+use anyhow::Result;
+use chrono::Utc;
+use serde::Serialize;
+use std::sync::{
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    Arc, Mutex,
+};
+
+use super::tracker::{self, SyncResult};
+
+pub type JobId = u64;
+
+/// What started a sync
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum SyncTrigger {
+    Manual,
+    Scheduled,
+}
+
+/// Where a sync job is at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum JobState {
+    Running,
+    Finished,
+    Cancelled,
+    Failed,
+}
+
+/// Which shows a sync searches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncPlan {
+    /// Every tracked show
+    AllShows,
+    /// Tracked shows due for a search by the airing schedule
+    DueShows,
+}
+
+/// Snapshot of a sync job's progress
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncProgress {
+    pub id: JobId,
+    pub trigger: SyncTrigger,
+    pub state: JobState,
+    /// Shows the sync searches
+    pub total: usize,
+    /// Shows searched so far
+    pub done: usize,
+    /// Show being searched
+    pub current: Option<String>,
+    pub episodes_downloaded: u32,
+    /// Unix timestamp the sync started at
+    pub started_at: i64,
+    /// Unix timestamp the sync stopped at
+    pub finished_at: Option<i64>,
+    /// Outcome once the sync has finished or was cancelled
+    pub result: Option<SyncResult>,
+    /// Why the sync failed
+    pub error: Option<String>,
+}
+
+impl SyncProgress {
+    pub fn is_running(&self) -> bool {
+        self.state == JobState::Running
+    }
+}
+
+/// A sync job, through which the sync reports progress and sees cancellation
+#[derive(Debug)]
+pub struct SyncJob {
+    progress: Mutex<SyncProgress>,
+    cancelled: AtomicBool,
+}
+
+impl SyncJob {
+    fn new(id: JobId, trigger: SyncTrigger) -> Self {
+        Self {
+            progress: Mutex::new(SyncProgress {
+                id,
+                trigger,
+                state: JobState::Running,
+                total: 0,
+                done: 0,
+                current: None,
+                episodes_downloaded: 0,
+                started_at: Utc::now().timestamp(),
+                finished_at: None,
+                result: None,
+                error: None,
+            }),
+            cancelled: AtomicBool::new(false),
+        }
+    }
+
+    fn update(&self, f: impl FnOnce(&mut SyncProgress)) {
+        f(&mut self.progress.lock().unwrap_or_else(|e| e.into_inner()));
+    }
+
+    pub fn progress(&self) -> SyncProgress {
+        self.progress.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Number of shows the sync will search
+    pub fn set_total(&self, total: usize) {
+        self.update(|p| p.total = total);
+    }
+
+    /// A show is being searched
+    pub fn start_show(&self, title: &str) {
+        self.update(|p| p.current = Some(title.to_string()));
+    }
+
+    /// A show has been searched, downloading `downloaded` episodes
+    pub fn finish_show(&self, downloaded: u32) {
+        self.update(|p| {
+            p.done += 1;
+            p.episodes_downloaded += downloaded;
+            p.current = None;
+        });
+    }
+
+    /// Record how the sync ended
+    fn finish(&self, outcome: &Result<SyncResult>) {
+        let cancelled = self.is_cancelled();
+        self.update(|p| {
+            p.current = None;
+            p.finished_at = Some(Utc::now().timestamp());
+            match outcome {
+                Ok(result) => {
+                    p.state = if cancelled {
+                        JobState::Cancelled
+                    } else {
+                        JobState::Finished
+                    };
+                    p.result = Some(result.clone());
+                }
+                Err(err) => {
+                    p.state = JobState::Failed;
+                    p.error = Some(err.to_string());
+                }
+            }
+        });
+    }
+}
+
+/// The running job and the last one to finish
+struct Jobs {
+    running: Option<Arc<SyncJob>>,
+    last: Option<Arc<SyncJob>>,
+}
+
+static JOBS: Mutex<Jobs> = Mutex::new(Jobs {
+    running: None,
+    last: None,
+});
+
+static NEXT_JOB_ID: AtomicU64 = AtomicU64::new(1);
+
+fn jobs() -> std::sync::MutexGuard<'static, Jobs> {
+    JOBS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Start a job unless one is running, in which case that one is returned as the error
+fn begin(trigger: SyncTrigger) -> std::result::Result<Arc<SyncJob>, Arc<SyncJob>> {
+    let mut jobs = jobs();
+    if let Some(running) = &jobs.running {
+        return Err(running.clone());
+    }
+
+    let job = Arc::new(SyncJob::new(NEXT_JOB_ID.fetch_add(1, Ordering::Relaxed), trigger));
+    jobs.running = Some(job.clone());
+    Ok(job)
+}
+
+/// Releases the lock when a job stops, even if the sync panicked
+struct Release(Arc<SyncJob>);
+
+impl Drop for Release {
+    fn drop(&mut self) {
+        if self.0.progress().is_running() {
+            self.0.finish(&Err(anyhow::anyhow!("Sync stopped unexpectedly")));
+        }
+
+        let mut jobs = jobs();
+        jobs.running = None;
+        jobs.last = Some(self.0.clone());
+    }
+}
+
+/// Run a started job to the end and release the lock
+async fn run(job: Arc<SyncJob>, plan: SyncPlan) -> Result<SyncResult> {
+    let release = Release(job);
+    let outcome = tracker::run_sync(plan, &release.0).await;
+    release.0.finish(&outcome);
+    outcome
+}
+
+/// Sync all tracked shows in the background, or join the sync already running
+pub fn start_manual_sync() -> Arc<SyncJob> {
+    match begin(SyncTrigger::Manual) {
+        Ok(job) => {
+            let running = job.clone();
+            tokio::spawn(async move {
+                if let Err(e) = run(running, SyncPlan::AllShows).await {
+                    tracing::error!("Sync failed: {:?}", e);
+                }
+            });
+            job
+        }
+        Err(running) => running,
+    }
+}
+
+/// Run a scheduled sync to the end
+///
+/// Returns `None` without syncing if another sync is already running.
+pub async fn run_scheduled_sync(plan: SyncPlan) -> Option<Result<SyncResult>> {
+    match begin(SyncTrigger::Scheduled) {
+        Ok(job) => Some(run(job, plan).await),
+        Err(_) => None,
+    }
+}
+
+/// A job by ID, if it's running or the last one to finish
+pub fn get_job(id: JobId) -> Option<Arc<SyncJob>> {
+    let jobs = jobs();
+    [&jobs.running, &jobs.last]
+        .into_iter()
+        .flatten()
+        .find(|job| job.progress().id == id)
+        .cloned()
+}
+
+/// Ask a running job to stop after the show it's searching
+///
+/// Returns whether the job was running.
+pub fn cancel(id: JobId) -> bool {
+    match get_job(id) {
+        Some(job) if job.progress().is_running() => {
+            job.cancelled.store(true, Ordering::Relaxed);
+            true
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_progress() {
+        let job = SyncJob::new(1, SyncTrigger::Manual);
+        job.set_total(2);
+        job.start_show("Frieren");
+        assert_eq!(job.progress().current.as_deref(), Some("Frieren"));
+
+        job.finish_show(1);
+        let progress = job.progress();
+        assert_eq!((progress.done, progress.episodes_downloaded), (1, 1));
+        assert!(progress.current.is_none());
+
+        job.cancelled.store(true, Ordering::Relaxed);
+        job.finish(&Ok(SyncResult::default()));
+        let progress = job.progress();
+        assert_eq!(progress.state, JobState::Cancelled);
+        assert!(progress.finished_at.is_some());
+        assert!(!progress.is_running());
+    }
+
+    #[test]
+    fn test_failed_job() {
+        let job = SyncJob::new(2, SyncTrigger::Scheduled);
+        job.finish(&Err(anyhow::anyhow!("Transmission is down")));
+
+        let progress = job.progress();
+        assert_eq!(progress.state, JobState::Failed);
+        assert_eq!(progress.error.as_deref(), Some("Transmission is down"));
+    }
+}
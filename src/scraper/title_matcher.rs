@@ -0,0 +1,175 @@
+//! Fuzzy title matching
+//!
+//! AniList titles and release titles rarely agree character for character: en-dashes
+//! vs hyphens, full-width characters, "Re:Zero" vs "Re Zero", reordered words. Titles
+//! are normalized first and then compared with a blend of token-set ratio, token-sort
+//! ratio and Jaro-Winkler, giving a score between 0.0 and 1.0. Titles naming
+//! different seasons are marked down, since they're different AniList entries.
+
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+use super::season_parser::detect_season;
+
+/// Factor applied to the score of titles naming different seasons, enough to put
+/// "Title" vs "Title S2" under the default auto-match threshold
+const SEASON_MISMATCH_PENALTY: f64 = 0.8;
+
+/// Normalizes a title for comparison
+///
+/// Applies compatibility decomposition (full-width to ASCII, accents split off),
+/// drops combining marks, lowercases, and turns punctuation into spaces. The
+/// multiplication sign becomes a spaced "x", as in "Hunter×Hunter" or "SPY×FAMILY".
+///
+/// # Examples
+/// - "Sousou no Frieren – S2" -> "sousou no frieren s2"
+/// - "Re:ZERO" -> "re zero"
+/// - "Pokémon" -> "pokemon"
+pub fn normalize_title(title: &str) -> String {
+    let mut folded = String::with_capacity(title.len());
+    for c in title.nfkd().filter(|c| !is_combining_mark(*c)).flat_map(char::to_lowercase) {
+        match c {
+            '×' => folded.push_str(" x "),
+            c if c.is_alphanumeric() => folded.push(c),
+            _ => folded.push(' '),
+        }
+    }
+
+    folded.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Normalized Levenshtein similarity of two strings (1.0 = identical)
+fn ratio(a: &str, b: &str) -> f64 {
+    strsim::normalized_levenshtein(a, b)
+}
+
+/// Sorted, deduplicated tokens of a normalized title
+fn tokens(title: &str) -> Vec<&str> {
+    let mut tokens: Vec<&str> = title.split_whitespace().collect();
+    tokens.sort_unstable();
+    tokens.dedup();
+    tokens
+}
+
+/// Similarity of the two titles with their words sorted, so word order doesn't matter
+pub fn token_sort_ratio(a: &str, b: &str) -> f64 {
+    let (a, b) = (normalize_title(a), normalize_title(b));
+    ratio(&tokens(&a).join(" "), &tokens(&b).join(" "))
+}
+
+/// Token-set ratio: compares the shared words against each title's full word set
+///
+/// A title whose words are all contained in the other scores 1.0, so this is
+/// generous on its own and is blended with stricter measures in [`similarity`].
+pub fn token_set_ratio(a: &str, b: &str) -> f64 {
+    let (a, b) = (normalize_title(a), normalize_title(b));
+    let (tokens_a, tokens_b) = (tokens(&a), tokens(&b));
+
+    let common: Vec<&str> = tokens_a
+        .iter()
+        .filter(|t| tokens_b.contains(t))
+        .copied()
+        .collect();
+    let only_a: Vec<&str> = tokens_a.iter().filter(|t| !common.contains(t)).copied().collect();
+    let only_b: Vec<&str> = tokens_b.iter().filter(|t| !common.contains(t)).copied().collect();
+
+    let base = common.join(" ");
+    let combined_a = [base.as_str(), &only_a.join(" ")].join(" ").trim().to_string();
+    let combined_b = [base.as_str(), &only_b.join(" ")].join(" ").trim().to_string();
+
+    let mut best = ratio(&combined_a, &combined_b);
+    if !base.is_empty() {
+        best = best.max(ratio(&base, &combined_a)).max(ratio(&base, &combined_b));
+    }
+    best
+}
+
+/// Jaro-Winkler similarity of the normalized titles
+pub fn jaro_winkler(a: &str, b: &str) -> f64 {
+    strsim::jaro_winkler(&normalize_title(a), &normalize_title(b))
+}
+
+/// Overall similarity score between two titles, from 0.0 to 1.0
+///
+/// Titles that are equal after normalization always score 1.0.
+pub fn similarity(a: &str, b: &str) -> f64 {
+    if normalize_title(a) == normalize_title(b) {
+        return 1.0;
+    }
+
+    let score = (token_set_ratio(a, b) + token_sort_ratio(a, b) + jaro_winkler(a, b)) / 3.0;
+    if detect_season(a).season != detect_season(b).season {
+        score * SEASON_MISMATCH_PENALTY
+    } else {
+        score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_title() {
+        assert_eq!(normalize_title("Sousou no Frieren – S2"), "sousou no frieren s2");
+        assert_eq!(normalize_title("Re:ZERO"), "re zero");
+        assert_eq!(normalize_title("Pokémon"), "pokemon");
+        assert_eq!(normalize_title("ＳＰＹ×ＦＡＭＩＬＹ"), "spy x family");
+        assert_eq!(normalize_title("Hunter×Hunter"), "hunter x hunter");
+        assert_eq!(normalize_title("  Oshi no Ko  "), "oshi no ko");
+    }
+
+    #[test]
+    fn test_dash_variants_match_exactly() {
+        assert_eq!(similarity("Kaiju No. 8 – Part 2", "Kaiju No. 8 - Part 2"), 1.0);
+    }
+
+    #[test]
+    fn test_token_set_ratio_subset() {
+        assert_eq!(token_set_ratio("One Piece", "One Piece Fan Letter"), 1.0);
+        assert!(token_set_ratio("One Piece", "Bleach") < 0.5);
+    }
+
+    #[test]
+    fn test_token_sort_ignores_order() {
+        assert_eq!(token_sort_ratio("Frieren Sousou no", "Sousou no Frieren"), 1.0);
+    }
+
+    #[test]
+    fn test_similarity_ranks_closest_title_first() {
+        let query = "Sousou no Frieren";
+        let exact = similarity(query, "Sousou no Frieren");
+        let season = similarity(query, "Sousou no Frieren S2");
+        let typo = similarity(query, "Sousou no Furieren");
+        let other = similarity(query, "Dungeon Meshi");
+
+        assert_eq!(exact, 1.0);
+        assert!(typo > 0.9, "typo scored {}", typo);
+        assert!(season < typo, "season variant scored {}", season);
+        assert!(other < 0.5, "unrelated title scored {}", other);
+    }
+
+    #[test]
+    fn test_similarity_penalizes_other_seasons() {
+        // Below the default match confidence threshold, so S2 isn't auto-accepted for S1
+        let threshold = crate::db::models::RssConfig::default().match_confidence_threshold;
+        for (a, b) in [
+            ("Sousou no Frieren", "Sousou no Frieren S2"),
+            ("Sousou no Frieren", "Sousou no Frieren 2nd Season"),
+            ("Dr. Stone Season 3", "Dr. Stone Season 4"),
+        ] {
+            let score = similarity(a, b);
+            assert!(score < threshold, "{} vs {} scored {}", a, b, score);
+        }
+
+        // Same season written differently still matches well
+        let score = similarity("Sousou no Frieren S2", "Sousou no Frieren Season 2");
+        assert!(score > 0.7, "same season scored {}", score);
+    }
+
+    #[test]
+    fn test_similarity_penalizes_supersets() {
+        // Spin-offs share every word of the main title but shouldn't auto-match it
+        let score = similarity("One Piece", "One Piece Fan Letter");
+        assert!(score < 0.9, "spin-off scored {}", score);
+    }
+}
@@ -4,75 +4,204 @@
 //! downloading of new episodes for tracked shows.
 
 use anyhow::Result;
-use chrono::{Duration, Local, TimeZone};
-use std::time::SystemTime;
-use tokio::time::sleep;
+use chrono::{DateTime, Duration, Local, NaiveTime};
+use std::sync::OnceLock;
+use tokio::{sync::Notify, time::sleep};
 
-use crate::db::{self, models::Show};
+use crate::db::{self, models::{RssConfig, Show}, AliasOrigin, FilterAction, FilterType, ShowFilterOverride};
 
 /// Result of a sync operation with detailed feedback
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, serde::Serialize)]
 pub struct SyncResult {
     pub shows_processed: u32,
     pub episodes_downloaded: u32,
     pub shows_with_no_results: Vec<String>,
+    /// Shows that gained a fallback search title which found releases,
+    /// as (show title, learned title)
+    pub learned_titles: Vec<(String, String)>,
     pub errors: Vec<String>,
 }
 
+use super::anilist::{get_anilist_titles, AniShow};
+use super::anilist_progress;
+use super::archive::save_torrent;
+use super::bencode::{fetch_torrent, has_info_hash, parse_torrent};
+use super::fansub::{canonical_group, feed_name, same_group};
 use super::filter_engine::FilterEngine;
-use super::rss::{construct_magnet_url, detect_fansub_source, fetch_rss_by_source, parse_episode_info_full, RssSource};
-use super::transmission::{get_existing_torrent_hashes, upload_to_transmission_rpc};
-use std::collections::HashSet;
+use super::nyaasi::{fetch_torrent_details, is_nyaa_view_url};
+use super::schedule;
+use super::sync_jobs::{self, SyncJob, SyncPlan};
+use super::rss::{construct_magnet_url, detect_fansub_source, fetch_rss_by_source, format_size, normalize_title_for_search, parse_episode_info_full, NyaaSearch, RssItem, RssSource};
+use super::title_matcher::normalize_title;
+use super::transmission::{get_existing_torrent_hashes, upload_to_transmission_rpc, upload_torrent_file};
+use std::collections::{HashMap, HashSet};
+
+/// How the tracker schedules its polls
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PollMode {
+    /// All shows are searched on the configured cron schedules
+    Cron,
+    /// Each show is searched around its airing times
+    Airing,
+    /// All shows are searched every `24 / poll_times_per_day` hours
+    Interval,
+    /// All shows are searched at 5AM and 5PM
+    Fallback,
+}
 
-/// Calculate the next run time based on RSS config
-///
-/// If RSS is enabled, calculates based on poll_times_per_day.
-/// Falls back to 5AM/5PM schedule if RSS is disabled.
-async fn calculate_next_run_time() -> Result<(SystemTime, bool)> {
-    let config = db::with_db(|conn| db::config::get_rss_config(conn)).await?;
+impl PollMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PollMode::Cron => "cron schedule",
+            PollMode::Airing => "airing schedule",
+            PollMode::Interval => "RSS interval",
+            PollMode::Fallback => "fallback (5AM/5PM)",
+        }
+    }
+}
 
-    if config.enabled && config.poll_times_per_day > 0 {
-        // Calculate interval based on polls per day
-        let hours_between_polls = 24.0 / config.poll_times_per_day as f64;
-        let duration_hours = hours_between_polls as i64;
-        let duration_minutes = ((hours_between_polls - duration_hours as f64) * 60.0) as i64;
+/// Next run the tracker is waiting for
+static NEXT_RUN: std::sync::Mutex<Option<DateTime<Local>>> = std::sync::Mutex::new(None);
 
-        let now = Local::now();
-        let next_time = now + Duration::hours(duration_hours) + Duration::minutes(duration_minutes);
+/// Wakes the tracker to work out its next run again after the schedule changed
+static RESCHEDULE: OnceLock<Notify> = OnceLock::new();
+
+fn reschedule_notify() -> &'static Notify {
+    RESCHEDULE.get_or_init(Notify::new)
+}
+
+/// Have the tracker pick up a changed schedule now instead of after its current wait
+pub fn reschedule() {
+    reschedule_notify().notify_one();
+}
+
+/// Enabled cron expressions of the sync schedules
+fn cron_expressions(schedules: &[db::SyncSchedule]) -> Vec<String> {
+    schedules
+        .iter()
+        .filter(|s| s.enabled)
+        .map(|s| s.expression.clone())
+        .collect()
+}
 
-        Ok((next_time.into(), true))
+/// How the tracker polls with the given config and cron expressions
+///
+/// Cron schedules win over airing-aware polling, which wins over the fixed interval.
+fn poll_mode(config: &RssConfig, cron: &[String]) -> PollMode {
+    if !config.enabled {
+        PollMode::Fallback
+    } else if !cron.is_empty() {
+        PollMode::Cron
+    } else if config.airing_aware_polling {
+        PollMode::Airing
+    } else if config.poll_times_per_day > 0 {
+        PollMode::Interval
     } else {
-        // Fallback to 5AM/5PM schedule
-        Ok((next_run_time_fallback(), false))
+        PollMode::Fallback
     }
 }
 
-/// Fallback schedule: next 5AM or 5PM
-fn next_run_time_fallback() -> SystemTime {
+/// Time between polls in interval mode
+fn poll_interval(config: &RssConfig) -> Duration {
+    Duration::minutes(24 * 60 / config.poll_times_per_day.max(1) as i64)
+}
+
+/// Calculate the next run time based on RSS config
+///
+/// Runs on the cron schedules if there are any. Otherwise, with airing-aware
+/// polling, wakes when the next show is due, refreshing airing times from AniList
+/// first, or, if RSS is enabled, calculates based on poll_times_per_day. Falls back
+/// to 5AM/5PM schedule if RSS is disabled. Runs in quiet hours wait for their end.
+async fn calculate_next_run_time() -> Result<(DateTime<Local>, PollMode)> {
+    let (config, sync_schedules) =
+        db::with_db(|conn| Ok((db::get_rss_config(conn)?, db::get_sync_schedules(conn)?))).await?;
+    let quiet = schedule::quiet_hours(&config);
+    let cron = cron_expressions(&sync_schedules);
+    let mode = poll_mode(&config, &cron);
+
+    let next_time = match mode {
+        PollMode::Cron => schedule::upcoming_runs(&cron, quiet, Local::now(), 1)
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("No valid sync schedule"))?,
+        PollMode::Airing => {
+            let shows = db::with_db(db::shows::get_tracked_shows).await?;
+            let schedules = db::with_db(db::get_poll_schedules).await?;
+            if let Err(e) = schedule::refresh_airing_times(&shows, &schedules).await {
+                tracing::warn!("Could not refresh airing times: {:?}", e);
+            }
+            let (schedules, stats) = db::with_db(|conn| {
+                Ok((db::get_poll_schedules(conn)?, db::get_release_delay_stats(conn)?))
+            })
+            .await?;
+            let delays = schedule::expected_delays(&shows, stats);
+
+            let now = chrono::Utc::now().timestamp();
+            let wake = schedule::next_wake(&shows, &schedules, &delays, now, &config);
+            DateTime::from_timestamp(wake, 0)
+                .map(|wake| wake.with_timezone(&Local))
+                .ok_or_else(|| anyhow::anyhow!("Invalid wake time {}", wake))?
+        }
+        PollMode::Interval => Local::now() + poll_interval(&config),
+        PollMode::Fallback => next_run_time_fallback(quiet),
+    };
+
+    Ok((schedule::after_quiet_hours(quiet, next_time), mode))
+}
+
+/// Fallback schedule: next 5AM or 5PM outside quiet hours
+fn next_run_time_fallback(quiet: Option<(NaiveTime, NaiveTime)>) -> DateTime<Local> {
+    schedule::upcoming_runs(&[schedule::FALLBACK_SCHEDULE.to_string()], quiet, Local::now(), 1)
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| Local::now() + Duration::hours(12))
+}
+
+/// The tracker's upcoming runs
+pub struct UpcomingSyncs {
+    pub mode: PollMode,
+    /// Next runs, soonest first; in airing mode only the next wake is known
+    pub runs: Vec<DateTime<Local>>,
+}
+
+/// The next `count` runs of the tracker with the current config
+pub async fn upcoming_syncs(count: usize) -> Result<UpcomingSyncs> {
+    let (config, sync_schedules) =
+        db::with_db(|conn| Ok((db::get_rss_config(conn)?, db::get_sync_schedules(conn)?))).await?;
+    let quiet = schedule::quiet_hours(&config);
+    let cron = cron_expressions(&sync_schedules);
+    let mode = poll_mode(&config, &cron);
+    let next_run = *NEXT_RUN.lock().unwrap_or_else(|e| e.into_inner());
     let now = Local::now();
-    let today = now.date_naive();
-    let next_5_am = today.and_hms_opt(5, 0, 0).expect("Invalid time");
-    let next_5_pm = today.and_hms_opt(17, 0, 0).expect("Invalid time"); // 17:00 is 5:00 PM
-
-    // Determine which one is the next target
-    let target_time = if now.naive_local() < next_5_am {
-        next_5_am
-    } else if now.naive_local() < next_5_pm {
-        next_5_pm
-    } else {
-        // If the current time is after 5:00 PM, the next target is 5:00 AM of the next day
-        next_5_am + Duration::days(1)
+
+    let runs = match mode {
+        PollMode::Cron => schedule::upcoming_runs(&cron, quiet, now, count),
+        PollMode::Fallback => {
+            schedule::upcoming_runs(&[schedule::FALLBACK_SCHEDULE.to_string()], quiet, now, count)
+        }
+        PollMode::Interval => {
+            let first = next_run.unwrap_or_else(|| now + poll_interval(&config));
+            let mut runs = vec![first];
+            while runs.len() < count {
+                let last = runs[runs.len() - 1];
+                runs.push(schedule::after_quiet_hours(quiet, last + poll_interval(&config)));
+            }
+            runs
+        }
+        PollMode::Airing => next_run.into_iter().collect(),
     };
 
-    let local_datetime = Local
-        .from_local_datetime(&target_time)
-        .single()
-        .expect("Failed to convert naive datetime to local datetime");
-    local_datetime.into()
+    Ok(UpcomingSyncs { mode, runs })
 }
 
 /// Process a single show: fetch RSS, apply filters, and download new episodes
-async fn process_show(show: &Show, existing_hashes: &HashSet<String>) -> Result<u32> {
+///
+/// Learned alternates are recorded in `sync_result`.
+async fn process_show(
+    show: &Show,
+    existing_hashes: &HashSet<String>,
+    sync_result: &mut SyncResult,
+) -> Result<u32> {
     let mut downloaded_count = 0u32;
     // Track episodes we've already downloaded this sync to avoid duplicates from different sources
     let mut downloaded_episodes: HashSet<u16> = HashSet::new();
@@ -81,16 +210,36 @@ async fn process_show(show: &Show, existing_hashes: &HashSet<String>) -> Result<
     let rss_source = RssSource::from_source_string(&show.source);
 
     tracing::info!(
-        "Processing: {} (source: {:?}, quality: {}, season: {})",
-        show.alternate, rss_source, show.quality, show.season
+        activity = "feed",
+        show = %show.title,
+        "Fetching {:?} feed for {} (quality: {}, season: {})",
+        rss_source, show.alternate, show.quality, show.season
     );
 
+    // Search under the alternate title and every alias of the show
+    let show_for_titles = show.clone();
+    let search_titles =
+        db::with_db(move |conn| db::get_search_titles(conn, &show_for_titles)).await?;
+    let search_titles: Vec<&str> = search_titles.iter().map(String::as_str).collect();
+    if search_titles.len() > 1 {
+        tracing::debug!("Searching '{}' under {:?}", show.title, search_titles);
+    }
+
+    // How to search Nyaa, with category and language falling back to the global defaults
+    let config = db::with_db(db::get_rss_config).await?;
+    let search = NyaaSearch {
+        category: show.effective_category(&config),
+        query_template: show.query_template.clone(),
+        user_feed: show.nyaa_user_feed,
+    };
+
     // Fetch RSS feed using appropriate source
     let rss_items = match fetch_rss_by_source(
         rss_source,
-        &show.source,
-        &show.alternate,
+        &feed_name(&show.source),
+        &search_titles,
         &show.quality,
+        &search,
     )
     .await
     {
@@ -106,13 +255,20 @@ async fn process_show(show: &Show, existing_hashes: &HashSet<String>) -> Result<
 
     tracing::debug!("Got {} RSS items for '{}'", rss_items.len(), show.alternate);
 
-    if rss_items.is_empty() {
-        return Ok(0);
-    }
+    // Nothing under the configured titles: try other forms of the title, which is
+    // only learned once one of its releases gets through the filters below
+    let (rss_items, mut learned) = if rss_items.is_empty() {
+        match search_fallback_titles(show, rss_source, &search, &search_titles).await {
+            Some((learned, items)) => (items, Some(learned)),
+            None => return Ok(0),
+        }
+    } else {
+        (rss_items, None)
+    };
 
     // Filter by quality first (e.g., "1080p" should only match "1080p" items)
     let quality_filter = &show.quality;
-    let rss_items: Vec<_> = rss_items
+    let mut rss_items: Vec<_> = rss_items
         .into_iter()
         .filter(|item| item.title.contains(quality_filter))
         .collect();
@@ -132,26 +288,42 @@ async fn process_show(show: &Show, existing_hashes: &HashSet<String>) -> Result<
 
     // Load show-specific filter overrides
     let show_id_for_filters = show.id;
-    let show_filters =
+    let mut show_filters =
         db::with_db(move |conn| db::get_show_filters(conn, show_id_for_filters)).await?;
 
+    // A preferred subtitle language ranks releases tagged with it first
+    if let Some(language) = show.effective_language(&config) {
+        show_filters.push(ShowFilterOverride {
+            id: 0,
+            show_id: show.id,
+            filter_rule_id: None,
+            filter_type: Some(FilterType::Language),
+            pattern: Some(language),
+            action: FilterAction::Prefer,
+            enabled: true,
+        });
+    }
+
     // Create filter engine and apply filters
     let engine = FilterEngine::new(global_filters, show_filters);
+    let mut fetched_torrents = if engine.needs_contents() {
+        fetch_item_contents(&mut rss_items).await
+    } else {
+        HashMap::new()
+    };
+    let candidates = rss_items.len();
     let filtered_results = engine.apply(rss_items);
 
     if filtered_results.is_empty() {
-        tracing::debug!(
-            "No items passed filters for '{}' (quality: {})",
-            show.alternate,
-            show.quality
-        );
+        tracing::debug!("Filtered {} → 0 release(s) for '{}'", candidates, show.title);
         return Ok(0);
     }
-
-    tracing::debug!(
-        "{} items passed filters for '{}'",
-        filtered_results.len(),
-        show.alternate
+    tracing::info!(
+        activity = "filter",
+        show = %show.title,
+        "Filtered {} → {} release(s)",
+        candidates,
+        filtered_results.len()
     );
 
     // Clone show data for use in closures (needed for 'static lifetime)
@@ -164,9 +336,14 @@ async fn process_show(show: &Show, existing_hashes: &HashSet<String>) -> Result<
     };
     let show_season = show.season;
 
+    // Announce URLs for magnets built from this show's releases
+    let show_source = canonical_group(&show.source);
+    let magnet_trackers =
+        db::with_db(move |conn| db::get_trackers_for_source(conn, &show_source)).await?;
+
     // Process items in score order (highest first)
     for result in &filtered_results {
-        let item = &result.item;
+        let mut item = result.item.clone();
 
         // Log matched rules for debugging
         if !result.matched_rules.is_empty() {
@@ -191,7 +368,7 @@ async fn process_show(show: &Show, existing_hashes: &HashSet<String>) -> Result<
         // Filter by source - only download from the configured fansub group
         // This prevents downloading duplicates from different groups (e.g., SubsPlease vs Erai-raws)
         let item_source = detect_fansub_source(&item.title);
-        if item_source.to_lowercase() != show.source.to_lowercase() {
+        if !same_group(&item_source, &show.source) {
             tracing::debug!(
                 "Skipping (source mismatch: {} != {}): '{}'",
                 item_source,
@@ -223,6 +400,12 @@ async fn process_show(show: &Show, existing_hashes: &HashSet<String>) -> Result<
             }
         }
 
+        // A release of the right group and season: the fallback title finds this show
+        if let Some(learned) = learned.take() {
+            learn_search_title(show, &learned).await;
+            sync_result.learned_titles.push((show.title.clone(), learned));
+        }
+
         // Skip if we've already downloaded this episode number in this sync
         // This prevents downloading the same episode from multiple sources (e.g., SubsPlease + Erai-raws)
         // or different encodings (HEVC vs AVC)
@@ -242,7 +425,7 @@ async fn process_show(show: &Show, existing_hashes: &HashSet<String>) -> Result<
                 if let Some(other_info) = parse_episode_info_full(&other.item.title) {
                     other_info.episode == episode
                         && other.item.title.contains("HEVC")
-                        && detect_fansub_source(&other.item.title).to_lowercase() == show.source.to_lowercase()
+                        && same_group(&detect_fansub_source(&other.item.title), &show.source)
                 } else {
                     false
                 }
@@ -257,6 +440,14 @@ async fn process_show(show: &Show, existing_hashes: &HashSet<String>) -> Result<
             continue;
         }
 
+        // SubsPlease direct items carry no hash; compute it from the .torrent so the
+        // Transmission check and history use the same key as Nyaa items
+        let hash_from_feed = !item.info_hash.is_empty();
+        let mut metainfo = fetched_torrents.remove(&item.torrent_link);
+        if metainfo.is_none() && !hash_from_feed {
+            metainfo = inspect_torrent(&mut item).await;
+        }
+
         // Check if this torrent is already in Transmission (by hash)
         // This is more reliable than database tracking since files can be deleted from Transmission
         if !item.info_hash.is_empty() {
@@ -276,24 +467,60 @@ async fn process_show(show: &Show, existing_hashes: &HashSet<String>) -> Result<
             item.info_hash.clone()
         };
 
-        // Determine download URL: prefer magnet, fallback to torrent file
-        let download_url = if !item.info_hash.is_empty() {
-            construct_magnet_url(&item.info_hash, &item.title)
-        } else {
-            // For SubsPlease direct RSS, use the torrent URL directly
-            item.torrent_link.clone()
-        };
+        // Grab the .torrent so it can be archived and uploaded directly
+        if metainfo.is_none() && item.torrent_link.starts_with("http") {
+            metainfo = match fetch_torrent(&item.torrent_link).await {
+                Ok(data) => Some(data),
+                Err(e) => {
+                    tracing::warn!("Could not fetch torrent for '{}': {:?}", item.title, e);
+                    None
+                }
+            };
+        }
 
-        // Upload to Transmission
-        match upload_to_transmission_rpc(
-            vec![download_url.clone()],
-            &show_alternate,
-            Some(show_season),
-        )
-        .await
+        // A .torrent that isn't the release the hash names is neither archived nor uploaded
+        if let Some(data) = &metainfo
+            && !item.info_hash.is_empty()
+            && !has_info_hash(data, &item.info_hash)
         {
+            tracing::warn!(
+                "Torrent for '{}' doesn't match its hash {}, not using it",
+                item.title,
+                item.info_hash
+            );
+            metainfo = None;
+        }
+
+        // Keep a copy of the .torrent in case the release disappears from Nyaa
+        let torrent_file = match &metainfo {
+            Some(data) if !item.info_hash.is_empty() => match save_torrent(&item.info_hash, data) {
+                Ok(path) => Some(path.to_string_lossy().into_owned()),
+                Err(e) => {
+                    tracing::warn!("Could not archive torrent for '{}': {:?}", item.title, e);
+                    None
+                }
+            },
+            _ => None,
+        };
+
+        // Upload to Transmission: prefer the .torrent itself, since it carries trackers
+        let upload_result = match &metainfo {
+            Some(data) => upload_torrent_file(data, &show_alternate, Some(show_season)).await,
+            None => {
+                // Fall back to a magnet when the feed gave us a hash, otherwise the torrent URL
+                let download_url = if hash_from_feed {
+                    construct_magnet_url(&item.info_hash, &item.title, &magnet_trackers)
+                } else {
+                    item.torrent_link.clone()
+                };
+                upload_to_transmission_rpc(vec![download_url], &show_alternate, Some(show_season))
+                    .await
+            }
+        };
+
+        match upload_result {
             Ok(_) => {
-                tracing::info!("Downloaded: {}", item.title);
+                tracing::info!(activity = "grab", show = %show.title, "Downloaded: {}", item.title);
 
                 // Clone values for the closure
                 let record_hash = check_hash.clone();
@@ -307,6 +534,7 @@ async fn process_show(show: &Show, existing_hashes: &HashSet<String>) -> Result<
                         episode,
                         &record_hash,
                         &torrent_link,
+                        torrent_file.as_deref(),
                     )
                 })
                 .await
@@ -324,6 +552,10 @@ async fn process_show(show: &Show, existing_hashes: &HashSet<String>) -> Result<
                     tracing::error!("Failed to update last downloaded episode: {:?}", e);
                 }
 
+                anilist_progress::push_download_progress(show_id, episode).await;
+                // Credit the group named in the release title
+                record_release_delay(show_id, item_source, episode, &item.pub_date).await;
+
                 // Mark this episode as downloaded to prevent duplicates from other sources
                 downloaded_episodes.insert(episode);
                 downloaded_count += 1;
@@ -337,18 +569,289 @@ async fn process_show(show: &Show, existing_hashes: &HashSet<String>) -> Result<
     Ok(downloaded_count)
 }
 
-/// Download shows for all tracked entries using RSS feeds
-pub async fn download_shows() -> Result<SyncResult> {
-    let mut result = SyncResult::default();
+/// Longest believable release delay; longer ones are a mismatched episode or a re-release
+const MAX_RELEASE_DELAY_SECS: i64 = 7 * 24 * 60 * 60;
+
+/// Record how long after air a group released an episode, if its airing time is known
+///
+/// The release time is the item's publish date, or now if the feed has none.
+async fn record_release_delay(show_id: u32, group: String, episode: u16, pub_date: &str) {
+    let released_at = chrono::DateTime::parse_from_rfc2822(pub_date)
+        .map(|date| date.timestamp())
+        .unwrap_or_else(|_| chrono::Utc::now().timestamp());
+
+    let result = db::with_db(move |conn| {
+        let Some(schedule) = db::get_poll_schedule(conn, show_id)? else {
+            return Ok(());
+        };
+        let aired_at = match (schedule.last_aired_episode, schedule.last_aired_at) {
+            (Some(aired), Some(at)) if aired == episode => at,
+            _ => return Ok(()),
+        };
+        if !(0..=MAX_RELEASE_DELAY_SECS).contains(&(released_at - aired_at)) {
+            return Ok(());
+        }
+        db::record_release_delay(conn, show_id, &group, episode, aired_at, released_at)
+    })
+    .await;
+    if let Err(e) = result {
+        tracing::warn!("Failed to record release delay: {:?}", e);
+    }
+}
+
+/// Other forms of a show's title to search when its configured titles find nothing
+///
+/// In order: the punctuation-normalized alternate, AniList's english and romaji
+/// titles and synonyms, then each of those with season suffixes stripped. Titles
+/// already searched and non-romanized titles are left out.
+fn fallback_search_titles(show: &Show, anilist: Option<&AniShow>, tried: &[&str]) -> Vec<String> {
+    let primary = if show.alternate.is_empty() {
+        &show.title
+    } else {
+        &show.alternate
+    };
+
+    let mut forms = vec![normalize_title(primary)];
+    if let Some(media) = anilist {
+        let title = media.title.as_ref();
+        forms.extend(title.and_then(|t| t.english.clone()));
+        forms.extend(title.and_then(|t| t.romaji.clone()));
+        forms.extend(media.synonyms.clone().unwrap_or_default());
+    }
+    let stripped: Vec<String> = forms
+        .iter()
+        .chain(std::iter::once(&show.title))
+        .map(|form| normalize_title_for_search(form))
+        .collect();
+    forms.extend(stripped);
+
+    let mut titles: Vec<String> = Vec::new();
+    for form in forms {
+        let form = form.trim().to_string();
+        let already_tried = tried.iter().any(|t| t.eq_ignore_ascii_case(&form))
+            || titles.iter().any(|t| t.eq_ignore_ascii_case(&form));
+        if !form.is_empty() && normalize_title(&form).is_ascii() && !already_tried {
+            titles.push(form);
+        }
+    }
+    titles
+}
+
+/// Search each fallback title in turn, returning the first that finds releases
+async fn search_fallback_titles(
+    show: &Show,
+    rss_source: RssSource,
+    search: &NyaaSearch,
+    tried: &[&str],
+) -> Option<(String, Vec<RssItem>)> {
+    // Shows added by hand have no AniList titles to fall back on
+    let anilist = if show.is_manual() {
+        None
+    } else {
+        match get_anilist_titles(show.id).await {
+            Ok(media) => media,
+            Err(e) => {
+                tracing::warn!("Could not fetch AniList titles for '{}': {:?}", show.title, e);
+                None
+            }
+        }
+    };
+
+    for title in fallback_search_titles(show, anilist.as_ref(), tried) {
+        tracing::debug!("No results for '{}', trying '{}'", show.alternate, title);
+        match fetch_rss_by_source(
+            rss_source,
+            &feed_name(&show.source),
+            &[&title],
+            &show.quality,
+            search,
+        )
+        .await
+        {
+            Ok(items) if !items.is_empty() => {
+                tracing::info!(
+                    "Found {} item(s) for '{}' under '{}'",
+                    items.len(),
+                    show.title,
+                    title
+                );
+                return Some((title, items));
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Fallback search for '{}' failed: {:?}", title, e),
+        }
+    }
+
+    None
+}
+
+/// Keep a fallback title that found releases as a learned alias of the show
+///
+/// The alternate is left alone, since it also names the show's download folder.
+async fn learn_search_title(show: &Show, learned: &str) {
+    let show_id = show.id;
+    let learned = learned.to_string();
+    let result = db::with_db(move |conn| {
+        db::add_show_alias(conn, show_id, &learned, AliasOrigin::Learned)
+    })
+    .await;
+
+    if let Err(e) = result {
+        tracing::error!("Failed to save learned search title for '{}': {:?}", show.title, e);
+    }
+}
+
+/// Fill in the file list of each item from its .torrent file or Nyaa torrent page
+///
+/// Only called when content-based filter rules are enabled, since it costs one
+/// request per item. Failures are logged and leave the item's contents unknown.
+/// Returns the .torrent files downloaded along the way, by torrent link, so they
+/// aren't downloaded again to be grabbed.
+async fn fetch_item_contents(items: &mut [RssItem]) -> HashMap<String, Vec<u8>> {
+    let mut torrents = HashMap::new();
+    for item in items.iter_mut() {
+        if item.files.is_some() {
+            continue;
+        }
+
+        if let Some(data) = inspect_torrent(item).await {
+            torrents.insert(item.torrent_link.clone(), data);
+            continue;
+        }
+
+        if !is_nyaa_view_url(&item.view_url) {
+            continue;
+        }
+
+        match fetch_torrent_details(&item.view_url).await {
+            Ok(details) => {
+                tracing::debug!(
+                    "Fetched {} file(s) for '{}'",
+                    details.files.len(),
+                    item.title
+                );
+                if item.info_hash.is_empty()
+                    && let Some(hash) = details.info_hash
+                {
+                    item.info_hash = hash;
+                }
+                item.files = Some(details.files);
+            }
+            Err(e) => {
+                tracing::warn!("Could not fetch contents of '{}': {:?}", item.title, e);
+            }
+        }
+    }
+    torrents
+}
+
+/// Download and parse an item's .torrent file, filling in its hash, size and files
+///
+/// Returns the raw .torrent on success, or None if the item has no downloadable
+/// torrent or it couldn't be parsed.
+async fn inspect_torrent(item: &mut RssItem) -> Option<Vec<u8>> {
+    if !item.torrent_link.starts_with("http") {
+        return None;
+    }
+
+    let parsed = fetch_torrent(&item.torrent_link)
+        .await
+        .and_then(|data| parse_torrent(&data).map(|meta| (data, meta)));
+
+    match parsed {
+        Ok((data, meta)) => {
+            tracing::debug!(
+                "Parsed torrent '{}' for '{}': {} ({} file(s))",
+                meta.name,
+                item.title,
+                meta.info_hash,
+                meta.files.len()
+            );
+            if item.info_hash.is_empty() {
+                item.info_hash = meta.info_hash;
+            }
+            if item.size.is_empty() {
+                item.size = format_size(meta.total_size);
+            }
+            item.files = Some(meta.files);
+            Some(data)
+        }
+        Err(e) => {
+            tracing::warn!("Could not inspect torrent for '{}': {:?}", item.title, e);
+            None
+        }
+    }
+}
+
+/// Run a sync job's plan, reporting progress to the job
+///
+/// Fails without searching during quiet hours, and stops once they start.
+pub(crate) async fn run_sync(plan: SyncPlan, job: &SyncJob) -> Result<SyncResult> {
+    let config = db::with_db(db::get_rss_config).await?;
+    let quiet = schedule::quiet_hours(&config);
+    if let Some(end) = quiet.and_then(|quiet| schedule::quiet_hours_end(quiet, Local::now())) {
+        anyhow::bail!("Quiet hours until {}, no torrents are added", end.format("%H:%M"));
+    }
+
+    match plan {
+        SyncPlan::AllShows => download_shows(job, quiet).await,
+        SyncPlan::DueShows => download_due_shows(job, quiet).await,
+    }
+}
 
+/// Download shows for all tracked entries using RSS feeds
+async fn download_shows(job: &SyncJob, quiet: Option<(NaiveTime, NaiveTime)>) -> Result<SyncResult> {
     // Get all tracked shows from SQLite
     let shows = db::with_db(|conn| db::shows::get_tracked_shows(conn)).await?;
 
     if shows.is_empty() {
         tracing::info!("No tracked shows found in database.");
-        return Ok(result);
+        return Ok(SyncResult::default());
+    }
+
+    poll_shows(&shows, job, quiet).await
+}
+
+/// Download shows for the tracked entries due for a search by the airing schedule
+async fn download_due_shows(
+    job: &SyncJob,
+    quiet: Option<(NaiveTime, NaiveTime)>,
+) -> Result<SyncResult> {
+    let (shows, schedules, stats, config) = db::with_db(|conn| {
+        Ok((
+            db::shows::get_tracked_shows(conn)?,
+            db::get_poll_schedules(conn)?,
+            db::get_release_delay_stats(conn)?,
+            db::get_rss_config(conn)?,
+        ))
+    })
+    .await?;
+    let delays = schedule::expected_delays(&shows, stats);
+
+    let now = chrono::Utc::now().timestamp();
+    let due: Vec<Show> = schedule::due_shows(&shows, &schedules, &delays, now, &config)
+        .into_iter()
+        .cloned()
+        .collect();
+    if due.is_empty() {
+        tracing::debug!("No shows due for a search.");
+        return Ok(SyncResult::default());
     }
 
+    poll_shows(&due, job, quiet).await
+}
+
+/// Search for and download new episodes of the given shows
+///
+/// Stops before the next show once the job is cancelled or quiet hours start.
+async fn poll_shows(
+    shows: &[Show],
+    job: &SyncJob,
+    quiet: Option<(NaiveTime, NaiveTime)>,
+) -> Result<SyncResult> {
+    let mut result = SyncResult::default();
+    job.set_total(shows.len());
+
     // Get existing torrent hashes from Transmission to avoid re-adding
     let existing_hashes = match get_existing_torrent_hashes().await {
         Ok(hashes) => {
@@ -363,7 +866,26 @@ pub async fn download_shows() -> Result<SyncResult> {
 
     tracing::info!("Processing {} tracked show(s)...", shows.len());
 
-    for show in &shows {
+    for show in shows {
+        if job.is_cancelled() {
+            tracing::info!(
+                "Sync cancelled after {} of {} show(s)",
+                result.shows_processed,
+                shows.len()
+            );
+            break;
+        }
+        // No torrents are added once quiet hours start
+        if let Some(end) = quiet.and_then(|quiet| schedule::quiet_hours_end(quiet, Local::now())) {
+            tracing::warn!(
+                "Quiet hours until {}, sync stopped after {} of {} show(s)",
+                end.format("%H:%M"),
+                result.shows_processed,
+                shows.len()
+            );
+            break;
+        }
+        job.start_show(&show.title);
         result.shows_processed += 1;
         tracing::debug!(
             "Checking: {} ({}) [source: {}]",
@@ -372,18 +894,26 @@ pub async fn download_shows() -> Result<SyncResult> {
             show.source
         );
 
-        match process_show(show, &existing_hashes).await {
+        let downloaded = match process_show(show, &existing_hashes, &mut result).await {
             Ok(count) => {
                 if count == 0 {
                     result.shows_with_no_results.push(show.title.clone());
                 }
                 result.episodes_downloaded += count;
+                count
             }
             Err(e) => {
                 let error_msg = format!("{}: {}", show.title, e);
-                tracing::error!("Error processing show '{}': {:?}", show.title, e);
+                tracing::error!(show = %show.title, "Error processing show '{}': {:?}", show.title, e);
                 result.errors.push(error_msg);
+                0
             }
+        };
+        job.finish_show(downloaded);
+
+        let (show_id, polled_at) = (show.id, chrono::Utc::now().timestamp());
+        if let Err(e) = db::with_db(move |conn| db::record_show_poll(conn, show_id, polled_at)).await {
+            tracing::warn!("Failed to record poll of '{}': {:?}", show.title, e);
         }
     }
 
@@ -405,19 +935,18 @@ pub async fn download_shows() -> Result<SyncResult> {
 /// Continuously polls RSS feeds at the configured interval and downloads new episodes.
 pub async fn run_tracker() {
     loop {
-        let now = SystemTime::now();
-
         // Calculate next run time based on config
-        let (next_time, rss_enabled) = match calculate_next_run_time().await {
+        let (next_time, mode) = match calculate_next_run_time().await {
             Ok(result) => result,
             Err(e) => {
                 tracing::error!("Failed to calculate next run time: {:?}", e);
                 // Fallback to fixed schedule on error
-                (next_run_time_fallback(), false)
+                (next_run_time_fallback(None), PollMode::Fallback)
             }
         };
+        *NEXT_RUN.lock().unwrap_or_else(|e| e.into_inner()) = Some(next_time);
 
-        let wait_duration = match next_time.duration_since(now) {
+        let wait_duration = match (next_time - Local::now()).to_std() {
             Ok(duration) => duration,
             Err(_) => {
                 // If next_time is in the past, run immediately
@@ -425,20 +954,26 @@ pub async fn run_tracker() {
             }
         };
 
-        let mode = if rss_enabled {
-            "RSS interval"
-        } else {
-            "fallback (5AM/5PM)"
-        };
-        tracing::info!("Next poll in {:?} ({} mode)", wait_duration, mode);
+        tracing::info!("Next poll in {:?} ({} mode)", wait_duration, mode.label());
 
-        sleep(wait_duration).await;
+        tokio::select! {
+            _ = sleep(wait_duration) => {}
+            _ = reschedule_notify().notified() => {
+                tracing::info!("Schedule changed, working out the next poll again");
+                continue;
+            }
+        }
 
         tracing::info!("Starting download check at {:?}", Local::now());
 
-        match download_shows().await {
-            Ok(_) => tracing::info!("Download check completed successfully."),
-            Err(e) => tracing::error!("Download check failed: {:?}", e),
+        let plan = match mode {
+            PollMode::Airing => SyncPlan::DueShows,
+            PollMode::Cron | PollMode::Interval | PollMode::Fallback => SyncPlan::AllShows,
+        };
+        match sync_jobs::run_scheduled_sync(plan).await {
+            Some(Ok(_)) => tracing::info!("Download check completed successfully."),
+            Some(Err(e)) => tracing::error!("Download check failed: {:?}", e),
+            None => tracing::info!("A sync is already running, skipping this download check."),
         }
     }
 }
@@ -454,7 +989,7 @@ mod test {
         // db::init_connection().expect("Failed to init DB");
         // db::init_database(&conn).expect("Failed to init schema");
 
-        match download_shows().await {
+        match sync_jobs::run_scheduled_sync(SyncPlan::AllShows).await.expect("No other sync running") {
             Ok(result) => {
                 println!("Download check completed successfully: {:?}", result);
             }
@@ -465,12 +1000,51 @@ mod test {
         };
     }
 
+    #[test]
+    fn test_fallback_search_titles() {
+        let show = Show {
+            id: 1,
+            title: "Sousou no Frieren 2nd Season".to_string(),
+            alternate: "Sousou no Frieren: S2".to_string(),
+            ..Default::default()
+        };
+        let media: AniShow = serde_json::from_str(
+            r#"{
+                "title": {"romaji": "Sousou no Frieren 2nd Season", "english": "Frieren: Beyond Journey's End Season 2"},
+                "synonyms": ["葬送のフリーレン 第2期"]
+            }"#,
+        )
+        .unwrap();
+
+        let titles = fallback_search_titles(&show, Some(&media), &["Sousou no Frieren: S2"]);
+        assert_eq!(
+            titles,
+            vec![
+                "sousou no frieren s2",
+                "Frieren: Beyond Journey's End Season 2",
+                "Sousou no Frieren 2nd Season",
+                "sousou no frieren",
+                "Frieren: Beyond Journey's End",
+            ]
+        );
+
+        // Without AniList, only forms of the show's own titles are tried
+        let titles = fallback_search_titles(&show, None, &[]);
+        assert_eq!(titles, vec!["sousou no frieren s2", "sousou no frieren"]);
+    }
+
     #[test]
     fn test_fallback_schedule() {
-        let next = next_run_time_fallback();
-        let now = SystemTime::now();
+        let next = next_run_time_fallback(None);
+        let now = Local::now();
 
         // Next run time should be in the future
         assert!(next > now);
+        assert!(matches!(next.format("%H:%M").to_string().as_str(), "05:00" | "17:00"));
+
+        // Runs in quiet hours move to their end
+        let quiet = NaiveTime::from_hms_opt(4, 0, 0).zip(NaiveTime::from_hms_opt(18, 0, 0));
+        let next = next_run_time_fallback(quiet);
+        assert_eq!(next.format("%H:%M").to_string(), "18:00");
     }
 }
@@ -155,10 +155,7 @@ pub async fn upload_to_transmission_rpc(
     println!("Received session_id from transmission");
 
 
-    let destination_folder = match season_number {
-        Some(season) => format!("/data/Anime/{}/Season {}/", show_name, season),
-        None => format!("/data/Anime/{}/", show_name),
-    };
+    let destination_folder = destination_folder(show_name, season_number);
 
     let count = links.len();
     for magnet_link in links {
@@ -191,3 +188,51 @@ pub async fn upload_to_transmission_rpc(
 
     Ok(())
 }
+
+/// Upload a .torrent file's contents to Transmission
+///
+/// Unlike a magnet link, the metainfo already lists trackers and pieces, so the
+/// download can start without waiting on DHT for metadata.
+pub async fn upload_torrent_file(
+    metainfo: &[u8],
+    show_name: &str,
+    season_number: Option<u8>,
+) -> Result<()> {
+    use base64::Engine;
+
+    let (client, url, session_id) = get_session_id().await?;
+
+    let body = serde_json::json!({
+        "method": "torrent-add",
+        "arguments": {
+            "metainfo": base64::engine::general_purpose::STANDARD.encode(metainfo),
+            "download-dir": destination_folder(show_name, season_number),
+        }
+    });
+
+    let resp = client
+        .post(&url)
+        .header("X-Transmission-Session-Id", &session_id)
+        .json(&body)
+        .send()
+        .await?;
+
+    let text = resp.text().await?;
+    let response: TransmissionResponse = serde_json::from_str(&text)
+        .map_err(|e| anyhow!("Failed to parse torrent-add response: {} - Response: {}", e, text))?;
+
+    if response.result == "success" {
+        tracing::info!("Sent torrent file to transmission for {}", show_name);
+        Ok(())
+    } else {
+        Err(anyhow!("Failed to add torrent: {}", response.result))
+    }
+}
+
+/// Download directory for a show, split by season when known
+fn destination_folder(show_name: &str, season_number: Option<u8>) -> String {
+    match season_number {
+        Some(season) => format!("/data/Anime/{}/Season {}/", show_name, season),
+        None => format!("/data/Anime/{}/", show_name),
+    }
+}